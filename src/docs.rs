@@ -1,14 +1,23 @@
-macro_rules! docs {
-    ($($lint_name: expr,)*) => {
-        pub fn explain(lint: &str) {
-            println!("{}", match lint {
-                $(
-                    $lint_name => include_lint!(concat!("docs/", concat!($lint_name, ".txt"))),
-                )*
-                _ => "unknown linttt",
-            })
-        }
+use substrace_lints::docs::LINTS;
+
+pub fn explain(lint: &str) {
+    match LINTS.iter().find(|candidate| candidate.name == lint) {
+        Some(lint) => print!("{}", lint.docs),
+        None => println!("unknown lint: `{lint}`, see `cargo substrace --list` for the available lints"),
     }
 }
 
-docs! {}
+pub fn list() {
+    let width = LINTS.iter().map(|lint| lint.name.len()).max().unwrap_or(0);
+    let mut groups: Vec<&str> = LINTS.iter().map(|lint| lint.group).collect();
+    groups.sort_unstable();
+    groups.dedup();
+
+    for group in groups {
+        println!("substrace::{group}");
+        for lint in LINTS.iter().filter(|lint| lint.group == group) {
+            println!("    substrace::{:width$}  {}", lint.name, lint.description);
+        }
+        println!();
+    }
+}
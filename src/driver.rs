@@ -11,16 +11,21 @@
 extern crate rustc_driver;
 extern crate rustc_errors;
 extern crate rustc_interface;
+extern crate rustc_parse;
 extern crate rustc_session;
 extern crate rustc_span;
 
 use rustc_interface::interface;
+use rustc_session::config::Input;
 use rustc_session::parse::ParseSess;
-use rustc_span::symbol::Symbol;
+use rustc_span::edition::Edition;
+use rustc_span::symbol::{sym, Symbol};
+use rustc_span::FileName;
 use rustc_tools_util::VersionInfo;
 
 use std::borrow::Cow;
 use std::env;
+use std::fs;
 use std::ops::Deref;
 use std::panic;
 use std::path::{Path, PathBuf};
@@ -113,6 +118,13 @@ impl rustc_driver::Callbacks for SubstraceCallbacks {
             substrace_lints::register_plugins(lint_store, sess, &conf);
         }));
 
+        // Unlike `clippy`, rustc doesn't know the `substrace` tool, so it has to be registered for
+        // `#[allow(substrace::..)]` and `-D substrace::..` to be accepted.
+        if !enables_register_tool(&config.input, config.opts.edition) {
+            config.opts.unstable_opts.crate_attr.push("feature(register_tool)".into());
+        }
+        config.opts.unstable_opts.crate_attr.push("register_tool(substrace)".into());
+
         // FIXME: #4825; This is required, because Substrace lints that are based on MIR have to be
         // run on the unoptimized MIR. On the other hand this results in some false negatives. If
         // MIR passes can be enabled / disabled separately, we should figure out, what passes to
@@ -121,6 +133,34 @@ impl rustc_driver::Callbacks for SubstraceCallbacks {
     }
 }
 
+// Whether the crate root enables `register_tool` itself, as enabling a feature twice is an error.
+// There is no session yet, so the crate attributes are parsed with a silent `ParseSess` of their own.
+fn enables_register_tool(input: &Input, edition: Edition) -> bool {
+    let (name, source) = match input {
+        Input::File(path) => {
+            let Ok(source) = fs::read_to_string(path) else { return false };
+            (FileName::from(path.clone()), source)
+        },
+        Input::Str { name, input } => (name.clone(), input.clone()),
+    };
+    rustc_span::create_session_if_not_set_then(edition, |_| {
+        let parse_sess = ParseSess::with_silent_emitter(None);
+        let attrs = rustc_parse::parse_crate_attrs_from_source_str(name, source, &parse_sess);
+        match attrs {
+            Ok(attrs) => attrs.iter().any(|attr| {
+                attr.has_name(sym::feature)
+                    && attr
+                        .meta_item_list()
+                        .map_or(false, |features| features.iter().any(|feature| feature.has_name(sym::register_tool)))
+            }),
+            Err(err) => {
+                err.cancel();
+                false
+            },
+        }
+    })
+}
+
 fn display_help() {
     println!(
         "\
@@ -144,9 +184,10 @@ with:
     -D --deny OPT       Set lint denied
     -F --forbid OPT     Set lint forbidden
 
-You can use tool lints to allow or deny lints from your code, eg.:
+You can use tool lints to allow or deny lints, or groups of lints such as
+`substrace::security`, from your code, eg.:
 
-    #[allow(substrace::needless_lifetimes)]
+    #[cfg_attr(feature = \"cargo-substrace\", allow(substrace::panics))]
 "
     );
 }
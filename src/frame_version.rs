@@ -0,0 +1,56 @@
+use cargo_metadata::{MetadataCommand, Package};
+
+/// Finds the version of `frame-support` used by the package being checked, for the driver to read
+/// from `SUBSTRACE_FRAME_VERSION`.
+///
+/// This is either a release like `4.0.0`, or the `polkadot-vX.Y.Z` branch of a git dependency on
+/// Substrate. Returns `None` if there is no such version, e.g. for `4.0.0-dev` from another branch.
+pub fn resolve(manifest_path: Option<&str>) -> Option<String> {
+    let mut command = MetadataCommand::new();
+    if let Some(manifest_path) = manifest_path {
+        command.manifest_path(manifest_path);
+    }
+    let metadata = command.exec().ok()?;
+
+    let is_frame_support = |package: &&Package| package.name == "frame-support";
+
+    // Prefer the `frame-support` the root package or a workspace member depends on, in case there
+    // are several.
+    let direct = metadata.resolve.as_ref().and_then(|resolve| {
+        let roots = resolve.root.iter().chain(&metadata.workspace_members);
+        roots
+            .filter_map(|root| resolve.nodes.iter().find(|node| node.id == *root))
+            .flat_map(|node| &node.deps)
+            .find_map(|dep| metadata.packages.iter().filter(is_frame_support).find(|p| p.id == dep.pkg))
+    });
+    let frame_support = direct.or_else(|| metadata.packages.iter().find(is_frame_support))?;
+
+    if frame_support.version.pre.is_empty() {
+        return Some(frame_support.version.to_string());
+    }
+
+    // `git+https://github.com/paritytech/substrate?branch=polkadot-v0.9.30#<commit>`
+    let source = frame_support.source.as_ref()?.repr.as_str();
+    let query = source.split_once('?')?.1;
+    let query = query.split_once('#').map_or(query, |(query, _)| query);
+    query
+        .split('&')
+        .find_map(|param| param.strip_prefix("branch="))
+        .filter(|branch| {
+            branch
+                .strip_prefix("polkadot-v")
+                .map_or(false, |version| semver::Version::parse(version).is_ok())
+        })
+        .map(ToString::to_string)
+}
+
+/// The value of `--manifest-path` in the arguments passed on to cargo, if any.
+pub fn manifest_path(args: &[String]) -> Option<&str> {
+    args.iter().enumerate().find_map(|(i, arg)| {
+        if arg == "--manifest-path" {
+            args.get(i + 1).map(String::as_str)
+        } else {
+            arg.strip_prefix("--manifest-path=")
+        }
+    })
+}
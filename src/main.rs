@@ -8,6 +8,7 @@ use std::path::PathBuf;
 use std::process::{self, Command};
 
 mod docs;
+mod frame_version;
 
 const CARGO_SUBSTRACE_HELP: &str = r#"Substrace: Checks a package to catch common mistakes and improve your Rust code.
 
@@ -20,6 +21,7 @@ Common options:
     -h, --help               Print this message
     -V, --version            Print version info and exit
     --explain LINT           Print the documentation for a given lint
+    --list                   Print the lints and the groups they are in
 
 Other options are the same as `cargo check`.
 
@@ -31,9 +33,10 @@ with:
     -D --deny OPT       Set lint denied
     -F --forbid OPT     Set lint forbidden
 
-You can use tool lints to allow or deny lints from your code, eg.:
+You can use tool lints to allow or deny lints, or groups of lints such as
+`substrace::security`, from your code, eg.:
 
-    #[allow(substrace::panics)]
+    #[cfg_attr(feature = "cargo-substrace", allow(substrace::panics))]
 "#;
 
 fn show_help() {
@@ -61,7 +64,6 @@ pub fn main() {
 
     if let Some(pos) = env::args().position(|a| a == "--explain") {
         if let Some(mut lint) = env::args().nth(pos + 1) {
-            println!("Lint? {:?}", lint);
             lint.make_ascii_lowercase();
             docs::explain(&lint.strip_prefix("substrace::").unwrap_or(&lint).replace('-', "_"));
         } else {
@@ -70,6 +72,11 @@ pub fn main() {
         return;
     }
 
+    if env::args().any(|a| a == "--list") {
+        docs::list();
+        return;
+    }
+
     if let Err(code) = process(env::args().skip(2)) {
         process::exit(code);
     }
@@ -148,6 +155,13 @@ impl SubstraceCmd {
             .arg(self.cargo_subcommand)
             .args(&self.args);
 
+        // The driver runs once per crate, so the FRAME version is resolved here rather than there.
+        if env::var_os("SUBSTRACE_FRAME_VERSION").is_none() {
+            if let Some(version) = frame_version::resolve(frame_version::manifest_path(&self.args)) {
+                cmd.env("SUBSTRACE_FRAME_VERSION", version);
+            }
+        }
+
         cmd
     }
 }
@@ -177,7 +191,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::SubstraceCmd;
+    use super::{frame_version, SubstraceCmd};
 
     #[test]
     fn fix() {
@@ -209,4 +223,17 @@ mod tests {
         let cmd = SubstraceCmd::new(args);
         assert_eq!("check", cmd.cargo_subcommand);
     }
+
+    #[test]
+    fn manifest_path() {
+        let args = "cargo substrace --manifest-path pallets/a/Cargo.toml -- --no-deps"
+            .split_whitespace()
+            .map(ToString::to_string);
+        let cmd = SubstraceCmd::new(args);
+        assert_eq!(frame_version::manifest_path(&cmd.args), Some("pallets/a/Cargo.toml"));
+
+        let args = "cargo substrace --manifest-path=Cargo.toml".split_whitespace().map(ToString::to_string);
+        let cmd = SubstraceCmd::new(args);
+        assert_eq!(frame_version::manifest_path(&cmd.args), Some("Cargo.toml"));
+    }
 }
@@ -0,0 +1,78 @@
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// The directory with the lint declarations `cargo substrace --explain` and `--list` are generated
+/// from.
+const LINTS_DIR: &str = "src/substrace_lints";
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed={LINTS_DIR}");
+
+    let mut lints = Vec::new();
+    for entry in fs::read_dir(LINTS_DIR).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().map_or(false, |extension| extension == "rs") {
+            lints.extend(parse_lints(&fs::read_to_string(&path).unwrap()));
+        }
+    }
+    lints.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut out = String::from("pub static LINTS: &[Lint] = &[\n");
+    for lint in &lints {
+        writeln!(
+            out,
+            "    Lint {{ name: {:?}, group: {:?}, description: {:?}, docs: {:?} }},",
+            lint.name, lint.group, lint.description, lint.docs
+        )
+        .unwrap();
+    }
+    out.push_str("];\n");
+    fs::write(Path::new(&env::var("OUT_DIR").unwrap()).join("lints.rs"), out).unwrap();
+}
+
+struct Lint {
+    name: String,
+    group: String,
+    description: String,
+    docs: String,
+}
+
+// Reads the `declare_substrace_lint!` invocations of a file, which look like:
+//
+// declare_substrace_lint! {
+//     /// docs
+//     pub LINT_NAME,
+//     group,
+//     "description"
+// }
+fn parse_lints(source: &str) -> Vec<Lint> {
+    let mut lints = Vec::new();
+    let mut lines = source.lines().map(str::trim);
+    while let Some(line) = lines.next() {
+        if line != "declare_substrace_lint! {" {
+            continue;
+        }
+
+        let mut docs = String::new();
+        let mut line = lines.next().unwrap_or_default();
+        while let Some(doc) = line.strip_prefix("///") {
+            docs.push_str(doc.strip_prefix(' ').unwrap_or(doc));
+            docs.push('\n');
+            line = lines.next().unwrap_or_default();
+        }
+
+        let name = line.trim_start_matches("pub ").trim_end_matches(',').to_lowercase();
+        let group = lines.next().unwrap_or_default().trim_end_matches(',').to_string();
+        let description = lines.next().unwrap_or_default().trim_matches('"').to_string();
+        lints.push(Lint {
+            name,
+            group,
+            description,
+            docs,
+        });
+    }
+    lints
+}
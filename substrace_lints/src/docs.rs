@@ -0,0 +1,10 @@
+/// A lint as declared with `declare_substrace_lint!`.
+pub struct Lint {
+    pub name: &'static str,
+    pub group: &'static str,
+    pub description: &'static str,
+    pub docs: &'static str,
+}
+
+// Generated by `build.rs` from the lint declarations.
+include!(concat!(env!("OUT_DIR"), "/lints.rs"));
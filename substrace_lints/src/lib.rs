@@ -29,28 +29,30 @@ extern crate rustc_data_structures;
 extern crate rustc_errors;
 extern crate rustc_hir;
 extern crate rustc_lint;
+extern crate rustc_middle;
+extern crate rustc_parse;
 extern crate rustc_session;
 extern crate rustc_span;
 
 #[macro_use]
 extern crate substrace_utils;
 
+use rustc_lint::LintId;
 use rustc_session::Session;
 
 /// Macro used to declare a Substrace lint.
 ///
 /// Every lint declaration consists of 4 parts:
 ///
-/// 1. The documentation, which is used for the website
+/// 1. The documentation, which is printed by `cargo substrace --explain`
 /// 2. The `LINT_NAME`. See [lint naming][lint_naming] on lint naming conventions.
-/// 3. The `lint_level`, which is a mapping from *one* of our lint groups to `Allow`, `Warn` or
-///    `Deny`. The lint level here has nothing to do with what lint groups the lint is a part of.
+/// 3. The lint group, `security`, `storage`, `weights` or `maintenance`, which the lint is
+///    registered in as `substrace::<group>`. The lints of `maintenance` are allowed by default, the
+///    others warn by default.
 /// 4. The `description` that contains a short explanation on what's wrong with code where the
-///    lint is triggered.
+///    lint is triggered, which is printed by `cargo substrace --list`.
 ///
-/// Currently the categories `style`, `correctness`, `suspicious`, `complexity` and `perf` are
-/// enabled by default. As said in the README.md of this repository, if the lint level mapping
-/// changes, please update README.md.
+/// The documentation is read from the declarations by `build.rs`, so keep them in this form.
 ///
 /// # Example
 ///
@@ -77,54 +79,29 @@ use rustc_session::Session;
 ///     /// Insert a short example of improved code that doesn't trigger the lint
 ///     /// ```
 ///     pub LINT_NAME,
-///     pedantic,
+///     security,
 ///     "description"
 /// }
 /// ```
 /// [lint_naming]: https://rust-lang.github.io/rfcs/0344-conventions-galore.html#lints
 #[macro_export]
 macro_rules! declare_substrace_lint {
-    { $(#[$attr:meta])* pub $name:tt, style, $description:tt } => {
+    { $(#[$attr:meta])* pub $name:tt, security, $description:tt } => {
         declare_tool_lint! {
             $(#[$attr])* pub substrace::$name, Warn, $description, report_in_external_macro: true
         }
     };
-    { $(#[$attr:meta])* pub $name:tt, correctness, $description:tt } => {
-        declare_tool_lint! {
-            $(#[$attr])* pub substrace::$name, Deny, $description, report_in_external_macro: true
-        }
-    };
-    { $(#[$attr:meta])* pub $name:tt, suspicious, $description:tt } => {
-        declare_tool_lint! {
-            $(#[$attr])* pub substrace::$name, Warn, $description, report_in_external_macro: true
-        }
-    };
-    { $(#[$attr:meta])* pub $name:tt, complexity, $description:tt } => {
+    { $(#[$attr:meta])* pub $name:tt, storage, $description:tt } => {
         declare_tool_lint! {
             $(#[$attr])* pub substrace::$name, Warn, $description, report_in_external_macro: true
         }
     };
-    { $(#[$attr:meta])* pub $name:tt, perf, $description:tt } => {
+    { $(#[$attr:meta])* pub $name:tt, weights, $description:tt } => {
         declare_tool_lint! {
             $(#[$attr])* pub substrace::$name, Warn, $description, report_in_external_macro: true
         }
     };
-    { $(#[$attr:meta])* pub $name:tt, pedantic, $description:tt } => {
-        declare_tool_lint! {
-            $(#[$attr])* pub substrace::$name, Allow, $description, report_in_external_macro: true
-        }
-    };
-    { $(#[$attr:meta])* pub $name:tt, restriction, $description:tt } => {
-        declare_tool_lint! {
-            $(#[$attr])* pub substrace::$name, Allow, $description, report_in_external_macro: true
-        }
-    };
-    { $(#[$attr:meta])* pub $name:tt, cargo, $description:tt } => {
-        declare_tool_lint! {
-            $(#[$attr])* pub substrace::$name, Allow, $description, report_in_external_macro: true
-        }
-    };
-    { $(#[$attr:meta])* pub $name:tt, nursery, $description:tt } => {
+    { $(#[$attr:meta])* pub $name:tt, maintenance, $description:tt } => {
         declare_tool_lint! {
             $(#[$attr])* pub substrace::$name, Allow, $description, report_in_external_macro: true
         }
@@ -141,6 +118,8 @@ macro_rules! declare_substrace_lint {
     };
 }
 
+pub mod docs;
+
 #[cfg(feature = "internal")]
 pub mod deprecated_lints;
 #[cfg_attr(feature = "internal", allow(clippy::missing_clippy_version_attribute))]
@@ -150,12 +129,32 @@ mod utils;
 // end lints modules, do not remove this comment, it’s used in `update_lints`
 
 mod substrace_lints;
+use substrace_lints::auxiliary::frame_version::FrameVersion;
+use substrace_lints::auxiliary::pallet::PalletCache;
 use substrace_lints::{
+    constant_weights,
+    db_weight_mismatch,
     enable_singlepass_benchmarks,
     extrinsics_must_be_tagged,
+    flat_weights,
+    floating_point,
+    ignored_currency_results,
+    insecure_randomness,
+    lossy_conversions,
     missing_security_doc,
     missing_transactional,
     no_panics,
+    nondeterminism,
+    reachable_panics,
+    storage_iter_insert,
+    storage_write_before_check,
+    unbounded_extrinsic_params,
+    unbounded_storage,
+    unbounded_storage_iteration,
+    unbounded_storage_removal,
+    unchecked_arithmetic,
+    unchecked_origin,
+    xcm_config_check,
 };
 
 pub use crate::utils::conf::Conf;
@@ -173,6 +172,19 @@ pub fn read_conf(sess: &Session) -> Conf {
         },
     };
 
+    if utils::conf::is_clippy_conf_file(&file_name) {
+        let keys = utils::conf::substrace_only_keys(&file_name);
+        if !keys.is_empty() {
+            sess.struct_warn(&format!(
+                "Substrace's configuration was read from `{}`, where Clippy rejects {}",
+                file_name.display(),
+                keys.iter().map(|key| format!("`{key}`")).collect::<Vec<_>>().join(", ")
+            ))
+            .help("move Substrace's configuration to a `substrace.toml` next to it")
+            .emit();
+        }
+    }
+
     let TryConf { conf, errors, warnings } = utils::conf::read(&file_name);
     // all conf errors are non-fatal, we just use the default conf in case of error
     for error in errors {
@@ -198,17 +210,158 @@ pub fn read_conf(sess: &Session) -> Conf {
 /// Register all lints and lint groups with the rustc plugin registry
 ///
 /// Used in `./src/driver.rs`.
-pub fn register_plugins(store: &mut rustc_lint::LintStore, _: &Session, _: &Conf) {
+pub fn register_plugins(store: &mut rustc_lint::LintStore, sess: &Session, conf: &Conf) {
+    let arithmetic_side_effects_allowed = conf.arithmetic_side_effects_allowed.clone();
+    let panics_required_lints = conf.panics_required_lints.clone();
+    let frame_version = FrameVersion::from_env(sess);
+    // Located once, by the first late pass that needs them.
+    let pallets = PalletCache::default();
+
+    store.register_lints(&[
+        constant_weights::CONSTANT_WEIGHTS,
+        db_weight_mismatch::DB_WEIGHT_MISMATCH,
+        enable_singlepass_benchmarks::ENABLE_SINGLEPASS_BENCHMARKS,
+        extrinsics_must_be_tagged::EXTRINSICS_MUST_BE_TAGGED,
+        flat_weights::FLAT_WEIGHTS,
+        floating_point::FLOATING_POINT,
+        ignored_currency_results::IGNORED_CURRENCY_RESULTS,
+        insecure_randomness::INSECURE_RANDOMNESS,
+        lossy_conversions::LOSSY_CONVERSIONS,
+        missing_security_doc::MISSING_SECURITY_DOC,
+        missing_transactional::MISSING_TRANSACTIONAL,
+        no_panics::PANICS,
+        nondeterminism::NONDETERMINISM,
+        reachable_panics::REACHABLE_PANICS,
+        storage_iter_insert::STORAGE_ITER_INSERT,
+        storage_write_before_check::STORAGE_WRITE_BEFORE_CHECK,
+        unbounded_extrinsic_params::UNBOUNDED_EXTRINSIC_PARAMS,
+        unbounded_storage::UNBOUNDED_STORAGE,
+        unbounded_storage_iteration::UNBOUNDED_STORAGE_ITERATION,
+        unbounded_storage_iteration::UNBOUNDED_STORAGE_ITERATION_MAINTENANCE,
+        unbounded_storage_removal::UNBOUNDED_STORAGE_REMOVAL,
+        unchecked_arithmetic::UNCHECKED_ARITHMETIC,
+        unchecked_origin::UNCHECKED_ORIGIN,
+        xcm_config_check::XCM_CONFIG_CHECK,
+    ]);
+
+    // Keep the groups in sync with the groups in the lint declarations. `substrace::all` leaves out
+    // the lints that are allowed by default.
+    store.register_group(true, "substrace::all", Some("substrace_all"), vec![
+        LintId::of(constant_weights::CONSTANT_WEIGHTS),
+        LintId::of(db_weight_mismatch::DB_WEIGHT_MISMATCH),
+        LintId::of(enable_singlepass_benchmarks::ENABLE_SINGLEPASS_BENCHMARKS),
+        LintId::of(extrinsics_must_be_tagged::EXTRINSICS_MUST_BE_TAGGED),
+        LintId::of(flat_weights::FLAT_WEIGHTS),
+        LintId::of(floating_point::FLOATING_POINT),
+        LintId::of(ignored_currency_results::IGNORED_CURRENCY_RESULTS),
+        LintId::of(insecure_randomness::INSECURE_RANDOMNESS),
+        LintId::of(lossy_conversions::LOSSY_CONVERSIONS),
+        LintId::of(missing_security_doc::MISSING_SECURITY_DOC),
+        LintId::of(missing_transactional::MISSING_TRANSACTIONAL),
+        LintId::of(no_panics::PANICS),
+        LintId::of(nondeterminism::NONDETERMINISM),
+        LintId::of(reachable_panics::REACHABLE_PANICS),
+        LintId::of(storage_iter_insert::STORAGE_ITER_INSERT),
+        LintId::of(storage_write_before_check::STORAGE_WRITE_BEFORE_CHECK),
+        LintId::of(unbounded_extrinsic_params::UNBOUNDED_EXTRINSIC_PARAMS),
+        LintId::of(unbounded_storage::UNBOUNDED_STORAGE),
+        LintId::of(unbounded_storage_iteration::UNBOUNDED_STORAGE_ITERATION),
+        LintId::of(unbounded_storage_removal::UNBOUNDED_STORAGE_REMOVAL),
+        LintId::of(unchecked_arithmetic::UNCHECKED_ARITHMETIC),
+        LintId::of(unchecked_origin::UNCHECKED_ORIGIN),
+        LintId::of(xcm_config_check::XCM_CONFIG_CHECK),
+    ]);
+    store.register_group(true, "substrace::security", Some("substrace_security"), vec![
+        LintId::of(extrinsics_must_be_tagged::EXTRINSICS_MUST_BE_TAGGED),
+        LintId::of(floating_point::FLOATING_POINT),
+        LintId::of(ignored_currency_results::IGNORED_CURRENCY_RESULTS),
+        LintId::of(insecure_randomness::INSECURE_RANDOMNESS),
+        LintId::of(lossy_conversions::LOSSY_CONVERSIONS),
+        LintId::of(missing_security_doc::MISSING_SECURITY_DOC),
+        LintId::of(no_panics::PANICS),
+        LintId::of(nondeterminism::NONDETERMINISM),
+        LintId::of(reachable_panics::REACHABLE_PANICS),
+        LintId::of(unbounded_extrinsic_params::UNBOUNDED_EXTRINSIC_PARAMS),
+        LintId::of(unbounded_storage_iteration::UNBOUNDED_STORAGE_ITERATION),
+        LintId::of(unchecked_arithmetic::UNCHECKED_ARITHMETIC),
+        LintId::of(unchecked_origin::UNCHECKED_ORIGIN),
+        LintId::of(xcm_config_check::XCM_CONFIG_CHECK),
+    ]);
+    store.register_group(true, "substrace::storage", Some("substrace_storage"), vec![
+        LintId::of(missing_transactional::MISSING_TRANSACTIONAL),
+        LintId::of(storage_iter_insert::STORAGE_ITER_INSERT),
+        LintId::of(storage_write_before_check::STORAGE_WRITE_BEFORE_CHECK),
+        LintId::of(unbounded_storage::UNBOUNDED_STORAGE),
+        LintId::of(unbounded_storage_removal::UNBOUNDED_STORAGE_REMOVAL),
+    ]);
+    store.register_group(true, "substrace::weights", Some("substrace_weights"), vec![
+        LintId::of(constant_weights::CONSTANT_WEIGHTS),
+        LintId::of(db_weight_mismatch::DB_WEIGHT_MISMATCH),
+        LintId::of(enable_singlepass_benchmarks::ENABLE_SINGLEPASS_BENCHMARKS),
+        LintId::of(flat_weights::FLAT_WEIGHTS),
+    ]);
+    store.register_group(true, "substrace::maintenance", Some("substrace_maintenance"), vec![
+        LintId::of(unbounded_storage_iteration::UNBOUNDED_STORAGE_ITERATION_MAINTENANCE),
+    ]);
 
-    // Allows to enable or disable lints in code
-    store.register_lints(&[enable_singlepass_benchmarks::ENABLE_SINGLEPASS_BENCHMARKS]);
-    store.register_lints(&[no_panics::PANICS]);
+    store.register_pre_expansion_pass(|| Box::new(enable_singlepass_benchmarks::EnableSinglepassBenchmarks));
+    store.register_pre_expansion_pass(|| Box::new(extrinsics_must_be_tagged::ExtrinsicsMustBeTagged));
+    let unbounded_storage_attrs = unbounded_storage::ReportedAttrs::default();
+    let reported_attrs = unbounded_storage_attrs.clone();
+    store.register_pre_expansion_pass(move || {
+        Box::new(unbounded_storage::UnboundedStorageAttrs::new(reported_attrs.clone()))
+    });
 
-    store.register_late_pass(|_| Box::new(enable_singlepass_benchmarks::EnableSinglepassBenchmarks));
-    store.register_late_pass(|_| Box::new(extrinsics_must_be_tagged::ExtrinsicsMustBeTagged));
-    store.register_late_pass(|_| Box::new(missing_security_doc::DocMarkdown));
-    store.register_late_pass(|_| Box::new(missing_transactional::MissingTransactional));
-    store.register_late_pass(|_| Box::new(no_panics::Panics::new()));
+    let constant_weights_pallets = pallets.clone();
+    store.register_late_pass(move |_| Box::new(constant_weights::ConstantWeights::new(constant_weights_pallets.clone())));
+    let db_weight_mismatch_pallets = pallets.clone();
+    store.register_late_pass(move |_| Box::new(db_weight_mismatch::DbWeightMismatch::new(db_weight_mismatch_pallets.clone())));
+    let flat_weights_pallets = pallets.clone();
+    store.register_late_pass(move |_| Box::new(flat_weights::FlatWeights::new(flat_weights_pallets.clone())));
+    let floating_point_pallets = pallets.clone();
+    store.register_late_pass(move |_| Box::new(floating_point::FloatingPoint::new(floating_point_pallets.clone())));
+    store.register_late_pass(|_| Box::new(ignored_currency_results::IgnoredCurrencyResults));
+    store.register_late_pass(|_| Box::new(insecure_randomness::InsecureRandomness));
+    let lossy_conversions_pallets = pallets.clone();
+    store.register_late_pass(move |_| Box::new(lossy_conversions::LossyConversions::new(lossy_conversions_pallets.clone())));
+    store.register_late_pass(|_| Box::<missing_security_doc::DocMarkdown>::default());
+    let missing_transactional_pallets = pallets.clone();
+    let missing_transactional_frame_version = frame_version.clone();
+    store.register_late_pass(move |_| {
+        Box::new(missing_transactional::MissingTransactional::new(
+            missing_transactional_pallets.clone(),
+            missing_transactional_frame_version.clone(),
+        ))
+    });
+    store.register_late_pass(move |_| Box::new(no_panics::Panics::new(&panics_required_lints)));
+    store.register_late_pass(|_| Box::<nondeterminism::Nondeterminism>::default());
+    let reachable_panics_pallets = pallets.clone();
+    store.register_late_pass(move |_| Box::new(reachable_panics::ReachablePanics::new(reachable_panics_pallets.clone())));
+    store.register_late_pass(|_| Box::new(storage_iter_insert::StorageIterInsert));
+    let storage_write_before_check_pallets = pallets.clone();
+    store.register_late_pass(move |_| {
+        Box::new(storage_write_before_check::StorageWriteBeforeCheck::new(
+            storage_write_before_check_pallets.clone(),
+            frame_version.clone(),
+        ))
+    });
+    let unbounded_extrinsic_params_pallets = pallets.clone();
+    store.register_late_pass(move |_| Box::new(unbounded_extrinsic_params::UnboundedExtrinsicParams::new(unbounded_extrinsic_params_pallets.clone())));
+    store.register_late_pass(move |_| {
+        Box::new(unbounded_storage::UnboundedStorage::new(unbounded_storage_attrs.clone()))
+    });
+    let unbounded_storage_iteration_pallets = pallets.clone();
+    store.register_late_pass(move |_| Box::new(unbounded_storage_iteration::UnboundedStorageIteration::new(unbounded_storage_iteration_pallets.clone())));
+    store.register_late_pass(|_| Box::new(unbounded_storage_removal::UnboundedStorageRemoval));
+    let unchecked_arithmetic_pallets = pallets.clone();
+    store.register_late_pass(move |_| {
+        Box::new(unchecked_arithmetic::UncheckedArithmetic::new(
+            unchecked_arithmetic_pallets.clone(),
+            &arithmetic_side_effects_allowed,
+        ))
+    });
+    store.register_late_pass(move |_| Box::new(unchecked_origin::UncheckedOrigin::new(pallets.clone())));
+    store.register_late_pass(|_| Box::new(xcm_config_check::XcmConfigCheck));
 }
 
 // only exists to let the dogfood integration test works.
@@ -0,0 +1,60 @@
+use rustc_ast::ast;
+use rustc_hir::def_id::CRATE_DEF_ID;
+use rustc_lint::{LateContext, LintContext};
+use rustc_span::sym;
+
+// Checks if `attr` is the pallet attribute `#[pallet::{name}]`, as seen before the pallet macro is
+// expanded.
+pub fn is_pallet_attr(attr: &ast::Attribute, name: &str) -> bool {
+    if let ast::AttrKind::Normal(normal) = &attr.kind
+        && let [pallet, attr_name] = &normal.item.path.segments[..] {
+        pallet.ident.name.as_str() == "pallet" && attr_name.ident.name.as_str() == name
+    } else {
+        false
+    }
+}
+
+// Checks if the doc comments in `attrs` have a `# {header}` section, at any heading level.
+pub fn has_doc_header(attrs: &[ast::Attribute], header: &str) -> bool {
+    attrs.iter().filter_map(ast::Attribute::doc_str).any(|doc| {
+        doc.as_str().lines().any(|line| {
+            let line = line.trim();
+            line.starts_with('#') && line.trim_start_matches('#').trim() == header
+        })
+    })
+}
+
+// Checks if the crate is compiled to wasm for the runtime: it is `no_std`, like pallets and runtimes
+// built without their `std` feature. The `#![cfg_attr(not(feature = "std"), no_std)]` attribute is
+// already gone when the `std` feature is enabled, so the attributes of the crate root are parsed
+// again from its source.
+pub fn is_runtime_crate(cx: &LateContext<'_>) -> bool {
+    if cx.tcx.hir().krate_attrs().iter().any(|attr| attr.has_name(sym::no_std)) {
+        return true;
+    }
+    let file = cx.sess().source_map().lookup_source_file(cx.tcx.def_span(CRATE_DEF_ID).lo());
+    let Some(src) = &file.src else { return false };
+    match rustc_parse::parse_crate_attrs_from_source_str(file.name.clone(), src.to_string(), &cx.sess().parse_sess) {
+        Ok(attrs) => attrs.iter().any(is_std_feature_no_std),
+        Err(err) => {
+            err.cancel();
+            false
+        },
+    }
+}
+
+// `#![cfg_attr(not(feature = "std"), no_std)]`, or another predicate on the `std` feature.
+fn is_std_feature_no_std(attr: &ast::Attribute) -> bool {
+    fn mentions_std(items: &[ast::NestedMetaItem]) -> bool {
+        items.iter().any(|item| {
+            (item.has_name(sym::feature) && item.value_str() == Some(sym::std))
+                || item.meta_item_list().map_or(false, mentions_std)
+        })
+    }
+    if !attr.has_name(sym::cfg_attr) {
+        return false;
+    }
+    let Some(items) = attr.meta_item_list() else { return false };
+    let Some((predicate, attrs)) = items.split_first() else { return false };
+    mentions_std(std::slice::from_ref(predicate)) && attrs.iter().any(|attr| attr.has_name(sym::no_std))
+}
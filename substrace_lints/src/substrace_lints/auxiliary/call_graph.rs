@@ -0,0 +1,196 @@
+use super::pallet::{is_frame_support_trait, pallets, PalletCache};
+use substrace_utils::visitors::for_each_expr_with_closures;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_hir as hir;
+use rustc_hir::def::{DefKind, Res};
+use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_lint::LateContext;
+use rustc_middle::ty::{DefIdTree, Instance, SubstsRef, TypeckResults};
+use std::collections::hash_map::Entry;
+use std::collections::VecDeque;
+use std::ops::ControlFlow;
+
+/// Functions of the trait hooks the runtime calls for every block or on upgrades.
+const HOOKS: [&str; 4] = ["on_initialize", "on_finalize", "on_idle", "on_runtime_upgrade"];
+const HOOK_TRAITS: [&str; 5] = ["Hooks", "OnInitialize", "OnFinalize", "OnIdle", "OnRuntimeUpgrade"];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EntryKind {
+    Dispatchable,
+    Hook,
+    RuntimeApi,
+}
+
+impl EntryKind {
+    pub fn descr(self) -> &'static str {
+        match self {
+            Self::Dispatchable => "dispatchable",
+            Self::Hook => "hook",
+            Self::RuntimeApi => "runtime API",
+        }
+    }
+}
+
+/// A function the runtime calls directly.
+#[derive(Clone, Copy, Debug)]
+pub struct EntryPoint {
+    pub def_id: LocalDefId,
+    pub kind: EntryKind,
+}
+
+/// Finds the dispatchables, hooks and runtime API implementations of the crate.
+pub fn entry_points(cx: &LateContext<'_>, cache: &PalletCache) -> Vec<EntryPoint> {
+    let hir = cx.tcx.hir();
+
+    let mut entry_points: Vec<EntryPoint> = pallets(cx, cache)
+        .iter()
+        .flat_map(|pallet| &pallet.calls)
+        .filter_map(|call| call.def_id.as_local())
+        .map(|def_id| EntryPoint {
+            def_id,
+            kind: EntryKind::Dispatchable,
+        })
+        .collect();
+
+    for item_id in hir.items() {
+        let item = hir.item(item_id);
+        let hir::ItemKind::Impl(impl_block) = item.kind else { continue };
+        let Some(trait_id) = impl_block.of_trait.as_ref().and_then(hir::TraitRef::trait_def_id) else { continue };
+
+        // The hooks written by the user, not the ones the pallet macro implements on top of them.
+        let kind = if !item.span.from_expansion() && HOOK_TRAITS.iter().any(|name| is_frame_support_trait(cx, trait_id, name)) {
+            EntryKind::Hook
+        } else if is_runtime_api(cx, trait_id) {
+            EntryKind::RuntimeApi
+        } else {
+            continue;
+        };
+
+        for impl_item_ref in impl_block.items {
+            if matches!(impl_item_ref.kind, hir::AssocItemKind::Fn { .. })
+                && (kind == EntryKind::RuntimeApi || HOOKS.contains(&impl_item_ref.ident.as_str())) {
+                entry_points.push(EntryPoint {
+                    def_id: impl_item_ref.id.owner_id.def_id,
+                    kind,
+                });
+            }
+        }
+    }
+
+    entry_points
+}
+
+// `impl_runtime_apis!` implements the `runtime_decl_for_<Api>` traits generated by `decl_runtime_apis!`.
+fn is_runtime_api(cx: &LateContext<'_>, trait_id: DefId) -> bool {
+    cx.tcx
+        .opt_parent(trait_id)
+        .map_or(false, |module| cx.tcx.item_name(module).as_str().starts_with("runtime_decl_for_"))
+}
+
+/// A local function reachable from an entry point.
+pub struct Reached {
+    pub def_id: LocalDefId,
+    /// The functions called to get here, starting with the entry point and ending with `def_id`.
+    pub path: Vec<LocalDefId>,
+}
+
+/// Finds the local functions reachable from `entry`, each with the shortest call path to it.
+pub fn reachable(cx: &LateContext<'_>, entry: LocalDefId) -> Vec<Reached> {
+    let mut callers: FxHashMap<LocalDefId, Option<LocalDefId>> = FxHashMap::default();
+    callers.insert(entry, None);
+
+    let mut order = Vec::new();
+    let mut queue = VecDeque::from([entry]);
+    while let Some(def_id) = queue.pop_front() {
+        order.push(def_id);
+        for callee in callees(cx, def_id) {
+            if let Entry::Vacant(entry) = callers.entry(callee) {
+                entry.insert(Some(def_id));
+                queue.push_back(callee);
+            }
+        }
+    }
+
+    order
+        .into_iter()
+        .map(|def_id| {
+            let mut path = vec![def_id];
+            while let Some(Some(caller)) = callers.get(path.last().unwrap()) {
+                path.push(*caller);
+            }
+            path.reverse();
+            Reached { def_id, path }
+        })
+        .collect()
+}
+
+/// Finds the local functions called from the body of `def_id`, including from its closures.
+pub fn callees(cx: &LateContext<'_>, def_id: LocalDefId) -> Vec<LocalDefId> {
+    let Some(body_id) = cx.tcx.hir().maybe_body_owned_by(def_id) else { return Vec::new() };
+    let typeck = cx.tcx.typeck(def_id);
+
+    let mut seen = FxHashSet::default();
+    let mut callees = Vec::new();
+    for_each_expr_with_closures(cx, cx.tcx.hir().body(body_id).value, |expr| {
+        if let Some((fn_id, substs)) = called_fn(typeck, expr)
+            && let Some(local_id) = resolve(cx, def_id, fn_id, substs).as_local()
+            && cx.tcx.hir().maybe_body_owned_by(local_id).is_some()
+            && seen.insert(local_id) {
+            callees.push(local_id);
+        }
+        ControlFlow::<()>::Continue(())
+    });
+    callees
+}
+
+/// The function called by a call or method call expression, with its generic arguments.
+pub fn called_fn<'tcx>(typeck: &TypeckResults<'tcx>, expr: &hir::Expr<'_>) -> Option<(DefId, SubstsRef<'tcx>)> {
+    match expr.kind {
+        hir::ExprKind::Call(callee, _) => match &callee.kind {
+            hir::ExprKind::Path(qpath) => match typeck.qpath_res(qpath, callee.hir_id) {
+                Res::Def(DefKind::Fn | DefKind::AssocFn, fn_id) => Some((fn_id, typeck.node_substs(callee.hir_id))),
+                _ => None,
+            },
+            _ => None,
+        },
+        hir::ExprKind::MethodCall(..) => typeck
+            .type_dependent_def_id(expr.hir_id)
+            .map(|fn_id| (fn_id, typeck.node_substs(expr.hir_id))),
+        _ => None,
+    }
+}
+
+/// Resolves trait methods to the implementation that is called, if it is known.
+pub fn resolve<'tcx>(cx: &LateContext<'tcx>, caller: LocalDefId, fn_id: DefId, substs: SubstsRef<'tcx>) -> DefId {
+    // Lang item paths, like the `Try::branch` of `?`, have no substitutions in the typeck results.
+    if substs.len() != cx.tcx.generics_of(fn_id).count() {
+        return fn_id;
+    }
+    let param_env = cx.tcx.param_env(caller);
+    let substs = cx.tcx.erase_regions(substs);
+    match Instance::resolve(cx.tcx, param_env, fn_id, substs) {
+        Ok(Some(instance)) => instance.def_id(),
+        _ => fn_id,
+    }
+}
+
+/// Describes how `reached` is reached from `entry`, for a note at the entry point.
+pub fn reachable_note(cx: &LateContext<'_>, entry: &EntryPoint, reached: &Reached) -> String {
+    let mut note = format!(
+        "reachable from the {} `{}`",
+        entry.kind.descr(),
+        cx.tcx.def_path_str(entry.def_id.to_def_id())
+    );
+    if reached.path.len() > 1 {
+        note.push_str(&format!(" through {}", format_path(cx, &reached.path)));
+    }
+    note
+}
+
+/// Formats a call path as `a -> b -> c`.
+fn format_path(cx: &LateContext<'_>, path: &[LocalDefId]) -> String {
+    path.iter()
+        .map(|def_id| format!("`{}`", cx.tcx.def_path_str(def_id.to_def_id())))
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
@@ -0,0 +1,69 @@
+use rustc_session::Session;
+use semver::Version;
+use std::env;
+
+/// A change in FRAME, with the first `frame-support` release and the first Polkadot release
+/// branch of Substrate that have it.
+pub struct FrameFeature {
+    release: (u64, u64, u64),
+    polkadot_branch: (u64, u64, u64),
+}
+
+// paritytech/substrate#11431
+pub const TRANSACTIONAL_BY_DEFAULT: FrameFeature = FrameFeature {
+    release: (4, 0, 0),
+    polkadot_branch: (0, 9, 25),
+};
+
+/// The version of `frame-support` used by the crate being linted.
+#[derive(Debug, Clone)]
+pub enum FrameVersion {
+    /// A `frame-support` release, e.g. from crates.io.
+    Release(Version),
+    /// `frame-support` from a `polkadot-vX.Y.Z` branch of Substrate.
+    PolkadotBranch(u64, u64, u64),
+}
+
+impl FrameVersion {
+    /// Reads the FRAME version from the `SUBSTRACE_FRAME_VERSION` environment variable, which
+    /// `cargo substrace` sets from the `frame-support` dependency of the package.
+    ///
+    /// Returns `None` if the version is not known, e.g. for a `4.0.0-dev` version from a branch
+    /// other than a Polkadot release branch.
+    pub fn from_env(sess: &Session) -> Option<Self> {
+        let version = env::var("SUBSTRACE_FRAME_VERSION").ok()?;
+        let parsed = Self::parse(&version);
+        if parsed.is_none() {
+            sess.warn(format!(
+                "`{version}` in `SUBSTRACE_FRAME_VERSION` is not a `frame-support` version or a `polkadot-vX.Y.Z` branch"
+            ));
+        }
+        parsed
+    }
+
+    /// Whether this version has `feature`.
+    pub fn has(&self, feature: &FrameFeature) -> bool {
+        match self {
+            Self::Release(version) => {
+                let (major, minor, patch) = feature.release;
+                *version >= Version::new(major, minor, patch)
+            },
+            Self::PolkadotBranch(major, minor, patch) => (*major, *minor, *patch) >= feature.polkadot_branch,
+        }
+    }
+
+    fn parse(version: &str) -> Option<Self> {
+        if let Some(branch) = version.strip_prefix("polkadot-v") {
+            let Version { major, minor, patch, .. } = Version::parse(branch).ok()?;
+            Some(Self::PolkadotBranch(major, minor, patch))
+        } else {
+            let version = Version::parse(version).ok()?;
+            // Git versions of Substrate were all called `4.0.0-dev` for a long time.
+            if version.pre.is_empty() {
+                Some(Self::Release(version))
+            } else {
+                None
+            }
+        }
+    }
+}
@@ -1 +1,8 @@
-pub mod paths;
\ No newline at end of file
+pub mod attrs;
+pub mod call_graph;
+pub mod frame_version;
+pub mod pallet;
+pub mod paths;
+pub mod storage;
+pub mod unbounded;
+pub mod weights;
\ No newline at end of file
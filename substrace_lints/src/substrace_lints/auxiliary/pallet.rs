@@ -0,0 +1,203 @@
+use substrace_utils::last_path_segment;
+use substrace_utils::visitors::for_each_expr_with_closures;
+use rustc_ast::ast::LitKind;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::sync::{Lrc, OnceCell};
+use rustc_hir as hir;
+use rustc_hir::def::{DefKind, Res};
+use rustc_hir::def_id::DefId;
+use rustc_lint::LateContext;
+use rustc_middle::ty;
+use rustc_span::symbol::Symbol;
+use std::ops::ControlFlow;
+
+/// A pallet, as generated by the `#[pallet]` macro.
+#[derive(Clone)]
+pub struct Pallet {
+    /// The `Pallet` struct, e.g. `Pallet<T>` or `Pallet<T, I>`.
+    pub pallet: DefId,
+    /// The `Call` enum of the pallet.
+    pub call: DefId,
+    /// The extrinsics of the pallet, in the order of `get_call_names`.
+    pub calls: Vec<PalletCall>,
+}
+
+/// An extrinsic of a pallet.
+#[derive(Clone)]
+pub struct PalletCall {
+    pub name: Symbol,
+    /// The function that is dispatched for this call.
+    pub def_id: DefId,
+    /// The expression of `#[pallet::weight(..)]`, as expanded in `GetDispatchInfo`.
+    pub weight: Option<hir::HirId>,
+}
+
+/// The pallets of the crate, located by the first lint pass that asks for them. `register_plugins`
+/// creates one for every compilation session and hands it to the passes that need the pallets, as
+/// the `DefId`s only mean something in the session they come from.
+pub type PalletCache = Lrc<OnceCell<Vec<Pallet>>>;
+
+// Gets the pallets of the crate, locating them the first time.
+pub fn pallets<'a>(cx: &LateContext<'_>, cache: &'a PalletCache) -> &'a [Pallet] {
+    cache.get_or_init(|| locate_pallets(cx))
+}
+
+// Finds all the pallets in the crate.
+fn locate_pallets(cx: &LateContext<'_>) -> Vec<Pallet> {
+    let hir = cx.tcx.hir();
+    let mut pallets = Vec::new();
+
+    // Walks every item of the crate, however deeply nested the pallet module is.
+    for item_id in hir.items() {
+        let item = hir.item(item_id);
+
+        // `impl UnfilteredDispatchable for Call<T>` dispatches every call to its function in the pallet.
+        if let hir::ItemKind::Impl(impl_block) = item.kind
+            && let Some(trait_id) = impl_block.of_trait.as_ref().and_then(hir::TraitRef::trait_def_id)
+            && is_frame_support_trait(cx, trait_id, "UnfilteredDispatchable")
+            && let ty::Adt(call_adt, _) = cx.tcx.type_of(item.owner_id).kind() {
+            let call = call_adt.did();
+
+            // The calls are dispatched to associated functions of the `Pallet` struct. Other paths
+            // in the body, like the `unreachable!` of the `__Ignore` variant, are skipped.
+            let names = call_names(cx, call);
+            let mut dispatched = Vec::new();
+            for impl_item_ref in impl_block.items {
+                let impl_item = hir.impl_item(impl_item_ref.id);
+                if let hir::ImplItemKind::Fn(_, body_id) = impl_item.kind {
+                    let typeck = cx.tcx.typeck(impl_item.owner_id.def_id);
+                    for_each_expr_with_closures(cx, hir.body(body_id).value, |expr| {
+                        if let hir::ExprKind::Path(qpath) = &expr.kind
+                            && let Res::Def(DefKind::AssocFn, fn_id) = typeck.qpath_res(qpath, expr.hir_id)
+                            && fn_id.is_local()
+                            && let Some(impl_id) = cx.tcx.impl_of_method(fn_id)
+                            && cx.tcx.trait_id_of_impl(impl_id).is_none()
+                            && names.as_ref().map_or(true, |names| names.contains(&cx.tcx.item_name(fn_id)))
+                            && !dispatched.contains(&fn_id) {
+                            dispatched.push(fn_id);
+                        }
+                        ControlFlow::<()>::Continue(())
+                    });
+                }
+            }
+
+            let Some(pallet) = dispatched.first().and_then(|fn_id| {
+                let impl_id = cx.tcx.impl_of_method(*fn_id)?;
+                match cx.tcx.type_of(impl_id).kind() {
+                    ty::Adt(adt, _) => Some(adt.did()),
+                    _ => None,
+                }
+            }) else { continue };
+
+            let weights = call_weights(cx, call);
+            let mut calls: Vec<PalletCall> = dispatched
+                .into_iter()
+                .map(|def_id| {
+                    let name = cx.tcx.item_name(def_id);
+                    PalletCall {
+                        name,
+                        def_id,
+                        weight: weights.get(&name).copied(),
+                    }
+                })
+                .collect();
+
+            // Keep the order in which the pallet macro lists the calls.
+            if let Some(names) = names {
+                calls.sort_by_key(|call| names.iter().position(|name| *name == call.name));
+            }
+
+            pallets.push(Pallet { pallet, call, calls });
+        }
+    }
+
+    pallets
+}
+
+// Gets the pallet that `def_id` is an extrinsic of, if any.
+pub fn extrinsic_pallet(pallets: &[Pallet], def_id: DefId) -> Option<&Pallet> {
+    pallets
+        .iter()
+        .find(|pallet| pallet.calls.iter().any(|call| call.def_id == def_id))
+}
+
+pub fn is_extrinsic(pallets: &[Pallet], def_id: DefId) -> bool {
+    extrinsic_pallet(pallets, def_id).is_some()
+}
+
+
+// Reads the names from `get_call_names` in the `GetCallName` implementation of `call`.
+fn call_names(cx: &LateContext<'_>, call: DefId) -> Option<Vec<Symbol>> {
+    let hir = cx.tcx.hir();
+    for item_id in hir.items() {
+        let item = hir.item(item_id);
+        if let hir::ItemKind::Impl(impl_block) = item.kind
+            && let Some(trait_id) = impl_block.of_trait.as_ref().and_then(hir::TraitRef::trait_def_id)
+            && is_frame_support_trait(cx, trait_id, "GetCallName")
+            && let ty::Adt(adt, _) = cx.tcx.type_of(item.owner_id).kind()
+            && adt.did() == call {
+            for impl_item_ref in impl_block.items {
+                if impl_item_ref.ident.as_str() == "get_call_names"
+                    && let hir::ImplItemKind::Fn(_, body_id) = hir.impl_item(impl_item_ref.id).kind
+                    && let hir::ExprKind::Block(body_block, _) = hir.body(body_id).value.kind
+                    && let Some(block_expr) = body_block.expr
+                    && let hir::ExprKind::AddrOf(_, _, ref_expr) = block_expr.kind
+                    && let hir::ExprKind::Array(name_exprs) = ref_expr.kind {
+                    return Some(
+                        name_exprs
+                            .iter()
+                            .filter_map(|name_expr| match &name_expr.kind {
+                                hir::ExprKind::Lit(lit) => match lit.node {
+                                    LitKind::Str(name, _) => Some(name),
+                                    _ => None,
+                                },
+                                _ => None,
+                            })
+                            .collect(),
+                    );
+                }
+            }
+        }
+    }
+    None
+}
+
+// Finds the weight expressions in the `GetDispatchInfo` implementation of `call`, by the name of the
+// call. The pallet macro assigns the expression of `#[pallet::weight(..)]` to a local at the start
+// of the match arm of each call.
+fn call_weights(cx: &LateContext<'_>, call: DefId) -> FxHashMap<Symbol, hir::HirId> {
+    let hir = cx.tcx.hir();
+    let mut weights = FxHashMap::default();
+    for item_id in hir.items() {
+        let item = hir.item(item_id);
+        if let hir::ItemKind::Impl(impl_block) = item.kind
+            && let Some(trait_id) = impl_block.of_trait.as_ref().and_then(hir::TraitRef::trait_def_id)
+            && is_frame_support_trait(cx, trait_id, "GetDispatchInfo")
+            && let ty::Adt(adt, _) = cx.tcx.type_of(item.owner_id).kind()
+            && adt.did() == call {
+            for impl_item_ref in impl_block.items {
+                let hir::ImplItemKind::Fn(_, body_id) = hir.impl_item(impl_item_ref.id).kind else { continue };
+                for_each_expr_with_closures(cx, hir.body(body_id).value, |expr| {
+                    if let hir::ExprKind::Match(_, arms, _) = expr.kind {
+                        for arm in arms {
+                            if let hir::PatKind::Struct(ref qpath, ..) | hir::PatKind::TupleStruct(ref qpath, ..) = arm.pat.kind
+                                && let hir::ExprKind::Block(block, _) = arm.body.kind
+                                && let Some(hir::Stmt { kind: hir::StmtKind::Local(local), .. }) = block.stmts.first()
+                                && let Some(init) = local.init {
+                                weights.insert(last_path_segment(qpath).ident.name, init.hir_id);
+                            }
+                        }
+                    }
+                    ControlFlow::<()>::Continue(())
+                });
+            }
+        }
+    }
+    weights
+}
+
+// The traits used by the pallet macro moved around between FRAME versions, so they are matched by
+// name instead of by path.
+pub fn is_frame_support_trait(cx: &LateContext<'_>, trait_id: DefId, name: &str) -> bool {
+    cx.tcx.item_name(trait_id).as_str() == name && cx.tcx.crate_name(trait_id.krate).as_str() == "frame_support"
+}
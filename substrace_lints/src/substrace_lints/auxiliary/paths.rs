@@ -1,24 +1,22 @@
 use substrace_utils::match_def_path;
-use rustc_hir as hir;
 use rustc_hir::def_id::DefId;
 use rustc_lint::LateContext;
 
-pub fn is_insecure_hash_function<'hir>(cx: &LateContext<'hir>, generics: &hir::GenericArgs<'_>) -> bool {
-    for typ in generics.args {
-        if let hir::GenericArg::Type(ty) = typ {
-            if let hir::TyKind::Path(hir::QPath::Resolved(_, path)) = &ty.kind {
-                if let hir::def::Res::Def(hir::def::DefKind::Struct, id) = path.res {
-                    if match_def_path(cx, id, &TWOX64CONCTAT)
-                        || match_def_path(cx, id, &IDENTITY)
-                        || match_def_path(cx, id, &TWOX128)
-                        || match_def_path(cx, id, &TWOX256) {
-                        return true;
-                    }
-                }
-            }
-        }
-    }
-    false
+// Hashers that an attacker can find colliding or similar keys for, which can be used to make lookups
+// and iteration expensive.
+pub fn is_insecure_hasher(cx: &LateContext<'_>, def_id: DefId) -> bool {
+    match_def_path(cx, def_id, &TWOX64CONCTAT)
+        || match_def_path(cx, def_id, &IDENTITY)
+        || match_def_path(cx, def_id, &TWOX128)
+        || match_def_path(cx, def_id, &TWOX256)
+}
+
+// Hashers that don't store the key next to its hash, so the key can't be read back when iterating.
+pub fn is_non_reversible_hasher(cx: &LateContext<'_>, def_id: DefId) -> bool {
+    match_def_path(cx, def_id, &BLAKE2_128)
+        || match_def_path(cx, def_id, &BLAKE2_256)
+        || match_def_path(cx, def_id, &TWOX128)
+        || match_def_path(cx, def_id, &TWOX256)
 }
 
 pub fn is_like_storage_map(cx: &LateContext<'_>, fn_def_id: DefId) -> bool {
@@ -27,14 +25,45 @@ pub fn is_like_storage_map(cx: &LateContext<'_>, fn_def_id: DefId) -> bool {
         || match_def_path(cx, fn_def_id, &STORAGE_N_MAP)
 }
 
+pub const STORAGE_VALUE: [&str; 5] = ["frame_support", "storage", "types", "value", "StorageValue"];
 pub const STORAGE_MAP: [&str; 5] = ["frame_support", "storage", "types", "map", "StorageMap"];
 pub const STORAGE_DOUBLE_MAP: [&str; 5] = ["frame_support", "storage", "types", "double_map", "StorageDoubleMap"];
 pub const STORAGE_N_MAP: [&str; 5] = ["frame_support", "storage", "types", "nmap", "StorageNMap"];
+pub const STORAGE_KEY: [&str; 5] = ["frame_support", "storage", "types", "key", "Key"];
 
 pub const TWOX64CONCTAT: [&str; 3] = ["frame_support", "hash", "Twox64Concat"];
 pub const TWOX128: [&str; 3] = ["frame_support", "hash", "Twox128"];
 pub const TWOX256: [&str; 3] = ["frame_support", "hash", "Twox256"];
+pub const BLAKE2_128: [&str; 3] = ["frame_support", "hash", "Blake2_128"];
+pub const BLAKE2_256: [&str; 3] = ["frame_support", "hash", "Blake2_256"];
 
 pub const IDENTITY: [&str; 3] = ["frame_support", "hash", "Identity"];
 
-pub const WITH_TRANSACTION: [&str; 4] = ["frame_support", "storage", "transactional", "with_transaction"];
\ No newline at end of file
+pub const WITH_TRANSACTION: [&str; 4] = ["frame_support", "storage", "transactional", "with_transaction"];
+pub const WITH_STORAGE_LAYER: [&str; 3] = ["frame_support", "storage", "with_storage_layer"];
+pub const XCM_EXECUTOR_CONFIG: [&str; 3] = ["xcm_executor", "config", "Config"];
+pub const ALLOW_UNPAID_EXECUTION_FROM: [&str; 3] = ["xcm_builder", "barriers", "AllowUnpaidExecutionFrom"];
+pub const ALLOW_EXPLICIT_UNPAID_EXECUTION_FROM: [&str; 3] = ["xcm_builder", "barriers", "AllowExplicitUnpaidExecutionFrom"];
+pub const ALLOW_TOP_LEVEL_PAID_EXECUTION_FROM: [&str; 3] = ["xcm_builder", "barriers", "AllowTopLevelPaidExecutionFrom"];
+
+pub const CURRENCY: [&str; 5] = ["frame_support", "traits", "tokens", "currency", "Currency"];
+pub const RESERVABLE_CURRENCY: [&str; 6] = ["frame_support", "traits", "tokens", "currency", "reservable", "ReservableCurrency"];
+pub const FUNGIBLE_MUTATE: [&str; 5] = ["frame_support", "traits", "tokens", "fungible", "Mutate"];
+pub const FUNGIBLE_MUTATE_HOLD: [&str; 5] = ["frame_support", "traits", "tokens", "fungible", "MutateHold"];
+// Since `polkadot-v0.9.42` the `fungible` traits live in submodules.
+pub const FUNGIBLE_REGULAR_MUTATE: [&str; 6] = ["frame_support", "traits", "tokens", "fungible", "regular", "Mutate"];
+pub const FUNGIBLE_HOLD_MUTATE: [&str; 6] = ["frame_support", "traits", "tokens", "fungible", "hold", "Mutate"];
+
+pub const MAX_ENCODED_LEN: [&str; 2] = ["parity_scale_codec", "MaxEncodedLen"];
+// Older FRAME versions define the trait themselves.
+pub const FRAME_MAX_ENCODED_LEN: [&str; 3] = ["frame_support", "traits", "MaxEncodedLen"];
+
+pub const EVERYTHING: [&str; 4] = ["frame_support", "traits", "members", "Everything"];
+pub const CONTAINS: [&str; 4] = ["frame_support", "traits", "members", "Contains"];
+
+pub const TIME: [&str; 4] = ["frame_support", "traits", "time", "Time"];
+pub const UNIX_TIME: [&str; 4] = ["frame_support", "traits", "time", "UnixTime"];
+
+pub const LOCAL_KEY: [&str; 4] = ["std", "thread", "local", "LocalKey"];
+pub const INSTANT: [&str; 3] = ["std", "time", "Instant"];
+pub const SYSTEM_TIME: [&str; 3] = ["std", "time", "SystemTime"];
@@ -0,0 +1,118 @@
+use super::paths;
+use substrace_utils::match_def_path;
+use rustc_hir::def_id::DefId;
+use rustc_lint::LateContext;
+use rustc_middle::ty::{self, subst::SubstsRef, Ty};
+use rustc_span::symbol::Symbol;
+
+/// Prefix of the struct generated by `#[pallet::storage]` for every storage item.
+const GENERATED_PREFIX: &str = "_GeneratedPrefixForStorage";
+
+/// A call to one of the methods of a FRAME storage type.
+pub struct StorageCall<'tcx> {
+    /// The storage item the method is called on, e.g. `StorageMap<_GeneratedPrefixForStorageFoo<T>, ..>`.
+    pub storage: Ty<'tcx>,
+    /// Name of the called method, e.g. `insert`.
+    pub method: Symbol,
+}
+
+// Returns the storage item and method name if `def_id` is a (trait or inherent) method of a FRAME
+// `StorageValue` or one of the storage maps.
+pub fn storage_call<'tcx>(cx: &LateContext<'tcx>, def_id: DefId, substs: SubstsRef<'tcx>) -> Option<StorageCall<'tcx>> {
+    let storage = if cx.tcx.trait_of_item(def_id).is_some() {
+        substs.types().next()?
+    } else {
+        let impl_id = cx.tcx.impl_of_method(def_id)?;
+        cx.tcx.bound_type_of(impl_id).subst(cx.tcx, substs)
+    };
+
+    if let ty::Adt(adt, _) = storage.kind()
+        && (paths::is_like_storage_map(cx, adt.did()) || match_def_path(cx, adt.did(), &paths::STORAGE_VALUE)) {
+        Some(StorageCall {
+            storage,
+            method: cx.tcx.item_name(def_id),
+        })
+    } else {
+        None
+    }
+}
+
+/// A key of a FRAME storage map and the hasher used for it.
+pub struct StorageKey<'tcx> {
+    pub hasher: Ty<'tcx>,
+    pub key: Ty<'tcx>,
+}
+
+// Returns the keys of a FRAME storage map in order, e.g. both keys of a `StorageDoubleMap` or the
+// `Key<Hasher, Key>`s of a `StorageNMap`.
+pub fn storage_keys<'tcx>(cx: &LateContext<'tcx>, storage: Ty<'tcx>) -> Vec<StorageKey<'tcx>> {
+    let ty::Adt(adt, substs) = storage.kind() else { return Vec::new() };
+    let key = |hasher, key| StorageKey {
+        hasher: substs.type_at(hasher),
+        key: substs.type_at(key),
+    };
+
+    if match_def_path(cx, adt.did(), &paths::STORAGE_MAP) {
+        vec![key(1, 2)]
+    } else if match_def_path(cx, adt.did(), &paths::STORAGE_DOUBLE_MAP) {
+        vec![key(1, 2), key(3, 4)]
+    } else if match_def_path(cx, adt.did(), &paths::STORAGE_N_MAP) {
+        let keys = substs.type_at(1);
+        let keys = match keys.kind() {
+            ty::Tuple(keys) => keys.iter().collect(),
+            _ => vec![keys],
+        };
+        keys.into_iter()
+            .filter_map(|key| match key.kind() {
+                ty::Adt(adt, substs) if match_def_path(cx, adt.did(), &paths::STORAGE_KEY) => Some(StorageKey {
+                    hasher: substs.type_at(0),
+                    key: substs.type_at(1),
+                }),
+                _ => None,
+            })
+            .collect()
+    } else {
+        Vec::new()
+    }
+}
+
+// Returns the value type of a FRAME storage item.
+pub fn storage_value<'tcx>(cx: &LateContext<'tcx>, storage: Ty<'tcx>) -> Option<Ty<'tcx>> {
+    let ty::Adt(adt, substs) = storage.kind() else { return None };
+
+    if match_def_path(cx, adt.did(), &paths::STORAGE_VALUE) {
+        Some(substs.type_at(1))
+    } else if match_def_path(cx, adt.did(), &paths::STORAGE_MAP) {
+        Some(substs.type_at(3))
+    } else if match_def_path(cx, adt.did(), &paths::STORAGE_DOUBLE_MAP) {
+        Some(substs.type_at(5))
+    } else if match_def_path(cx, adt.did(), &paths::STORAGE_N_MAP) {
+        Some(substs.type_at(2))
+    } else {
+        None
+    }
+}
+
+// The prefix struct `#[pallet::storage]` generates for the storage item, which identifies it
+// regardless of the generics it is used with.
+pub fn storage_prefix(storage: Ty<'_>) -> Option<DefId> {
+    if let ty::Adt(_, substs) = storage.kind()
+        && let Some(prefix) = substs.types().next()
+        && let ty::Adt(prefix_adt, _) = prefix.kind() {
+        Some(prefix_adt.did())
+    } else {
+        None
+    }
+}
+
+// Name of the storage item as written in the pallet. Falls back to the full type if the prefix was
+// not generated by the pallet macro.
+pub fn storage_name(cx: &LateContext<'_>, storage: Ty<'_>) -> String {
+    if let ty::Adt(_, substs) = storage.kind()
+        && let Some(prefix) = substs.types().next()
+        && let ty::Adt(prefix_adt, _) = prefix.kind()
+        && let Some(name) = cx.tcx.item_name(prefix_adt.did()).as_str().strip_prefix(GENERATED_PREFIX) {
+        return name.to_string();
+    }
+    storage.to_string()
+}
@@ -0,0 +1,112 @@
+use substrace_utils::ty::{is_type_diagnostic_item, is_type_lang_item};
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir::def_id::DefId;
+use rustc_hir::LangItem;
+use rustc_lint::LateContext;
+use rustc_middle::ty::{self, ParamEnv, Ty};
+use rustc_span::sym;
+use rustc_span::symbol::Symbol;
+
+/// The collections of `std` and `alloc` that can hold any number of items, besides `String`.
+const UNBOUNDED_COLLECTIONS: [Symbol; 8] = [
+    sym::Vec,
+    sym::VecDeque,
+    sym::LinkedList,
+    sym::BinaryHeap,
+    sym::BTreeMap,
+    sym::BTreeSet,
+    sym::HashMap,
+    sym::HashSet,
+];
+
+/// The bounded collections of `frame_support`, `sp_runtime` and `bounded_collections`. They moved
+/// between crates over time, so they are matched by name.
+const BOUNDED_COLLECTIONS: [&str; 5] = [
+    "BoundedVec",
+    "WeakBoundedVec",
+    "BoundedBTreeMap",
+    "BoundedBTreeSet",
+    "BoundedSlice",
+];
+
+/// The crates that define the `Get` trait.
+pub const GET_CRATES: [&str; 2] = ["frame_support", "sp_core"];
+
+/// The `Get` implementations of `frame_support` and `sp_core` for constant bounds.
+const CONST_BOUNDS: [&str; 3] = ["ConstU32", "ConstU64", "ConstU128"];
+
+/// Bounds and limits from which a number of items is unbounded in practice: even one byte per item
+/// is more than fits in the proof of a block.
+pub const PRACTICALLY_UNBOUNDED: u128 = 1 << 24;
+
+/// A collection without a maximum length.
+pub struct Unbounded<'tcx> {
+    /// The collection.
+    pub ty: Ty<'tcx>,
+    /// The field of a type of the crate that holds the collection, if it is nested in one.
+    pub field: Option<DefId>,
+    /// The bound of a bounded collection that is too large to limit it, like `ConstU32<{u32::MAX}>`.
+    pub bound: Option<Ty<'tcx>>,
+}
+
+// Finds a collection without a maximum length in `ty`, walking the fields of the types of the crate
+// and the generic arguments of other types, like `Option<Vec<u8>>`.
+pub fn find_unbounded<'tcx>(cx: &LateContext<'tcx>, ty: Ty<'tcx>) -> Option<Unbounded<'tcx>> {
+    walk(cx, ty, &mut FxHashSet::default())
+}
+
+fn walk<'tcx>(cx: &LateContext<'tcx>, ty: Ty<'tcx>, visited: &mut FxHashSet<Ty<'tcx>>) -> Option<Unbounded<'tcx>> {
+    if !visited.insert(ty) {
+        return None;
+    }
+    let unbounded = |bound| Unbounded { ty, field: None, bound };
+    match ty.kind() {
+        ty::Str | ty::Slice(_) => Some(unbounded(None)),
+        ty::Adt(..)
+            if is_type_lang_item(cx, ty, LangItem::String)
+                || UNBOUNDED_COLLECTIONS.iter().any(|&collection| is_type_diagnostic_item(cx, ty, collection)) =>
+        {
+            Some(unbounded(None))
+        },
+        ty::Adt(adt, substs) if BOUNDED_COLLECTIONS.contains(&cx.tcx.item_name(adt.did()).as_str()) => {
+            // The bound is the last type argument, the items can still be unbounded themselves.
+            match substs.types().last() {
+                Some(bound) if is_practically_unbounded(cx, bound) => Some(unbounded(Some(bound))),
+                _ => substs.types().find_map(|ty| walk(cx, ty, visited)),
+            }
+        },
+        ty::Adt(adt, substs) if adt.did().is_local() => adt.all_fields().find_map(|field| {
+            let unbounded = walk(cx, field.ty(cx.tcx, substs), visited)?;
+            Some(Unbounded {
+                field: unbounded.field.or(Some(field.did)),
+                ..unbounded
+            })
+        }),
+        ty::Adt(_, substs) => substs.types().find_map(|ty| walk(cx, ty, visited)),
+        ty::Tuple(tys) => tys.iter().find_map(|ty| walk(cx, ty, visited)),
+        ty::Array(ty, _) | ty::Ref(_, ty, _) => walk(cx, *ty, visited),
+        _ => None,
+    }
+}
+
+// Returns the bound of a bounded collection, like `T::MaxMembers` in `BoundedVec<T::AccountId,
+// T::MaxMembers>`, also behind references.
+pub fn collection_bound<'tcx>(cx: &LateContext<'tcx>, ty: Ty<'tcx>) -> Option<Ty<'tcx>> {
+    match ty.peel_refs().kind() {
+        ty::Adt(adt, substs) if BOUNDED_COLLECTIONS.contains(&cx.tcx.item_name(adt.did()).as_str()) => substs.types().last(),
+        _ => None,
+    }
+}
+
+// Whether `bound` is a constant bound, like `ConstU32<N>`, that is too large to limit a collection.
+fn is_practically_unbounded<'tcx>(cx: &LateContext<'tcx>, bound: Ty<'tcx>) -> bool {
+    if let ty::Adt(adt, substs) = bound.kind()
+        && CONST_BOUNDS.contains(&cx.tcx.item_name(adt.did()).as_str())
+        && let Some(value) = substs.consts().next()
+        && let Some(value) = value.try_eval_bits(cx.tcx, ParamEnv::empty(), value.ty())
+    {
+        value >= PRACTICALLY_UNBOUNDED
+    } else {
+        false
+    }
+}
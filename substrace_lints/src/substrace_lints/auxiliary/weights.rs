@@ -0,0 +1,231 @@
+use super::call_graph::called_fn;
+use substrace_utils::consts::{constant, FullInt};
+use substrace_utils::visitors::for_each_expr_with_closures;
+use rustc_hir as hir;
+use rustc_hir::def::{DefKind, Res};
+use rustc_hir::def_id::DefId;
+use rustc_lint::LateContext;
+use rustc_middle::ty::{self, TypeckResults};
+use rustc_span::symbol::{Ident, Symbol};
+use std::ops::ControlFlow;
+
+/// The crates of the weight types and of `T::DbWeight::get()`, whose functions don't depend on
+/// anything but their arguments.
+const WEIGHT_CRATES: [&str; 4] = ["frame_support", "sp_weights", "sp_core", "core"];
+
+/// The methods that do work for every element of the collection or iterator they are called on.
+/// `min`, `max` and `contains` are left out, as they are also methods of integers and ranges.
+pub const ITERATING_METHODS: [&str; 16] = [
+    "for_each",
+    "try_for_each",
+    "collect",
+    "sum",
+    "product",
+    "count",
+    "fold",
+    "try_fold",
+    "all",
+    "any",
+    "find",
+    "position",
+    "last",
+    "retain",
+    "sort",
+    "dedup",
+];
+
+/// A weight expression, evaluated as far as it can be at compile time.
+#[derive(Clone, Copy, Debug)]
+pub struct WeightValue {
+    /// Whether the weight is the same for every call, as it depends neither on the arguments of the
+    /// call nor on benchmarked weight functions.
+    pub constant: bool,
+    /// The `ref_time`, or the whole weight on FRAME versions where it is a `u64`.
+    pub ref_time: Option<u128>,
+    /// The `proof_size`, which is `None` if it is unknown or the weight is a `u64`.
+    pub proof_size: Option<u128>,
+}
+
+impl WeightValue {
+    const UNKNOWN: Self = Self {
+        constant: false,
+        ref_time: None,
+        proof_size: None,
+    };
+
+    fn constant(ref_time: Option<u128>, proof_size: Option<u128>) -> Self {
+        Self {
+            constant: true,
+            ref_time,
+            proof_size,
+        }
+    }
+}
+
+/// The weight of a `#[pallet::weight(..)]` expression, without the `DispatchClass` and `Pays` of
+/// `(weight, DispatchClass::Operational)` or `(weight, class, Pays::No)`.
+pub fn weight_expr<'a, 'tcx>(expr: &'a hir::Expr<'tcx>) -> &'a hir::Expr<'tcx> {
+    match expr.kind {
+        hir::ExprKind::Tup([weight, ..]) => weight,
+        _ => expr,
+    }
+}
+
+/// Evaluates a weight, like `Weight::from_parts(10_000, 0)`, `T::DbWeight::get().reads(1)` or
+/// `T::WeightInfo::transfer()`.
+pub fn eval_weight<'tcx>(cx: &LateContext<'tcx>, typeck: &TypeckResults<'tcx>, expr: &hir::Expr<'_>) -> WeightValue {
+    if let Some(value) = constant(cx, typeck, expr).and_then(|(value, _)| value.int_value(cx, typeck.expr_ty(expr))) {
+        let value = match value {
+            FullInt::U(value) => Some(value),
+            FullInt::S(value) => u128::try_from(value).ok(),
+        };
+        return WeightValue::constant(value, None);
+    }
+
+    match expr.kind {
+        hir::ExprKind::Binary(op, lhs, rhs) => {
+            let (lhs, rhs) = (eval_weight(cx, typeck, lhs), eval_weight(cx, typeck, rhs));
+            let combine = |lhs: Option<u128>, rhs: Option<u128>| match op.node {
+                hir::BinOpKind::Add => lhs?.checked_add(rhs?),
+                hir::BinOpKind::Sub => lhs?.checked_sub(rhs?),
+                hir::BinOpKind::Mul => lhs?.checked_mul(rhs?),
+                _ => None,
+            };
+            WeightValue {
+                constant: lhs.constant && rhs.constant,
+                ref_time: combine(lhs.ref_time, rhs.ref_time),
+                proof_size: combine(lhs.proof_size, rhs.proof_size),
+            }
+        },
+        hir::ExprKind::Cast(inner, _) | hir::ExprKind::DropTemps(inner) | hir::ExprKind::AddrOf(_, _, inner) => {
+            eval_weight(cx, typeck, inner)
+        },
+        hir::ExprKind::Tup([weight, ..]) => eval_weight(cx, typeck, weight),
+        hir::ExprKind::Block(
+            hir::Block {
+                stmts: [],
+                expr: Some(inner),
+                ..
+            },
+            _,
+        ) => eval_weight(cx, typeck, inner),
+        hir::ExprKind::Path(ref qpath) => match typeck.qpath_res(qpath, expr.hir_id) {
+            Res::Def(DefKind::Const | DefKind::AssocConst, _) => WeightValue::constant(None, None),
+            _ => WeightValue::UNKNOWN,
+        },
+        hir::ExprKind::Call(_, args) | hir::ExprKind::MethodCall(_, _, args, _) => {
+            let Some((fn_id, _)) = called_fn(typeck, expr) else { return WeightValue::UNKNOWN };
+            let args: Vec<WeightValue> = match expr.kind {
+                hir::ExprKind::MethodCall(_, receiver, ..) => [receiver].into_iter().chain(args).collect::<Vec<_>>(),
+                _ => args.iter().collect(),
+            }
+            .into_iter()
+            .map(|arg| eval_weight(cx, typeck, arg))
+            .collect();
+            let krate = cx.tcx.trait_of_item(fn_id).unwrap_or(fn_id).krate;
+            let constant = WEIGHT_CRATES.contains(&cx.tcx.crate_name(krate).as_str()) && args.iter().all(|arg| arg.constant);
+            let arg = |idx: usize| args.get(idx).copied().unwrap_or(WeightValue::UNKNOWN);
+
+            let (ref_time, proof_size) = if is_weight_fn(cx, fn_id) {
+                match cx.tcx.item_name(fn_id).as_str() {
+                    "zero" => (Some(0), Some(0)),
+                    "from_parts" => (arg(0).ref_time, arg(1).ref_time),
+                    "from_ref_time" => (arg(0).ref_time, Some(0)),
+                    "from_proof_size" => (Some(0), arg(0).ref_time),
+                    "saturating_add" | "add" => (
+                        arg(0).ref_time.zip(arg(1).ref_time).map(|(lhs, rhs)| lhs.saturating_add(rhs)),
+                        arg(0).proof_size.zip(arg(1).proof_size).map(|(lhs, rhs)| lhs.saturating_add(rhs)),
+                    ),
+                    "set_proof_size" => (arg(0).ref_time, arg(1).ref_time),
+                    _ => (None, None),
+                }
+            } else {
+                (None, None)
+            };
+            WeightValue {
+                constant,
+                ref_time,
+                proof_size,
+            }
+        },
+        _ => WeightValue::UNKNOWN,
+    }
+}
+
+/// The storage reads and writes a constant weight accounts for with `T::DbWeight::get()`, like
+/// `(2, 1)` for `10_000 + T::DbWeight::get().reads_writes(2, 1)`. `None` if the weight isn't
+/// constant or the counts aren't known.
+pub fn db_weight<'tcx>(cx: &LateContext<'tcx>, typeck: &TypeckResults<'tcx>, expr: &'tcx hir::Expr<'tcx>) -> Option<(u128, u128)> {
+    if !eval_weight(cx, typeck, expr).constant {
+        return None;
+    }
+    let (mut reads, mut writes) = (0, 0);
+    let unknown = for_each_expr_with_closures(cx, expr, |e| {
+        if let hir::ExprKind::MethodCall(path, receiver, args, _) = e.kind
+            && let ty::Adt(adt, _) = typeck.expr_ty(receiver).peel_refs().kind()
+            && cx.tcx.item_name(adt.did()).as_str() == "RuntimeDbWeight"
+        {
+            // Integers evaluate to their value as the `ref_time`.
+            let arg = |idx: usize| args.get(idx).and_then(|arg| eval_weight(cx, typeck, arg).ref_time);
+            match (path.ident.as_str(), arg(0), arg(1)) {
+                ("reads", Some(r), _) => reads += r,
+                ("writes", Some(w), _) => writes += w,
+                ("reads_writes", Some(r), Some(w)) => {
+                    reads += r;
+                    writes += w;
+                },
+                _ => return ControlFlow::Break(()),
+            }
+        }
+        ControlFlow::Continue(())
+    });
+    unknown.is_none().then_some((reads, writes))
+}
+
+// Whether `fn_id` is a function of the `Weight` struct, which replaced the `u64` weight in
+// `polkadot-v0.9.29`.
+fn is_weight_fn(cx: &LateContext<'_>, fn_id: DefId) -> bool {
+    cx.tcx.impl_of_method(fn_id).map_or(false, |impl_id| {
+        matches!(cx.tcx.type_of(impl_id).kind(), ty::Adt(adt, _) if cx.tcx.item_name(adt.did()).as_str() == "Weight")
+    })
+}
+
+/// The benchmarked weight function of an extrinsic, like `T::WeightInfo::transfer`.
+pub struct WeightInfoFn {
+    /// The path to call it by, like `T::WeightInfo::transfer`.
+    pub path: String,
+    /// The function of the `WeightInfo` trait, if it has one for the extrinsic.
+    pub def_id: Option<DefId>,
+}
+
+/// Finds the weight function named `name` in the `WeightInfo` associated type of the `Config`
+/// that the extrinsic `def_id` is generic over.
+pub fn weight_info_fn(cx: &LateContext<'_>, def_id: DefId, name: Symbol) -> Option<WeightInfoFn> {
+    cx.tcx.param_env(def_id).caller_bounds().iter().find_map(|predicate| {
+        let ty::PredicateKind::Trait(trait_predicate) = predicate.kind().skip_binder() else { return None };
+        let config_id = trait_predicate.def_id();
+        let ty::Param(param) = trait_predicate.self_ty().kind() else { return None };
+        if !config_id.is_local() || cx.tcx.item_name(config_id).as_str() != "Config" {
+            return None;
+        }
+        let weight_info = cx.tcx.associated_items(config_id).find_by_name_and_kind(
+            cx.tcx,
+            Ident::from_str("WeightInfo"),
+            ty::AssocKind::Type,
+            config_id,
+        )?;
+
+        // The trait bounding `WeightInfo`, which has a function for every benchmark.
+        let fn_id = cx.tcx.explicit_item_bounds(weight_info.def_id).iter().find_map(|(predicate, _)| {
+            let ty::PredicateKind::Trait(bound) = predicate.kind().skip_binder() else { return None };
+            cx.tcx
+                .associated_items(bound.def_id())
+                .find_by_name_and_kind(cx.tcx, Ident::with_dummy_span(name), ty::AssocKind::Fn, bound.def_id())
+                .map(|item| item.def_id)
+        });
+        Some(WeightInfoFn {
+            path: format!("{}::WeightInfo::{name}", param.name),
+            def_id: fn_id,
+        })
+    })
+}
@@ -0,0 +1,139 @@
+use super::auxiliary::pallet::{pallets, PalletCache, PalletCall};
+use super::auxiliary::weights::{eval_weight, weight_expr, weight_info_fn};
+use substrace_utils::diagnostics::span_lint_hir_and_then;
+use rustc_errors::{Applicability, Diagnostic};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_session::{declare_tool_lint, impl_lint_pass};
+use rustc_span::Span;
+
+declare_substrace_lint! {
+    /// ### What it does
+    /// Checks the `#[pallet::weight(..)]` of every dispatchable for weights that are zero, like
+    /// `#[pallet::weight(0)]` or `Weight::zero()`, that are constant, like
+    /// `Weight::from_parts(10_000, 0)` or `T::DbWeight::get().reads(1)`, or that have a zero
+    /// `proof_size`. When the `Config` of the pallet has a `WeightInfo` type, it suggests the
+    /// benchmarked weight function of the call.
+    ///
+    /// ### Why is this bad?
+    /// The weight is charged before the call runs, so it must cover the worst case of the work the
+    /// call does. A zero weight lets anyone fill blocks for free, and a constant one is rarely
+    /// accurate and isn't updated when the call changes. Parachains also limit the size of the
+    /// storage proof of a block, which a zero `proof_size` doesn't account for.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// #[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+    /// pub fn transfer(origin: OriginFor<T>, dest: T::AccountId, amount: T::Balance) -> DispatchResult
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// #[pallet::weight(T::WeightInfo::transfer())]
+    /// pub fn transfer(origin: OriginFor<T>, dest: T::AccountId, amount: T::Balance) -> DispatchResult
+    /// ```
+    pub CONSTANT_WEIGHTS,
+    weights,
+    "Dispatchables must have a benchmarked weight, not a constant or zero one."
+}
+
+impl_lint_pass!(ConstantWeights => [CONSTANT_WEIGHTS]);
+
+#[derive(Clone)]
+pub struct ConstantWeights {
+    pallets: PalletCache,
+}
+
+impl ConstantWeights {
+    pub fn new(pallets: PalletCache) -> Self {
+        Self { pallets }
+    }
+}
+
+impl<'tcx> LateLintPass<'tcx> for ConstantWeights {
+    fn check_crate_post(&mut self, cx: &LateContext<'tcx>) {
+        for call in pallets(cx, &self.pallets).iter().flat_map(|pallet| &pallet.calls) {
+            let (Some(def_id), Some(weight_id)) = (call.def_id.as_local(), call.weight) else { continue };
+            let expr = weight_expr(cx.tcx.hir().expect_expr(weight_id));
+            if expr.span.from_expansion() {
+                continue;
+            }
+            let typeck = cx.tcx.typeck(weight_id.owner.def_id);
+            let weight = eval_weight(cx, typeck, expr);
+            let name = call.name;
+            // A `u64` weight, from before `Weight` had a `proof_size`.
+            let legacy = typeck.expr_ty(expr).is_integral();
+
+            let (msg, note) = if weight.constant
+                && weight.ref_time == Some(0)
+                && (legacy || weight.proof_size == Some(0))
+            {
+                (
+                    format!("substrace: `{name}` has a zero weight"),
+                    "anyone can call it for free, and fill blocks with it",
+                )
+            } else if weight.constant {
+                (
+                    format!("substrace: `{name}` has a constant weight"),
+                    "the weight must cover the worst case of the work the call does, which a constant is rarely accurate for, and it isn't updated when the call changes",
+                )
+            } else if weight.proof_size == Some(0) {
+                (
+                    format!("substrace: the weight of `{name}` has a zero `proof_size`"),
+                    "the size of the storage proof of a block is limited on parachains, which this weight doesn't account for",
+                )
+            } else {
+                continue;
+            };
+
+            span_lint_hir_and_then(
+                cx,
+                CONSTANT_WEIGHTS,
+                cx.tcx.hir().local_def_id_to_hir_id(def_id),
+                expr.span,
+                &msg,
+                |diag| {
+                    diag.note(note);
+                    if weight.constant && !legacy && weight.proof_size == Some(0) && weight.ref_time != Some(0) {
+                        diag.note("its `proof_size` is zero as well");
+                    }
+                    suggest_weight_info(cx, diag, call, expr.span);
+                },
+            );
+        }
+    }
+}
+
+// Suggests the benchmarked weight function of `call`, like `T::WeightInfo::transfer()`.
+fn suggest_weight_info(cx: &LateContext<'_>, diag: &mut Diagnostic, call: &PalletCall, span: Span) {
+    let name = call.name;
+    match weight_info_fn(cx, call.def_id, name) {
+        Some(weight_info) => match weight_info.def_id {
+            Some(fn_id) => {
+                let args = cx.tcx.fn_arg_names(fn_id);
+                let applicability = if args.is_empty() {
+                    Applicability::MaybeIncorrect
+                } else {
+                    Applicability::HasPlaceholders
+                };
+                let args: Vec<String> = args.iter().map(ToString::to_string).collect();
+                diag.span_suggestion(
+                    span,
+                    "use the benchmarked weight",
+                    format!("{}({})", weight_info.path, args.join(", ")),
+                    applicability,
+                );
+            },
+            None => {
+                diag.help(format!(
+                    "add a benchmark for `{name}`, and use the generated `{}` as its weight",
+                    weight_info.path
+                ));
+            },
+        },
+        None => {
+            diag.help(format!(
+                "add a benchmark for `{name}`, and use the generated weight through a `WeightInfo` type in `Config`"
+            ));
+        },
+    }
+}
@@ -0,0 +1,367 @@
+use super::auxiliary::call_graph::{called_fn, resolve};
+use super::auxiliary::pallet::{pallets, PalletCache};
+use super::auxiliary::paths;
+use super::auxiliary::storage::{storage_call, storage_keys, storage_name};
+use super::auxiliary::weights::{db_weight, ITERATING_METHODS};
+use substrace_utils::diagnostics::span_lint_hir_and_then;
+use substrace_utils::{match_def_path, path_to_local};
+use substrace_utils::source::snippet;
+use substrace_utils::visitors::for_each_expr_with_closures;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexMap, FxIndexSet};
+use rustc_errors::MultiSpan;
+use rustc_hir as hir;
+use rustc_hir::def_id::LocalDefId;
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::ty::{self, Ty};
+use rustc_session::{declare_tool_lint, impl_lint_pass};
+use rustc_span::Span;
+use std::ops::ControlFlow;
+
+declare_substrace_lint! {
+    /// ### What it does
+    /// Counts the storage items each dispatchable reads and writes, in its body, the functions it
+    /// calls and the closures passed to `mutate`, and compares the counts with its weight when the
+    /// weight is written as `T::DbWeight::get().reads_writes(r, w)` (or `reads(r)` and
+    /// `writes(w)`) or is another constant. Each key of a storage item is counted once, however
+    /// often it is accessed, like the storage overlay does. Keys are told apart by the expressions
+    /// they are written with, and a key passed to a function is followed back to the argument the
+    /// dispatchable passed for it. Accesses in loops, in closures passed to iterator methods like
+    /// `for_each` and for every key of a map make the counts a lower bound, so then only weights
+    /// with too few reads or writes are reported.
+    ///
+    /// Storage accessed through other pallets, like `T::Currency`, isn't counted, so allow this
+    /// lint if the weight accounts for it.
+    ///
+    /// ### Why is this bad?
+    /// A weight with too few reads or writes lets callers do more work than they pay for, and one
+    /// with too many makes them overpay. Both are easy to miss when the call changes later.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+    /// pub fn leave(origin: OriginFor<T>) -> DispatchResult {
+    ///     let who = ensure_signed(origin)?;
+    ///     let deposit = Deposits::<T>::take(&who);
+    ///     Members::<T>::mutate(|members| members.retain(|member| *member != who));
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// #[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
+    /// ```
+    pub DB_WEIGHT_MISMATCH,
+    weights,
+    "The storage reads and writes in the weight of a dispatchable must match the storage it accesses."
+}
+
+impl_lint_pass!(DbWeightMismatch => [DB_WEIGHT_MISMATCH]);
+
+#[derive(Clone)]
+pub struct DbWeightMismatch {
+    pallets: PalletCache,
+}
+
+impl DbWeightMismatch {
+    pub fn new(pallets: PalletCache) -> Self {
+        Self { pallets }
+    }
+}
+
+/// The methods of the FRAME storage types that read storage.
+const READ_METHODS: [&str; 22] = [
+    "get",
+    "try_get",
+    "exists",
+    "contains_key",
+    "decode_len",
+    "take",
+    "mutate",
+    "mutate_exists",
+    "mutate_extant",
+    "try_mutate",
+    "try_mutate_exists",
+    "try_append",
+    "swap",
+    "translate",
+    "translate_values",
+    "iter",
+    "iter_keys",
+    "iter_values",
+    "iter_prefix",
+    "iter_prefix_values",
+    "drain",
+    "drain_prefix",
+];
+
+/// The methods of the FRAME storage types that write storage.
+const WRITE_METHODS: [&str; 22] = [
+    "put",
+    "set",
+    "insert",
+    "remove",
+    "kill",
+    "take",
+    "mutate",
+    "mutate_exists",
+    "mutate_extant",
+    "try_mutate",
+    "try_mutate_exists",
+    "append",
+    "try_append",
+    "swap",
+    "translate",
+    "translate_values",
+    "drain",
+    "drain_prefix",
+    "clear",
+    "clear_prefix",
+    "remove_all",
+    "remove_prefix",
+];
+
+/// The methods that access every key of a storage map, or every key with a prefix.
+const ALL_KEYS_METHODS: [&str; 12] = [
+    "translate",
+    "translate_values",
+    "iter",
+    "iter_keys",
+    "iter_values",
+    "iter_prefix",
+    "iter_prefix_values",
+    "drain",
+    "drain_prefix",
+    "clear",
+    "clear_prefix",
+    "remove_all",
+];
+
+/// How a dispatchable accesses a storage item.
+#[derive(Default)]
+struct Access {
+    /// The keys it is read with, as identified by `key_of`, or `""` for storage values and whole
+    /// maps.
+    read: FxIndexSet<String>,
+    /// The keys it is written with.
+    write: FxIndexSet<String>,
+    /// Whether it is accessed in a loop, or for every key of a map.
+    repeated: bool,
+    spans: Vec<(Span, &'static str)>,
+}
+
+impl<'tcx> LateLintPass<'tcx> for DbWeightMismatch {
+    fn check_crate_post(&mut self, cx: &LateContext<'tcx>) {
+        for call in pallets(cx, &self.pallets).iter().flat_map(|pallet| &pallet.calls) {
+            let (Some(def_id), Some(weight_id)) = (call.def_id.as_local(), call.weight) else { continue };
+            let weight = cx.tcx.hir().expect_expr(weight_id);
+            if weight.span.from_expansion() {
+                continue;
+            }
+            let Some((declared_reads, declared_writes)) = db_weight(cx, cx.tcx.typeck(weight_id.owner.def_id), weight) else { continue };
+
+            let accesses = storage_accesses(cx, def_id);
+            let reads = accesses.values().map(|access| access.read.len()).sum::<usize>() as u128;
+            let writes = accesses.values().map(|access| access.write.len()).sum::<usize>() as u128;
+            let repeated = accesses.values().any(|access| access.repeated);
+            let too_few = declared_reads < reads || declared_writes < writes;
+            let too_many = declared_reads > reads || declared_writes > writes;
+            if !too_few && (repeated || !too_many) {
+                continue;
+            }
+
+            let name = call.name;
+            let declared = if declared_reads == 0 && declared_writes == 0 {
+                "no storage access".to_owned()
+            } else {
+                format!("{} and {}", count(declared_reads, "read"), count(declared_writes, "write"))
+            };
+            span_lint_hir_and_then(
+                cx,
+                DB_WEIGHT_MISMATCH,
+                cx.tcx.hir().local_def_id_to_hir_id(def_id),
+                weight.span,
+                &format!(
+                    "substrace: the weight of `{name}` accounts for {declared}, but it reads {} and writes {}",
+                    count(reads, "storage item"),
+                    count(writes, "storage item")
+                ),
+                |diag| {
+                    if !accesses.is_empty() {
+                        let mut spans = MultiSpan::from_spans(accesses.values().flat_map(|access| &access.spans).map(|&(span, _)| span).collect());
+                        for &(span, label) in accesses.values().flat_map(|access| &access.spans) {
+                            spans.push_span_label(span, label);
+                        }
+                        let items = |accessed: fn(&Access) -> &FxIndexSet<String>| {
+                            let items: Vec<String> = accesses
+                                .iter()
+                                .filter(|(_, access)| !accessed(access).is_empty())
+                                .map(|(name, _)| format!("`{name}`"))
+                                .collect();
+                            if items.is_empty() { "nothing".to_owned() } else { items.join(", ") }
+                        };
+                        diag.span_note(
+                            spans,
+                            format!("it reads {} and writes {}", items(|access| &access.read), items(|access| &access.write)),
+                        );
+                    }
+                    if repeated {
+                        diag.note("some storage items are accessed in a loop or for every key, which can take more reads and writes than counted here");
+                    }
+                    diag.help(format!(
+                        "account for every storage item it reads and writes, e.g. with `T::DbWeight::get().reads_writes({reads}, {writes})`, or use a benchmarked weight"
+                    ));
+                },
+            );
+        }
+    }
+}
+
+// Finds the storage items the dispatchable `def_id` accesses, directly or through the functions it
+// calls, by the name of the storage item and then by the keys it is accessed with.
+fn storage_accesses(cx: &LateContext<'_>, def_id: LocalDefId) -> FxIndexMap<String, Access> {
+    let mut accesses: FxIndexMap<String, Access> = FxIndexMap::default();
+    collect_accesses(cx, def_id, Vec::new(), false, &mut FxHashSet::default(), &mut accesses);
+    accesses
+}
+
+// Adds the storage accesses in the body of `def_id` and in the functions it calls, where `args` are
+// the keys the caller passes for the parameters. Keys are identified from the dispatchable's side:
+// a parameter stands for the key its caller passed, and any other key expression for its source in
+// the function it is written in.
+fn collect_accesses(
+    cx: &LateContext<'_>,
+    def_id: LocalDefId,
+    args: Vec<String>,
+    in_loop: bool,
+    visited: &mut FxHashSet<(LocalDefId, Vec<String>, bool)>,
+    accesses: &mut FxIndexMap<String, Access>,
+) {
+    if !visited.insert((def_id, args.clone(), in_loop)) {
+        return;
+    }
+    let Some(body_id) = cx.tcx.hir().maybe_body_owned_by(def_id) else { return };
+    let body = cx.tcx.hir().body(body_id);
+    let typeck = cx.tcx.typeck(def_id);
+    let params: FxHashMap<hir::HirId, String> = body
+        .params
+        .iter()
+        .zip(args)
+        .filter_map(|(param, key)| match param.pat.kind {
+            hir::PatKind::Binding(_, id, ..) => Some((id, key)),
+            _ => None,
+        })
+        .collect();
+
+    let mut calls = Vec::new();
+    for_each_expr_with_closures(cx, body.value, |expr| {
+        let Some((fn_id, substs)) = called_fn(typeck, expr) else { return ControlFlow::<()>::Continue(()) };
+        if let Some(call) = storage_call(cx, fn_id, substs) {
+            let method = call.method.as_str();
+            let (read, write) = (READ_METHODS.contains(&method), WRITE_METHODS.contains(&method));
+            if read || write {
+                let all_keys = ALL_KEYS_METHODS.contains(&method);
+                let key = if all_keys {
+                    String::new()
+                } else {
+                    storage_key(cx, def_id, &params, call.storage, expr)
+                };
+                let access = accesses.entry(storage_name(cx, call.storage)).or_default();
+                if read {
+                    access.read.insert(key.clone());
+                }
+                if write {
+                    access.write.insert(key);
+                }
+                access.repeated |= all_keys || in_loop || is_in_loop(cx, expr.hir_id);
+                let label = match (read, write) {
+                    (true, true) => "read and written",
+                    (true, false) => "read",
+                    _ => "written",
+                };
+                if !access.spans.contains(&(expr.span, label)) {
+                    access.spans.push((expr.span, label));
+                }
+            }
+        } else if let Some(callee) = resolve(cx, def_id, fn_id, substs).as_local() {
+            let args = match expr.kind {
+                hir::ExprKind::Call(_, args) => args.iter().collect(),
+                hir::ExprKind::MethodCall(_, receiver, args, _) => [receiver].into_iter().chain(args).collect(),
+                _ => Vec::new(),
+            };
+            let args = args.into_iter().map(|arg| key_of(cx, def_id, &params, arg)).collect();
+            calls.push((callee, args, in_loop || is_in_loop(cx, expr.hir_id)));
+        }
+        ControlFlow::<()>::Continue(())
+    });
+
+    for (callee, args, in_loop) in calls {
+        collect_accesses(cx, callee, args, in_loop, visited, accesses);
+    }
+}
+
+// Returns the keys of a storage call in the body of `def_id`, like `who` in
+// `Deposits::<T>::insert(&who, amount)`, or `""` for a storage value.
+fn storage_key<'tcx>(
+    cx: &LateContext<'tcx>,
+    def_id: LocalDefId,
+    params: &FxHashMap<hir::HirId, String>,
+    storage: Ty<'tcx>,
+    call: &hir::Expr<'_>,
+) -> String {
+    let args = match call.kind {
+        hir::ExprKind::Call(_, args) | hir::ExprKind::MethodCall(_, _, args, _) => args,
+        _ => return String::new(),
+    };
+    let keys = match storage.kind() {
+        // The keys of a `StorageNMap` are passed as one tuple.
+        ty::Adt(adt, _) if match_def_path(cx, adt.did(), &paths::STORAGE_N_MAP) => 1,
+        _ => storage_keys(cx, storage).len(),
+    };
+    args.iter()
+        .take(keys)
+        .map(|arg| key_of(cx, def_id, params, arg))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// Identifies the key `arg` in the body of `def_id`: the caller's key for a parameter, or else the
+// source of the expression, without borrows and clones, in `def_id`.
+fn key_of(cx: &LateContext<'_>, def_id: LocalDefId, params: &FxHashMap<hir::HirId, String>, arg: &hir::Expr<'_>) -> String {
+    let mut arg = arg;
+    loop {
+        match arg.kind {
+            hir::ExprKind::AddrOf(_, _, inner) | hir::ExprKind::Unary(hir::UnOp::Deref, inner) => arg = inner,
+            hir::ExprKind::MethodCall(path, receiver, [], _) if path.ident.name.as_str() == "clone" => arg = receiver,
+            _ => break,
+        }
+    }
+    if let Some(key) = path_to_local(arg).and_then(|id| params.get(&id)) {
+        return key.clone();
+    }
+    let source: String = snippet(cx, arg.span, "..").split_whitespace().collect();
+    format!("{}::{source}", cx.tcx.def_path_str(def_id.to_def_id()))
+}
+
+// Whether `hir_id` is in a loop, or in a closure passed to an iterator method like `for_each`.
+fn is_in_loop(cx: &LateContext<'_>, hir_id: hir::HirId) -> bool {
+    let hir = cx.tcx.hir();
+    hir.parent_iter(hir_id)
+        .map_while(|(id, node)| match node {
+            hir::Node::Item(_) | hir::Node::ImplItem(_) | hir::Node::TraitItem(_) => None,
+            node => Some((id, node)),
+        })
+        .any(|(id, node)| match node {
+            hir::Node::Expr(hir::Expr { kind: hir::ExprKind::Loop(..), .. }) => true,
+            hir::Node::Expr(hir::Expr { kind: hir::ExprKind::Closure(..), .. }) => matches!(
+                hir.find(hir.get_parent_node(id)),
+                Some(hir::Node::Expr(hir::Expr { kind: hir::ExprKind::MethodCall(path, _, args, _), .. }))
+                    if ITERATING_METHODS.contains(&path.ident.as_str()) && args.iter().any(|arg| arg.hir_id == id)
+            ),
+            _ => false,
+        })
+}
+
+fn count(n: u128, noun: &str) -> String {
+    if n == 1 { format!("1 {noun}") } else { format!("{n} {noun}s") }
+}
@@ -1,19 +1,35 @@
-use rustc_lint::{LateContext, LateLintPass};
-use rustc_session::{declare_lint, impl_lint_pass};
-use serde::{Deserialize, Serialize};
-use std::str;
-
-use grep::printer::JSON;
-use grep_pcre2::RegexMatcher;
-use grep::searcher::{BinaryDetection, SearcherBuilder};
-use std::error::Error;
-use std::io::BufWriter;
-use walkdir::WalkDir;
-
-declare_lint! {
+use substrace_utils::diagnostics::{span_lint_and_help, span_lint_and_sugg};
+use substrace_utils::source::snippet_opt;
+use rustc_ast::ast::{Attribute, MetaItem, MetaItemKind, NestedMetaItem};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_errors::Applicability;
+use rustc_lint::{EarlyContext, EarlyLintPass};
+use rustc_session::{declare_tool_lint, impl_lint_pass};
+use rustc_span::source_map::Span;
+use rustc_span::sym;
+
+declare_substrace_lint! {
+    /// ### What it does
+    /// Checks that code behind `#[cfg(feature = "runtime-benchmarks")]` is also compiled for `test`.
+    ///
+    /// ### Why is this bad?
+    /// Benchmarks that are not compiled in tests are not run by `cargo test`, so broken benchmarks, and
+    /// the weights generated from them, go unnoticed.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// #[cfg(feature = "runtime-benchmarks")]
+    /// mod benchmarking;
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// #[cfg(any(feature = "runtime-benchmarks", test))]
+    /// mod benchmarking;
+    /// ```
     pub ENABLE_SINGLEPASS_BENCHMARKS,
-    Warn,
-    "TODO"
+    weights,
+    "Code enabled by the `runtime-benchmarks` feature should also be enabled in tests, so the benchmarks are run as tests."
 }
 
 impl_lint_pass!(EnableSinglepassBenchmarks => [ENABLE_SINGLEPASS_BENCHMARKS]);
@@ -21,86 +37,188 @@ impl_lint_pass!(EnableSinglepassBenchmarks => [ENABLE_SINGLEPASS_BENCHMARKS]);
 #[derive(Clone, Default)]
 pub struct EnableSinglepassBenchmarks;
 
-#[derive(Debug, Deserialize, Serialize)]
-struct MyGrepResult {
-    #[serde(rename = "type")] 
-    type_name: String,
-    data: serde_json::Value,
+const BENCHMARKS_FEATURE: &str = "runtime-benchmarks";
+
+// Predicates with more unknown configuration options than this are not checked.
+const MAX_OPTIONS: usize = 12;
+
+/// A parsed `cfg` predicate.
+enum Cfg {
+    /// `feature = "runtime-benchmarks"`
+    Benchmarks,
+    /// `test`
+    Test,
+    /// Any other configuration option, indexed into the list of options of the predicate.
+    Option(usize),
+    All(Vec<Cfg>),
+    Any(Vec<Cfg>),
+    Not(Box<Cfg>),
 }
 
-//TODO: Implement auto-fix.
-impl<'tcx> LateLintPass<'tcx> for EnableSinglepassBenchmarks {
-
-    fn check_crate(&mut self, cx: &LateContext<'tcx>) {
-
-        // See this pattern in action: https://regex101.com/r/jd6CX1/4
-        let ripgrep_output = search(r#"^(#\[cfg\(any\(((?!test, )[ \w\-="]*, )*feature *= *"runtime-benchmarks"((?!, test), [ \w\-="]*)*\)\)\])|^(#\[cfg\(feature *= *"runtime-benchmarks"\)\])"#, ".").expect("error while running libripgrep");
-
-        for line in ripgrep_output.lines() {
-            if let Ok(json_line) = serde_json::from_str::<MyGrepResult>(line)
-                && json_line.type_name == "match"
-                && let Some(found_text) = json_line.data["lines"]["text"].as_str()
-                && let Some(found_line) = json_line.data["line_number"].as_u64()
-                && let Some(found_file_name) = json_line.data["path"]["text"].as_str() {
-
-                let suggested_text: String = create_suggested_text(found_text);
-            
-                let warning_message = format!("substrace: benchmarks not run in tests.
-Found:
-{}
-at line {} in {}. Suggested replacement:
-{}", found_text, found_line, found_file_name, suggested_text);
-
-                //TODO: hir_id does not matter. Isn't there a lint emitter without it? Currently an arbitrary hir_id is used.
-                // Emit lint
-                cx.tcx.struct_lint_node(ENABLE_SINGLEPASS_BENCHMARKS, cx.last_node_with_lint_attrs, warning_message, |diag| diag)
-            }
+impl Cfg {
+    fn eval(&self, benchmarks: bool, test: bool, options: u32) -> bool {
+        match self {
+            Self::Benchmarks => benchmarks,
+            Self::Test => test,
+            Self::Option(i) => options & (1 << i) != 0,
+            Self::All(cfgs) => cfgs.iter().all(|cfg| cfg.eval(benchmarks, test, options)),
+            Self::Any(cfgs) => cfgs.iter().any(|cfg| cfg.eval(benchmarks, test, options)),
+            Self::Not(cfg) => !cfg.eval(benchmarks, test, options),
         }
     }
+
+    // Whether there is a configuration in which the code is compiled for benchmarks, but not in
+    // the same configuration when testing.
+    fn excludes_tests(&self, options: usize) -> bool {
+        (0..1u32 << options).any(|options| self.eval(true, false, options) && !self.eval(false, true, options))
+    }
+}
+
+/// Parses a `cfg` predicate. With `fixed`, parses the predicate as if the suggestion had already
+/// been applied.
+struct Parser {
+    options: FxHashMap<String, usize>,
+    fixed: bool,
 }
 
-fn create_suggested_text(text: &str) -> String {
-    if text.contains("any(") { // Example: #[cfg(any(feature = "runtime-benchmarks"))]
-        text.replacen("any(", "any(test, ", 1)
-    } else { // Example: #[cfg(feature = "runtime-benchmarks")]
-        String::from("#[cfg(any(test, feature = \"runtime-benchmarks\"))]")
+impl Parser {
+    fn parse(&mut self, item: &NestedMetaItem, in_any: bool) -> Option<Cfg> {
+        let meta = item.meta_item()?;
+        match &meta.kind {
+            MetaItemKind::List(items) if meta.has_name(sym::all) => Some(Cfg::All(self.parse_list(items, false)?)),
+            MetaItemKind::List(items) if meta.has_name(sym::any) => {
+                let mut cfgs = self.parse_list(items, true)?;
+                if self.fixed && items.iter().any(is_benchmarks_feature) {
+                    cfgs.push(Cfg::Test);
+                }
+                Some(Cfg::Any(cfgs))
+            },
+            MetaItemKind::List(items) if meta.has_name(sym::not) && items.len() == 1 => {
+                Some(Cfg::Not(Box::new(self.parse(&items[0], false)?)))
+            },
+            MetaItemKind::List(_) => None,
+            _ if is_benchmarks_feature(item) => {
+                if self.fixed && !in_any {
+                    Some(Cfg::Any(vec![Cfg::Test, Cfg::Benchmarks]))
+                } else {
+                    Some(Cfg::Benchmarks)
+                }
+            },
+            MetaItemKind::Word if meta.has_name(sym::test) => Some(Cfg::Test),
+            _ => {
+                let key = format!("{}={:?}", meta.name_or_empty(), meta.value_str());
+                let next = self.options.len();
+                Some(Cfg::Option(*self.options.entry(key).or_insert(next)))
+            },
+        }
+    }
+
+    fn parse_list(&mut self, items: &[NestedMetaItem], in_any: bool) -> Option<Vec<Cfg>> {
+        items.iter().map(|item| self.parse(item, in_any)).collect()
+    }
+}
+
+fn is_benchmarks_feature(item: &NestedMetaItem) -> bool {
+    item.meta_item().is_some_and(|meta| {
+        meta.has_name(sym::feature) && meta.value_str().is_some_and(|value| value.as_str() == BENCHMARKS_FEATURE)
+    })
+}
+
+// Collects the edits that enable the code in tests: `test, ` is added to `any(..)` lists directly
+// containing the feature, every other occurrence of the feature is wrapped in `any(test, ..)`.
+fn collect_edits(item: &NestedMetaItem, in_any: bool, edits: &mut Vec<(Span, String)>) {
+    let Some(meta) = item.meta_item() else { return };
+    if is_benchmarks_feature(item) {
+        if !in_any {
+            edits.push((meta.span.shrink_to_lo(), String::from("any(test, ")));
+            edits.push((meta.span.shrink_to_hi(), String::from(")")));
+        }
+    } else if let MetaItemKind::List(items) = &meta.kind {
+        let is_any = meta.has_name(sym::any);
+        if is_any && items.iter().any(is_benchmarks_feature) {
+            edits.push((items[0].span().shrink_to_lo(), String::from("test, ")));
+        }
+        for nested in items {
+            collect_edits(nested, is_any, edits);
+        }
     }
 }
 
-fn search(pattern: &str, path: &str) -> Result<String, Box<dyn Error>> {
-    let matcher = RegexMatcher::new(pattern)?;
-    let mut searcher = SearcherBuilder::new()
-        .binary_detection(BinaryDetection::quit(b'\x00'))
-        .line_number(true)
-        .build();
-
-    let buffer = BufWriter::new(Vec::new());
-    let mut printer = JSON::new(buffer);
-
-    for result in WalkDir::new(path) {
-        let dent = match result {
-            Ok(dent) => dent,
-            Err(err) => {
-                eprintln!("{}", err);
-                continue;
-            }
+impl EarlyLintPass for EnableSinglepassBenchmarks {
+    fn check_attribute(&mut self, cx: &EarlyContext<'_>, attr: &Attribute) {
+        if !(attr.has_name(sym::cfg) || attr.has_name(sym::cfg_attr)) {
+            return;
+        }
+
+        // Only the predicate of `cfg_attr(predicate, attrs..)`
+        let Some(predicate) = attr.meta_item_list().and_then(|items| items.into_iter().next()) else { return };
+        if !contains_benchmarks_feature(&predicate) {
+            return;
+        }
+
+        let mut parser = Parser {
+            options: FxHashMap::default(),
+            fixed: false,
         };
-        if !dent.file_type().is_file() {
-            continue;
+        let Some(cfg) = parser.parse(&predicate, false) else { return };
+        let options = parser.options.len();
+        if options > MAX_OPTIONS || !cfg.excludes_tests(options) {
+            return;
         }
-        let result = searcher.search_path(
-            &matcher,
-            dent.path(),
-            printer.sink_with_path(&matcher, dent.path()),
-        );
-        
-        if let Err(err) = result {
-            eprintln!("{}: {}", dent.path().display(), err);
+
+        let mut fixed_parser = Parser {
+            options: FxHashMap::default(),
+            fixed: true,
+        };
+        let fixed = fixed_parser.parse(&predicate, false);
+
+        let mut edits = Vec::new();
+        collect_edits(&predicate, false, &mut edits);
+
+        if let Some(fixed) = fixed
+            && !fixed.excludes_tests(options)
+            && let Some(suggestion) = apply_edits(cx, attr.span, edits) {
+            span_lint_and_sugg(
+                cx,
+                ENABLE_SINGLEPASS_BENCHMARKS,
+                attr.span,
+                "substrace: benchmarks not run in tests",
+                "also enable this code in tests, so the benchmarks are run as part of `cargo test`",
+                suggestion,
+                Applicability::MachineApplicable,
+            );
+        } else {
+            span_lint_and_help(
+                cx,
+                ENABLE_SINGLEPASS_BENCHMARKS,
+                attr.span,
+                "substrace: benchmarks not run in tests",
+                None,
+                "make sure this code is also enabled in tests, so the benchmarks are run as part of `cargo test`",
+            );
         }
     }
+}
 
-    let bytes = printer.into_inner().into_inner()?;
-    let string = String::from_utf8(bytes)?;
+fn contains_benchmarks_feature(item: &NestedMetaItem) -> bool {
+    is_benchmarks_feature(item)
+        || item
+            .meta_item()
+            .and_then(MetaItem::meta_item_list)
+            .is_some_and(|items| items.iter().any(contains_benchmarks_feature))
+}
 
-    Ok(string)
-}
\ No newline at end of file
+// Applies the edits to the source of the attribute at `span`.
+fn apply_edits(cx: &EarlyContext<'_>, span: Span, mut edits: Vec<(Span, String)>) -> Option<String> {
+    let mut snippet = snippet_opt(cx, span)?;
+    edits.sort_by_key(|(edit_span, _)| edit_span.lo());
+    for (edit_span, text) in edits.into_iter().rev() {
+        if edit_span.lo() < span.lo() || edit_span.hi() > span.hi() || edit_span.from_expansion() {
+            return None;
+        }
+        let start = (edit_span.lo() - span.lo()).0 as usize;
+        let end = (edit_span.hi() - span.lo()).0 as usize;
+        snippet.replace_range(start..end, &text);
+    }
+    Some(snippet)
+}
@@ -1,16 +1,37 @@
-use substrace_utils::diagnostics::span_lint_and_sugg;
-use substrace_utils::source::{snippet_opt, line_span, span_extend_prev_str};
-use rustc_ast::AstDeref;
-use rustc_ast::ast as ast;
+use super::auxiliary::attrs::is_pallet_attr;
+use substrace_utils::diagnostics::span_lint_and_then;
+use substrace_utils::source::snippet_indent;
+use rustc_ast::ast;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_errors::Applicability;
-use rustc_hir as hir;
-use rustc_lint::{LateContext, LateLintPass};
-use rustc_session::{declare_lint, impl_lint_pass};
+use rustc_lint::{EarlyContext, EarlyLintPass};
+use rustc_session::{declare_tool_lint, impl_lint_pass};
 use rustc_span::source_map::Span;
-
-declare_lint! {
+use rustc_span::symbol::Symbol;
+
+declare_substrace_lint! {
+    /// ### What it does
+    /// Checks that every extrinsic has a `#[pallet::call_index(..)]` attribute, and that the call indices
+    /// are unique and fit in a `u8`.
+    ///
+    /// ### Why is this bad?
+    /// Without explicit call indices, the index of an extrinsic follows its position in the pallet, so
+    /// reordering the extrinsics changes which extrinsic signed transactions and governance proposals call.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// #[pallet::weight(0)]
+    /// pub fn transfer(origin: OriginFor<T>) -> DispatchResult { .. }
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// #[pallet::call_index(0)]
+    /// #[pallet::weight(0)]
+    /// pub fn transfer(origin: OriginFor<T>) -> DispatchResult { .. }
+    /// ```
     pub EXTRINSICS_MUST_BE_TAGGED,
-    Warn,
+    security,
     "Extrinsics need to be tagged using the pallet::call_index macro to prevent accidental reordering"
 }
 
@@ -19,120 +40,151 @@ impl_lint_pass!(ExtrinsicsMustBeTagged => [EXTRINSICS_MUST_BE_TAGGED]);
 #[derive(Clone, Default)]
 pub struct ExtrinsicsMustBeTagged;
 
-// TODO: Check pallet::call_index is not in a comment? Relevant if it is 0?! But not a good idea to implement now, since we're most likely not gonna keep using this tacting of checking plain text.
-impl<'tcx> LateLintPass<'tcx> for ExtrinsicsMustBeTagged {
-    fn check_fn(&mut self,
-                cx: &LateContext<'tcx>,
-                fn_kind: hir::intravisit::FnKind<'tcx>, 
-                _: &'tcx hir::FnDecl<'tcx>,
-                _: &'tcx hir::Body<'tcx>,
-                _: Span,
-                _: hir::hir_id::HirId) {
-        if let hir::intravisit::FnKind::Method(rustc_span::symbol::Ident {name, ..}, fn_sig) = fn_kind 
-            && is_extrinsic_name(name, cx)
-            && let index = get_index_in_expansion(name, cx)
-            && !get_index_in_macro(cx, fn_sig).is_some_and(|i| i == index) {
+/// An extrinsic in a `#[pallet::call]` impl block.
+struct Call {
+    name: Symbol,
+    /// Span of the function signature.
+    span: Span,
+    /// Span of the function item, without its attributes.
+    item_span: Span,
+    /// Whether it has a `#[pallet::call_index(..)]` attribute.
+    tagged: bool,
+    /// The index given with `#[pallet::call_index(..)]`, and the span of the index, if it is a literal.
+    index: Option<(u128, Span)>,
+}
 
-            let fn_sig_span_line = snippet_opt(cx, line_span(cx, fn_sig.span)); // also extend to include whitespace
-            let suggestion = format!("#[pallet::call_index({})]\n{}", index, fn_sig_span_line.expect("We should always be able to find the span.")); 
+// Runs before the pallet macro is expanded, so the `pallet::call_index` attributes are still there.
+impl EarlyLintPass for ExtrinsicsMustBeTagged {
+    fn check_item(&mut self, cx: &EarlyContext<'_>, item: &ast::Item) {
+        if let ast::ItemKind::Impl(impl_block) = &item.kind
+            && item.attrs.iter().any(|attr| is_pallet_attr(attr, "call")) {
+            let calls: Vec<Call> = impl_block.items.iter().filter_map(|impl_item| {
+                if let ast::AssocItemKind::Fn(func) = &impl_item.kind {
+                    let attr = impl_item.attrs.iter().find(|attr| is_pallet_attr(attr, "call_index"));
+                    Some(Call {
+                        name: impl_item.ident.name,
+                        span: func.sig.span,
+                        item_span: impl_item.span,
+                        tagged: attr.is_some(),
+                        index: attr.and_then(call_index),
+                    })
+                } else {
+                    None
+                }
+            }).collect();
 
-            span_lint_and_sugg(
-                cx,
-                EXTRINSICS_MUST_BE_TAGGED,
-                fn_sig.span,
-                "substrace: Extrinsic not tagged",
-                "Add the #[pallet::call_index(...)] macro to the top of your extrinsic definition",
-                suggestion,
-                Applicability::MachineApplicable, // Suggestion can be applied automatically
-            );
+            check_calls(cx, &calls);
         }
     }
 }
 
-//Checks if the text above the function signature contains a call_index macro call.
-pub fn get_index_in_macro<'tcx>(cx: &LateContext<'tcx>, fn_sig: &hir::FnSig<'tcx>) -> Option<u8> {
-    let new_span = span_extend_prev_str(cx, fn_sig.span, "#[pallet::call_index(")?;
-    let snip = snippet_opt(cx, new_span)?;
-
-    snip.get(1..)?.split(')').next()?.parse::<u8>().ok() // Removes first character ('(') and parses number until closing parenthesis
-}
-
-// Gets call index for extrinsic from code expanded by pallet::call macro
-pub fn get_index_in_expansion(func_name_symbol: rustc_span::symbol::Symbol, cx: &LateContext<'_>) -> u8 {
-    for item_id_ in cx.tcx.hir().root_module().item_ids.iter() { // Gets entire file
-        if let Some(hir::Node::Item(parent_node)) = cx.tcx.hir().find(item_id_.hir_id())
-        && let hir::ItemKind::Mod(mod_item) = parent_node.kind { //pallet module
-
-            for mod_item_id in mod_item.item_ids.iter() {
-                let node_in_mod = cx.tcx.hir().find(mod_item_id.hir_id());
-
-                if let Some(hir::Node::Item(item_in_mod)) = node_in_mod
-                    && let hir::ItemKind::Enum(hir::EnumDef{variants}, _) = &item_in_mod.kind { // Look for implement blocks
+fn check_calls(cx: &EarlyContext<'_>, calls: &[Call]) {
+    // This is how the pallet macro numbers the extrinsics. Indices given by a constant, like
+    // `#[pallet::call_index(TRANSFER)]`, aren't known before expansion, nor are the ones after them.
+    let mut implicit: Vec<Option<u128>> = Vec::with_capacity(calls.len());
+    for call in calls {
+        let index = match call.index {
+            Some((index, _)) => Some(index),
+            None if call.tagged => None,
+            None => implicit.last().map_or(Some(0), |last| last.map(|last| last + 1)),
+        };
+        implicit.push(index);
+    }
 
-                    for variant in variants.iter() {
-                        if variant.ident.name == func_name_symbol {
-                                
-                            for attrib in cx.tcx.hir().attrs(variant.hir_id).iter() {
-                                if let ast::AttrKind::Normal(normal_attrib) = &attrib.kind
-                                    && let ast::NormalAttr{item, ..} = normal_attrib.ast_deref()
-                                    && let Some(ast::MetaItemKind::List(list_items)) = item.meta_kind() 
-                                    && !list_items.is_empty() 
-                                    && let ast::NestedMetaItem::MetaItem(meta_item) = &list_items[0]
-                                    && let ast::MetaItemKind::NameValue(lit) = &meta_item.kind 
-                                    && let ast::LitKind::Int(the_index_wow, _) = lit.kind {
+    // Indices given explicitly, and the first extrinsic using them.
+    let mut explicit: FxHashMap<u128, &Call> = FxHashMap::default();
+    for call in calls {
+        if let Some((index, _)) = call.index {
+            explicit.entry(index).or_insert(call);
+        }
+    }
 
-                                    return the_index_wow as u8; //casting is save here
-                                }
-                            }
-                        }
-                    }
-                }
+    // Indices that are in use or suggested. Untagged extrinsics keep their implicit index where
+    // possible, so tagging them doesn't change the encoding of the calls.
+    let mut taken: FxHashSet<u128> = explicit.keys().copied().collect();
+    let mut keeps_index = vec![false; calls.len()];
+    for (i, call) in calls.iter().enumerate() {
+        if !call.tagged && let Some(index) = implicit[i] && taken.insert(index) {
+            keeps_index[i] = true;
+        }
+    }
+    let mut next_free = |start: u128| {
+        let free = (start..).find(|i| !taken.contains(i)).unwrap_or(start);
+        taken.insert(free);
+        free
+    };
+
+    for call in calls {
+        if let Some((index, index_span)) = call.index {
+            if index > u128::from(u8::MAX) {
+                let free = next_free(0);
+                span_lint_and_then(
+                    cx,
+                    EXTRINSICS_MUST_BE_TAGGED,
+                    index_span,
+                    &format!("substrace: call index `{index}` does not fit in a `u8`"),
+                    |diag| {
+                        diag.span_suggestion(index_span, "use a free index", free.to_string(), Applicability::MaybeIncorrect);
+                    },
+                );
+            } else if let Some(first) = explicit.get(&index) && first.span != call.span {
+                let free = next_free(index);
+                span_lint_and_then(
+                    cx,
+                    EXTRINSICS_MUST_BE_TAGGED,
+                    index_span,
+                    &format!("substrace: call index `{index}` is used by more than one extrinsic"),
+                    |diag| {
+                        diag.span_note(first.span, &format!("`{}` already uses call index `{index}`", first.name));
+                        diag.span_suggestion(index_span, "use a free index", free.to_string(), Applicability::MaybeIncorrect);
+                    },
+                );
             }
         }
     }
-    
-    panic!("Call index not present in expansion");
-}
-
-// Check if func_name_symbol is in the list of functions created by pallet::call macro that are exposed as extrinsics
-// TODO: This is also used by missing_transactional. Should probably be moved to some other helper functions place?
-pub fn is_extrinsic_name(func_name_symbol: rustc_span::symbol::Symbol, cx: &LateContext<'_>) -> bool {
-    for item_id_ in cx.tcx.hir().root_module().item_ids.iter() { // Gets entire file
-        if let Some(hir::Node::Item(parent_node)) = cx.tcx.hir().find(item_id_.hir_id())
-        && let hir::ItemKind::Mod(mod_item) = parent_node.kind { //pallet module
-
-            for mod_item_id in mod_item.item_ids.iter() {
-                let node_in_mod = cx.tcx.hir().find(mod_item_id.hir_id());
-
-                if let Some(hir::Node::Item(item_in_mod)) = node_in_mod
-                    && let hir::ItemKind::Impl(impl_block) = item_in_mod.kind { // Look for implement blocks
 
-                    for item_ref in impl_block.items.iter() {
-                        if item_ref.ident.as_str() == "get_call_names"
-                            && let item_ref_hir_id = item_ref.id.hir_id()
-                            && let item_ref_node = cx.tcx.hir().find(item_ref_hir_id)
-                            && let Some(hir::Node::ImplItem(item_in_impl)) = item_ref_node
-                            && let hir::ImplItemKind::Fn(_, body_id) = item_in_impl.kind
-                            && let body_node = cx.tcx.hir().find(body_id.hir_id) // Body of get_call_names
-                            && let Some(hir::Node::Expr(body_expr)) = body_node
-                            && let hir::ExprKind::Block(body_block, _) = body_expr.kind
-                            && let Some(block_expr) = body_block.expr
-                            && let hir::ExprKind::AddrOf(_, _, ref_expr) = block_expr.kind
-                            && let hir::ExprKind::Array(extrinsic_name_exprs) = ref_expr.kind {
+    let unknown_indices = calls.iter().any(|call| call.tagged && call.index.is_none());
+    for (position, call) in calls.iter().enumerate() {
+        if call.tagged {
+            continue;
+        }
 
-                            for extrinsic_name_expr in extrinsic_name_exprs.iter() { // Check for each extrinsic whether func_name_symbol is in it
-                                if let hir::ExprKind::Lit(spanned) = &extrinsic_name_expr.kind
-                                    && let rustc_ast::ast::LitKind::Str(extrinsic_symbol, _) = spanned.node
-                                    && func_name_symbol == extrinsic_symbol { 
-                                    return true;
-                                }
-                            }
-                        }
-                    }
+        let index = implicit[position];
+        let free = match index {
+            Some(index) if keeps_index[position] => index,
+            Some(index) => next_free(index),
+            None => next_free(0),
+        };
+
+        span_lint_and_then(
+            cx,
+            EXTRINSICS_MUST_BE_TAGGED,
+            call.span,
+            "substrace: Extrinsic not tagged",
+            |diag| {
+                if let Some(index) = index && let Some(other) = explicit.get(&index) {
+                    diag.span_note(other.span, &format!("the pallet macro gives `{}` index `{index}`, which is already used by `{}`", call.name, other.name));
+                } else if let Some(index) = index && index != position as u128 {
+                    diag.note(&format!("the pallet macro gives `{}` index `{index}` instead of `{position}`, because of the call indices before it", call.name));
                 }
-            }
-        }
+                diag.span_suggestion(
+                    call.item_span.shrink_to_lo(),
+                    "Add the #[pallet::call_index(...)] macro to the top of your extrinsic definition",
+                    format!("#[pallet::call_index({free})]\n{}", snippet_indent(cx, call.item_span).unwrap_or_default()),
+                    // The free index may be taken by an index that isn't a literal.
+                    if unknown_indices { Applicability::MaybeIncorrect } else { Applicability::MachineApplicable },
+                );
+            },
+        );
     }
+}
 
-    false // func_name not found in get_call_names
+// Reads the index of a `#[pallet::call_index(..)]` attribute.
+fn call_index(attr: &ast::Attribute) -> Option<(u128, Span)> {
+    if let [ast::NestedMetaItem::Literal(lit)] = &attr.meta_item_list()?[..]
+        && let ast::LitKind::Int(index, _) = lit.kind {
+        Some((index, lit.span))
+    } else {
+        None
+    }
 }
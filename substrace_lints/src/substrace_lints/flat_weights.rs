@@ -0,0 +1,320 @@
+use super::auxiliary::call_graph::{called_fn, resolve};
+use super::auxiliary::pallet::{pallets, PalletCache};
+use super::auxiliary::unbounded::{collection_bound, GET_CRATES};
+use super::auxiliary::weights::{weight_info_fn, ITERATING_METHODS};
+use substrace_utils::diagnostics::span_lint_hir_and_then;
+use substrace_utils::higher::{ForLoop, Range};
+use substrace_utils::visitors::for_each_expr_with_closures;
+use substrace_utils::{find_binding_init, path_to_local};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_errors::MultiSpan;
+use rustc_hir as hir;
+use rustc_hir::def_id::LocalDefId;
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::ty::{self, Ty};
+use rustc_session::{declare_tool_lint, impl_lint_pass};
+use rustc_span::symbol::Symbol;
+use rustc_span::Span;
+use std::ops::ControlFlow;
+
+declare_substrace_lint! {
+    /// ### What it does
+    /// Checks for dispatchables that iterate over a parameter, like a `Vec` or `BoundedVec`, or
+    /// loop up to a count parameter, while their `#[pallet::weight(..)]` doesn't use that
+    /// parameter. The loops are followed through the locals and the local functions the parameter
+    /// is passed to. A weight for the worst case, which uses the bound of a bounded parameter like
+    /// `T::MaxMembers::get()` for a `BoundedVec<_, T::MaxMembers>`, depends on that parameter too.
+    ///
+    /// ### Why is this bad?
+    /// The work grows with the length of the parameter, but the weight doesn't, so a caller can do
+    /// far more work than they pay for by passing a long list.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// #[pallet::weight(T::WeightInfo::add_members())]
+    /// pub fn add_members(origin: OriginFor<T>, members: BoundedVec<T::AccountId, T::MaxMembers>) -> DispatchResult {
+    ///     for member in members.iter() { .. }
+    /// }
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// #[pallet::weight(T::WeightInfo::add_members(members.len() as u32))]
+    /// pub fn add_members(origin: OriginFor<T>, members: BoundedVec<T::AccountId, T::MaxMembers>) -> DispatchResult {
+    ///     for member in members.iter() { .. }
+    /// }
+    /// ```
+    pub FLAT_WEIGHTS,
+    weights,
+    "The weight of a dispatchable must depend on the parameters whose length its work depends on."
+}
+
+impl_lint_pass!(FlatWeights => [FLAT_WEIGHTS]);
+
+#[derive(Clone)]
+pub struct FlatWeights {
+    pallets: PalletCache,
+}
+
+impl FlatWeights {
+    pub fn new(pallets: PalletCache) -> Self {
+        Self { pallets }
+    }
+}
+
+/// The methods that pass on the length of the collection or iterator they are called on, like
+/// `items.iter().map(..)` or `items.len()`.
+const LENGTH_METHODS: [&str; 24] = [
+    "iter",
+    "iter_mut",
+    "into_iter",
+    "drain",
+    "len",
+    "enumerate",
+    "map",
+    "filter",
+    "filter_map",
+    "flat_map",
+    "skip",
+    "rev",
+    "cloned",
+    "copied",
+    "chunks",
+    "windows",
+    "keys",
+    "values",
+    "as_slice",
+    "as_ref",
+    "to_vec",
+    "clone",
+    "peekable",
+    "inspect",
+];
+
+/// The parameters of a dispatchable, by their index, that a value gets its length from.
+type Params = FxHashSet<usize>;
+
+impl<'tcx> LateLintPass<'tcx> for FlatWeights {
+    fn check_crate_post(&mut self, cx: &LateContext<'tcx>) {
+        for call in pallets(cx, &self.pallets).iter().flat_map(|pallet| &pallet.calls) {
+            let (Some(def_id), Some(weight_id)) = (call.def_id.as_local(), call.weight) else { continue };
+            let weight = cx.tcx.hir().expect_expr(weight_id);
+            let Some(body_id) = cx.tcx.hir().maybe_body_owned_by(def_id) else { continue };
+            if weight.span.from_expansion() {
+                continue;
+            }
+
+            // The parameters the weight uses, by the names the pallet macro binds them to, and the
+            // bounds it takes the worst case of, like `T::MaxMembers::get()`.
+            let mut weighed: FxHashSet<Symbol> = FxHashSet::default();
+            let mut bounds: Vec<Ty<'_>> = Vec::new();
+            let typeck = cx.tcx.typeck(weight_id.owner.def_id);
+            for_each_expr_with_closures(cx, weight, |expr| {
+                if let Some(local) = path_to_local(expr) {
+                    weighed.insert(cx.tcx.hir().name(local));
+                } else if let Some((fn_id, substs)) = called_fn(typeck, expr)
+                    && let Some(trait_id) = cx.tcx.trait_of_item(fn_id)
+                    && cx.tcx.item_name(trait_id).as_str() == "Get"
+                    && GET_CRATES.contains(&cx.tcx.crate_name(trait_id.krate).as_str())
+                {
+                    bounds.push(substs.type_at(0));
+                }
+                ControlFlow::<()>::Continue(())
+            });
+
+            // The first parameter is the origin.
+            let params = cx.tcx.hir().body(body_id).params;
+            let sources: Vec<Params> = (0..params.len())
+                .map(|idx| if idx == 0 { Params::default() } else { Params::from_iter([idx]) })
+                .collect();
+            let mut iterations: FxHashMap<usize, Vec<Span>> = FxHashMap::default();
+            let mut visited = FxHashSet::default();
+            find_iterations(cx, def_id, &sources, &mut visited, &mut iterations);
+
+            let inputs = cx.tcx.fn_sig(def_id).skip_binder().inputs();
+            let mut iterations: Vec<_> = iterations.into_iter().collect();
+            iterations.sort_unstable_by_key(|(idx, _)| *idx);
+            for (idx, spans) in iterations {
+                let hir::PatKind::Binding(_, _, ident, _) = params[idx].pat.kind else { continue };
+                if weighed.contains(&ident.name)
+                    || collection_bound(cx, inputs[idx]).map_or(false, |bound| bounds.iter().any(|used| same_bound(bound, *used)))
+                {
+                    continue;
+                }
+                let name = call.name;
+                let length = if inputs[idx].is_integral() {
+                    format!("{ident}")
+                } else {
+                    format!("{ident}.len() as u32")
+                };
+
+                span_lint_hir_and_then(
+                    cx,
+                    FLAT_WEIGHTS,
+                    cx.tcx.hir().local_def_id_to_hir_id(def_id),
+                    weight.span,
+                    &format!("substrace: the weight of `{name}` doesn't depend on `{ident}`, which it iterates over"),
+                    |diag| {
+                        diag.span_note(MultiSpan::from_spans(spans), format!("the work depends on `{ident}` here"));
+                        match weight_info_fn(cx, call.def_id, name) {
+                            Some(weight_info) => match weight_info.def_id.map(|fn_id| cx.tcx.fn_arg_names(fn_id)) {
+                                Some(args) if !args.is_empty() => {
+                                    diag.help(format!("pass `{length}` to `{}`", weight_info.path));
+                                },
+                                Some(_) => {
+                                    diag.help(format!(
+                                        "add a component for `{ident}` to the `{name}` benchmark, and pass `{length}` to `{}`",
+                                        weight_info.path
+                                    ));
+                                },
+                                None => {
+                                    diag.help(format!(
+                                        "benchmark `{name}` with a component for `{ident}`, and pass `{length}` to `{}`",
+                                        weight_info.path
+                                    ));
+                                },
+                            },
+                            None => {
+                                diag.help(format!(
+                                    "benchmark `{name}` with a component for `{ident}`, and pass `{length}` to its weight function"
+                                ));
+                            },
+                        }
+                    },
+                );
+            }
+        }
+    }
+}
+
+// Whether the bound of a parameter and a bound used in the weight are the same. The weight is
+// type checked in the `GetDispatchInfo` implementation of the pallet, so bounds like `T::MaxMembers`
+// are compared by the associated type rather than by its generic parameter.
+fn same_bound<'tcx>(param: Ty<'tcx>, weight: Ty<'tcx>) -> bool {
+    match (param.kind(), weight.kind()) {
+        (ty::Projection(param), ty::Projection(weight)) => param.item_def_id == weight.item_def_id,
+        _ => param == weight,
+    }
+}
+
+// Finds the loops and iterations in `def_id` whose length depends on the parameters of the
+// dispatchable, given the parameters each of its own parameters gets its length from. Local
+// functions the parameters are passed to are searched as well.
+fn find_iterations(
+    cx: &LateContext<'_>,
+    def_id: LocalDefId,
+    sources: &[Params],
+    visited: &mut FxHashSet<LocalDefId>,
+    iterations: &mut FxHashMap<usize, Vec<Span>>,
+) {
+    if !visited.insert(def_id) {
+        return;
+    }
+    let Some(body_id) = cx.tcx.hir().maybe_body_owned_by(def_id) else { return };
+    let body = cx.tcx.hir().body(body_id);
+    let typeck = cx.tcx.typeck(def_id);
+    let locals: FxHashMap<hir::HirId, &Params> = body
+        .params
+        .iter()
+        .zip(sources)
+        .filter_map(|(param, sources)| match param.pat.kind {
+            hir::PatKind::Binding(_, local, ..) => Some((local, sources)),
+            _ => None,
+        })
+        .collect();
+
+    let mut callees = Vec::new();
+    for_each_expr_with_closures(cx, body.value, |expr| {
+        let mut record = |iterated: &hir::Expr<'_>, span: Span| {
+            for idx in length_sources(cx, iterated, &locals) {
+                iterations.entry(idx).or_default().push(span);
+            }
+        };
+        if let Some(for_loop) = ForLoop::hir(expr) {
+            record(for_loop.arg, for_loop.arg.span);
+        } else if let hir::ExprKind::MethodCall(path, receiver, ..) = expr.kind
+            && ITERATING_METHODS.contains(&path.ident.as_str())
+            && !expr.span.from_expansion()
+        {
+            record(receiver, expr.span.with_hi(path.ident.span.hi()));
+        } else if let Some((fn_id, substs)) = called_fn(typeck, expr)
+            && let Some(callee) = resolve(cx, def_id, fn_id, substs).as_local()
+        {
+            let args: Vec<&hir::Expr<'_>> = match expr.kind {
+                hir::ExprKind::Call(_, args) => args.iter().collect(),
+                hir::ExprKind::MethodCall(_, receiver, args, _) => [receiver].into_iter().chain(args).collect(),
+                _ => Vec::new(),
+            };
+            let args: Vec<Params> = args.into_iter().map(|arg| length_sources(cx, arg, &locals)).collect();
+            if args.iter().any(|sources| !sources.is_empty()) {
+                callees.push((callee, args));
+            }
+        }
+        ControlFlow::<()>::Continue(())
+    });
+
+    for (callee, args) in callees {
+        find_iterations(cx, callee, &args, visited, iterations);
+    }
+}
+
+// The parameters of the dispatchable that the length of `expr` depends on, like `items` for
+// `items.iter().enumerate()` or `count` for `0..count`.
+fn length_sources(cx: &LateContext<'_>, expr: &hir::Expr<'_>, locals: &FxHashMap<hir::HirId, &Params>) -> Params {
+    let mut sources = Params::default();
+    let mut visited = FxHashSet::default();
+    add_length_sources(cx, expr, locals, &mut visited, &mut sources);
+    sources
+}
+
+fn add_length_sources(
+    cx: &LateContext<'_>,
+    expr: &hir::Expr<'_>,
+    locals: &FxHashMap<hir::HirId, &Params>,
+    visited: &mut FxHashSet<hir::HirId>,
+    sources: &mut Params,
+) {
+    if let Some(range) = Range::hir(expr) {
+        for bound in [range.start, range.end].into_iter().flatten() {
+            add_length_sources(cx, bound, locals, visited, sources);
+        }
+        return;
+    }
+    match expr.kind {
+        hir::ExprKind::Path(_) => {
+            let Some(local) = path_to_local(expr) else { return };
+            if let Some(params) = locals.get(&local) {
+                sources.extend(params.iter().copied());
+            } else if visited.insert(local)
+                && let Some(init) = find_binding_init(cx, local)
+            {
+                add_length_sources(cx, init, locals, visited, sources);
+            }
+        },
+        hir::ExprKind::MethodCall(path, receiver, args, _) => match path.ident.as_str() {
+            // `take(n)` limits the length to `n`.
+            "take" => {
+                if let [limit] = args {
+                    add_length_sources(cx, limit, locals, visited, sources);
+                }
+            },
+            "zip" | "chain" => {
+                add_length_sources(cx, receiver, locals, visited, sources);
+                for arg in args {
+                    add_length_sources(cx, arg, locals, visited, sources);
+                }
+            },
+            method if LENGTH_METHODS.contains(&method) => add_length_sources(cx, receiver, locals, visited, sources),
+            _ => {},
+        },
+        hir::ExprKind::AddrOf(_, _, inner)
+        | hir::ExprKind::Unary(_, inner)
+        | hir::ExprKind::Cast(inner, _)
+        | hir::ExprKind::DropTemps(inner) => add_length_sources(cx, inner, locals, visited, sources),
+        hir::ExprKind::Binary(_, lhs, rhs) => {
+            add_length_sources(cx, lhs, locals, visited, sources);
+            add_length_sources(cx, rhs, locals, visited, sources);
+        },
+        _ => {},
+    }
+}
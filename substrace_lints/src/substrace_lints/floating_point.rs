@@ -0,0 +1,242 @@
+use super::auxiliary::call_graph::{called_fn, entry_points, reachable, reachable_note, EntryPoint, Reached};
+use super::auxiliary::pallet::PalletCache;
+use substrace_utils::diagnostics::span_lint_hir_and_then;
+use substrace_utils::numeric_literal::{NumericLiteral, Radix};
+use substrace_utils::source::snippet;
+use substrace_utils::ty::peel_mid_ty_refs;
+use substrace_utils::visitors::for_each_expr_with_closures;
+use substrace_utils::{get_parent_expr, is_expn_of};
+use rustc_ast::ast::LitKind;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_errors::{Applicability, Diagnostic};
+use rustc_hir as hir;
+use rustc_hir::def_id::LocalDefId;
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::ty::{self, Ty};
+use rustc_session::{declare_tool_lint, impl_lint_pass};
+use rustc_span::Span;
+use std::ops::ControlFlow;
+
+declare_substrace_lint! {
+    /// ### What it does
+    /// Checks for `f32` and `f64` types, float literals, casts from and to floats, and
+    /// `FixedU128::from_float` and `to_float`, in functions reachable from dispatchables, hooks and
+    /// runtime APIs, and in `parameter_types!`.
+    ///
+    /// ### Why is this bad?
+    /// Floating point arithmetic isn't guaranteed to give the same result on every platform, so
+    /// nodes can disagree on the state after a block. Rounding also makes balances and ratios lose
+    /// precision silently.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// let fee = (amount as f64 * 0.05) as u128;
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// let fee = Perbill::from_percent(5) * amount;
+    /// ```
+    pub FLOATING_POINT,
+    security,
+    "The runtime must not use floating point numbers."
+}
+
+impl_lint_pass!(FloatingPoint => [FLOATING_POINT]);
+
+#[derive(Clone)]
+pub struct FloatingPoint {
+    pallets: PalletCache,
+}
+
+impl FloatingPoint {
+    pub fn new(pallets: PalletCache) -> Self {
+        Self { pallets }
+    }
+}
+
+/// The functions of the fixed point types of `sp_arithmetic` that convert from and to floats.
+const FLOAT_CONVERSIONS: [&str; 2] = ["from_float", "to_float"];
+
+const NOTE: &str = "floating point arithmetic isn't guaranteed to give the same result on every node";
+const HELP: &str = "use the fixed point types of `sp_arithmetic`, like `FixedU128`, or a `Perbill`";
+
+/// Why a function runs in the runtime.
+enum Runtime {
+    Reachable(EntryPoint, Reached),
+    Config,
+}
+
+impl Runtime {
+    fn note(&self, cx: &LateContext<'_>, diag: &mut Diagnostic) {
+        match self {
+            Self::Reachable(entry, reached) => {
+                diag.span_note(cx.tcx.def_span(entry.def_id), reachable_note(cx, entry, reached));
+            },
+            Self::Config => {
+                diag.note("in the runtime configuration");
+            },
+        }
+    }
+}
+
+impl<'tcx> LateLintPass<'tcx> for FloatingPoint {
+    fn check_crate_post(&mut self, cx: &LateContext<'tcx>) {
+        let mut runtime: FxHashMap<LocalDefId, Runtime> = FxHashMap::default();
+        for entry in entry_points(cx, &self.pallets) {
+            for reached in reachable(cx, entry.def_id) {
+                runtime
+                    .entry(reached.def_id)
+                    .or_insert_with(|| Runtime::Reachable(entry, reached));
+            }
+        }
+        for def_id in cx.tcx.hir().body_owners() {
+            if is_expn_of(cx.tcx.def_span(def_id), "parameter_types").is_some() {
+                for reached in reachable(cx, def_id) {
+                    runtime.entry(reached.def_id).or_insert(Runtime::Config);
+                }
+            }
+        }
+
+        // `parameter_types!` repeats the type and value of a parameter in several functions.
+        let mut linted: FxHashSet<Span> = FxHashSet::default();
+        let mut runtime: Vec<_> = runtime.into_iter().collect();
+        runtime.sort_by_key(|(def_id, _)| cx.tcx.def_span(*def_id));
+        for (def_id, reason) in runtime {
+            let hir_id = cx.tcx.hir().local_def_id_to_hir_id(def_id);
+            let Some(body_id) = cx.tcx.hir().maybe_body_owned_by(def_id) else { continue };
+            let typeck = cx.tcx.typeck(def_id);
+            let body = cx.tcx.hir().body(body_id);
+            let mut lint = |hir_id, span: Span, msg: &str, f: &dyn Fn(&mut Diagnostic)| {
+                if span.from_expansion() || !linted.insert(span) {
+                    return;
+                }
+                span_lint_hir_and_then(cx, FLOATING_POINT, hir_id, span, msg, |diag| {
+                    diag.note(NOTE);
+                    reason.note(cx, diag);
+                    f(diag);
+                });
+            };
+
+            if let Some(decl) = cx.tcx.hir().fn_decl_by_hir_id(hir_id) {
+                let sig = cx.tcx.fn_sig(def_id).skip_binder();
+                let output = match decl.output {
+                    hir::FnRetTy::Return(hir_ty) => Some((hir_ty, sig.output())),
+                    hir::FnRetTy::DefaultReturn(_) => None,
+                };
+                for (hir_ty, ty) in decl.inputs.iter().zip(sig.inputs().iter().copied()).chain(output) {
+                    if contains_float(ty) {
+                        lint(hir_id, hir_ty.span, &format!("substrace: floating point type `{ty}` in runtime code"), &|diag| {
+                            diag.help(HELP);
+                        });
+                    }
+                }
+            }
+
+            for_each_expr_with_closures(cx, body.value, |expr| {
+                match expr.kind {
+                    // The argument of `from_float` is part of the conversion, which is linted instead.
+                    hir::ExprKind::Lit(ref lit)
+                        if matches!(lit.node, LitKind::Float(..))
+                            && !get_parent_expr(cx, expr).map_or(false, |parent| is_float_conversion(cx, typeck, parent)) =>
+                    {
+                        let suggestion = fixed_point_literal(cx, lit);
+                        lint(expr.hir_id, expr.span, &format!(
+                            "substrace: floating point literal `{}` in runtime code",
+                            snippet(cx, expr.span, "..")
+                        ), &|diag| match &suggestion {
+                            Some(suggestion) => {
+                                diag.span_suggestion(expr.span, HELP, suggestion, Applicability::MaybeIncorrect);
+                            },
+                            None => {
+                                diag.help(HELP);
+                            },
+                        });
+                    },
+                    hir::ExprKind::Cast(inner, _) => {
+                        let (from, _) = peel_mid_ty_refs(typeck.expr_ty(inner));
+                        let to = typeck.expr_ty(expr);
+                        if from.is_floating_point() || to.is_floating_point() {
+                            lint(expr.hir_id, expr.span, &format!(
+                                "substrace: floating point cast from `{from}` to `{to}` in runtime code"
+                            ), &|diag| {
+                                diag.help(HELP);
+                            });
+                        }
+                    },
+                    hir::ExprKind::Block(block, _) => {
+                        for stmt in block.stmts {
+                            if let hir::StmtKind::Local(local) = stmt.kind
+                                && let Some(hir_ty) = local.ty
+                                && let ty = typeck.pat_ty(local.pat)
+                                && contains_float(ty)
+                            {
+                                lint(local.hir_id, hir_ty.span, &format!("substrace: floating point type `{ty}` in runtime code"), &|diag| {
+                                    diag.help(HELP);
+                                });
+                            }
+                        }
+                    },
+                    _ => {
+                        if is_float_conversion(cx, typeck, expr)
+                            && let Some((fn_id, _)) = called_fn(typeck, expr)
+                        {
+                            lint(expr.hir_id, expr.span, &format!(
+                                "substrace: floating point conversion `{}` in runtime code",
+                                cx.tcx.def_path_str(fn_id)
+                            ), &|diag| {
+                                diag.help("use the fixed point arithmetic of `sp_arithmetic` throughout, e.g. `FixedU128::from_rational`");
+                            });
+                        }
+                    },
+                }
+                ControlFlow::<()>::Continue(())
+            });
+        }
+    }
+}
+
+fn is_float_conversion(cx: &LateContext<'_>, typeck: &ty::TypeckResults<'_>, expr: &hir::Expr<'_>) -> bool {
+    called_fn(typeck, expr).map_or(false, |(fn_id, _)| {
+        FLOAT_CONVERSIONS.contains(&cx.tcx.item_name(fn_id).as_str())
+            && cx.tcx.crate_name(fn_id.krate).as_str() == "sp_arithmetic"
+    })
+}
+
+fn contains_float(ty: Ty<'_>) -> bool {
+    ty.walk().any(|arg| matches!(arg.unpack(), ty::GenericArgKind::Type(ty) if ty.is_floating_point()))
+}
+
+// The fixed point equivalent of a float literal, like `Perbill::from_percent(5)` for `0.05` or
+// `FixedU128::from_rational(15, 10)` for `1.5`.
+fn fixed_point_literal(cx: &LateContext<'_>, lit: &hir::Lit) -> Option<String> {
+    let src = snippet(cx, lit.span, "");
+    let literal = NumericLiteral::from_lit_kind(&src, &lit.node)?;
+    if literal.radix != Radix::Decimal || literal.exponent.is_some() {
+        return None;
+    }
+    let integer = literal.integer.replace('_', "");
+    let fraction = literal.fraction.unwrap_or("").replace('_', "");
+    let fraction = fraction.trim_end_matches('0');
+    let digits = format!("{integer}{fraction}");
+    let numerator = digits.trim_start_matches('0');
+    let numerator = if numerator.is_empty() { "0" } else { numerator };
+    let denominator = format!("1{}", "0".repeat(fraction.len()));
+
+    if integer.trim_start_matches('0').is_empty() || (integer == "1" && fraction.is_empty()) {
+        match fraction.len() {
+            0..=2 => {
+                let percent = format!("{numerator}{}", "0".repeat(2 - fraction.len()));
+                let percent = percent.trim_start_matches('0');
+                Some(format!(
+                    "Perbill::from_percent({})",
+                    if percent.is_empty() { "0" } else { percent }
+                ))
+            },
+            3..=9 => Some(format!("Perbill::from_rational({numerator}u32, {denominator}u32)")),
+            _ => None,
+        }
+    } else {
+        Some(format!("FixedU128::from_rational({numerator}, {denominator})"))
+    }
+}
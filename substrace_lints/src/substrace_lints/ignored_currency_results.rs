@@ -0,0 +1,185 @@
+use super::auxiliary::call_graph::called_fn;
+use super::auxiliary::paths;
+use substrace_utils::diagnostics::span_lint_and_help;
+use substrace_utils::ty::is_type_diagnostic_item;
+use substrace_utils::{is_path_diagnostic_item, match_def_path};
+use rustc_hir as hir;
+use rustc_hir::def_id::DefId;
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::ty;
+use rustc_session::{declare_tool_lint, impl_lint_pass};
+use rustc_span::{sym, Span};
+
+declare_substrace_lint! {
+    /// ### What it does
+    /// Checks for ignored results of the `Currency`, `ReservableCurrency`, `fungible::Mutate` and
+    /// `fungible::MutateHold` methods that move funds: results bound to `_`, discarded with `.ok()`
+    /// or `drop`, or not used at all, like the amount `unreserve` could not unreserve and the
+    /// imbalance and remaining amount of `slash`.
+    ///
+    /// ### Why is this bad?
+    /// A failed transfer or reserve, an amount that wasn't unreserved or slashed, and a dropped
+    /// imbalance all make the accounting of the pallet drift from the actual balances.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// let _ = T::Currency::reserve(&who, deposit);
+    /// T::Currency::unreserve(&who, deposit);
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// T::Currency::reserve(&who, deposit)?;
+    /// let remaining = T::Currency::unreserve(&who, deposit);
+    /// debug_assert!(remaining.is_zero());
+    /// ```
+    pub IGNORED_CURRENCY_RESULTS,
+    security,
+    "The results and imbalances of `Currency` and `fungible` operations must be checked."
+}
+
+impl_lint_pass!(IgnoredCurrencyResults => [IGNORED_CURRENCY_RESULTS]);
+
+#[derive(Clone, Default)]
+pub struct IgnoredCurrencyResults;
+
+const CURRENCY_METHODS: &[&str] = &[
+    "transfer",
+    "slash",
+    "withdraw",
+    "deposit_into_existing",
+    "deposit_creating",
+    "settle",
+    "make_free_balance_be",
+    "burn",
+    "issue",
+];
+const RESERVABLE_CURRENCY_METHODS: &[&str] = &["reserve", "unreserve", "slash_reserved", "repatriate_reserved"];
+const MUTATE_METHODS: &[&str] = &[
+    "mint_into",
+    "burn_from",
+    "transfer",
+    "slash",
+    "teleport",
+    "set_balance",
+    "shelve",
+    "restore",
+];
+const MUTATE_HOLD_METHODS: &[&str] = &[
+    "hold",
+    "release",
+    "release_all",
+    "burn_held",
+    "burn_all_held",
+    "transfer_held",
+    "transfer_on_hold",
+];
+
+/// The balance traits, how they are named in messages, and their methods that move funds.
+const BALANCE_TRAITS: [(&[&str], &str, &[&str]); 6] = [
+    (&paths::CURRENCY, "Currency", CURRENCY_METHODS),
+    (&paths::RESERVABLE_CURRENCY, "ReservableCurrency", RESERVABLE_CURRENCY_METHODS),
+    (&paths::FUNGIBLE_MUTATE, "fungible::Mutate", MUTATE_METHODS),
+    (&paths::FUNGIBLE_REGULAR_MUTATE, "fungible::Mutate", MUTATE_METHODS),
+    (&paths::FUNGIBLE_MUTATE_HOLD, "fungible::MutateHold", MUTATE_HOLD_METHODS),
+    (&paths::FUNGIBLE_HOLD_MUTATE, "fungible::MutateHold", MUTATE_HOLD_METHODS),
+];
+
+impl<'tcx> LateLintPass<'tcx> for IgnoredCurrencyResults {
+    fn check_local(&mut self, cx: &LateContext<'tcx>, local: &'tcx hir::Local<'tcx>) {
+        let Some(init) = local.init else { return };
+        if local.span.from_expansion() {
+            return;
+        }
+
+        match local.pat.kind {
+            hir::PatKind::Wild => check_ignored(cx, init),
+            hir::PatKind::Binding(_, _, ident, None) if ident.as_str().starts_with('_') => check_ignored(cx, init),
+            // `let (imbalance, _) = T::Currency::slash(..)`
+            hir::PatKind::Tuple([_, remaining], dotdot) if dotdot.as_opt_usize().is_none() && is_ignored_pat(remaining) => {
+                if let Some((name, method)) = balance_call(cx, init)
+                    && matches!(method, "slash" | "slash_reserved")
+                {
+                    span_lint_and_help(
+                        cx,
+                        IGNORED_CURRENCY_RESULTS,
+                        init.span,
+                        &format!("substrace: the amount `{name}` could not slash is ignored"),
+                        Some(remaining.span),
+                        "check that this amount is zero, or slash it from somewhere else",
+                    );
+                }
+            },
+            _ => {},
+        }
+    }
+
+    fn check_stmt(&mut self, cx: &LateContext<'tcx>, stmt: &'tcx hir::Stmt<'tcx>) {
+        if let hir::StmtKind::Semi(expr) = stmt.kind
+            && !stmt.span.from_expansion()
+        {
+            check_ignored(cx, expr);
+        }
+    }
+}
+
+fn is_ignored_pat(pat: &hir::Pat<'_>) -> bool {
+    match pat.kind {
+        hir::PatKind::Wild => true,
+        hir::PatKind::Binding(_, _, ident, None) => ident.as_str().starts_with('_'),
+        _ => false,
+    }
+}
+
+// Lints `expr` if it is a call to one of the balance traits, possibly wrapped in `.ok()` or `drop`,
+// whose value is thrown away.
+fn check_ignored<'tcx>(cx: &LateContext<'tcx>, expr: &'tcx hir::Expr<'tcx>) {
+    let (call, how) = match expr.kind {
+        hir::ExprKind::MethodCall(path, receiver, [], _) if path.ident.as_str() == "ok" => (receiver, " with `.ok()`"),
+        hir::ExprKind::Call(callee, [arg]) if is_path_diagnostic_item(cx, callee, sym::mem_drop) => (arg, " with `drop`"),
+        _ => (expr, ""),
+    };
+    let Some((name, method)) = balance_call(cx, call) else { return };
+
+    let ty = cx.typeck_results().expr_ty(call);
+    let help = if is_type_diagnostic_item(cx, ty, sym::Result) {
+        format!("`{method}` can fail without moving any funds, handle the error, e.g. with `?`")
+    } else if let ty::Tuple(..) = ty.kind() {
+        format!("`{method}` returns the imbalance and the amount it could not slash, resolve the imbalance and check that the amount is zero")
+    } else if method == "unreserve" {
+        "`unreserve` returns the amount it could not unreserve, check that it is zero".to_owned()
+    } else {
+        format!("`{method}` returns an imbalance, resolve it, e.g. with `OnUnbalanced::on_unbalanced`, as dropping it changes the total issuance")
+    };
+
+    span_lint_and_help(
+        cx,
+        IGNORED_CURRENCY_RESULTS,
+        expr.span,
+        &format!("substrace: the result of `{name}` is ignored{how}"),
+        None::<Span>,
+        &help,
+    );
+}
+
+// Returns the name, like `Currency::transfer`, and the method name, if `expr` calls a method of one
+// of the balance traits that moves funds.
+fn balance_call(cx: &LateContext<'_>, expr: &hir::Expr<'_>) -> Option<(String, &'static str)> {
+    let (fn_id, _) = called_fn(cx.typeck_results(), expr)?;
+    let trait_id = trait_of(cx, fn_id)?;
+    let name = cx.tcx.item_name(fn_id);
+
+    BALANCE_TRAITS.iter().find_map(|(path, trait_name, methods)| {
+        let method = methods.iter().find(|method| name.as_str() == **method)?;
+        match_def_path(cx, trait_id, path).then(|| (format!("{trait_name}::{method}"), *method))
+    })
+}
+
+// The trait of a trait method, or of the trait impl a method is in.
+fn trait_of(cx: &LateContext<'_>, fn_id: DefId) -> Option<DefId> {
+    cx.tcx.trait_of_item(fn_id).or_else(|| {
+        cx.tcx
+            .impl_of_method(fn_id)
+            .and_then(|impl_id| cx.tcx.trait_id_of_impl(impl_id))
+    })
+}
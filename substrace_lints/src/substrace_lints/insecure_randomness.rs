@@ -0,0 +1,237 @@
+use super::auxiliary::call_graph::called_fn;
+use super::auxiliary::paths;
+use substrace_utils::diagnostics::span_lint_and_then;
+use substrace_utils::visitors::for_each_expr_with_closures;
+use substrace_utils::{find_binding_init, match_any_def_paths, path_to_local};
+use rustc_data_structures::fx::FxHashSet;
+use rustc_errors::MultiSpan;
+use rustc_hir as hir;
+use rustc_hir::def_id::{CrateNum, DefId};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::ty::{self, Ty};
+use rustc_session::{declare_tool_lint, impl_lint_pass};
+use rustc_span::Span;
+use std::ops::ControlFlow;
+
+declare_substrace_lint! {
+    /// ### What it does
+    /// Checks for randomness that block producers can influence or anyone can predict:
+    /// - `Config` implementations of the runtime that bind a type, like `Randomness`, to
+    ///   `pallet_insecure_randomness_collective_flip`,
+    /// - calls to that pallet, like `RandomnessCollectiveFlip::random(..)`,
+    /// - hashes of the block number, a block hash, the extrinsic index or the timestamp, like
+    ///   `T::Hashing::hash_of(&(who, frame_system::Pallet::<T>::parent_hash()))`.
+    ///
+    /// ### Why is this bad?
+    /// Anyone can compute the outcome before sending an extrinsic, or have a block producer choose
+    /// it, to win a lottery or mint a rare NFT.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// impl pallet_lottery::Config for Runtime {
+    ///     type Randomness = RandomnessCollectiveFlip;
+    /// }
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// impl pallet_lottery::Config for Runtime {
+    ///     type Randomness = pallet_babe::RandomnessFromOneEpochAgo<Runtime>;
+    /// }
+    /// ```
+    pub INSECURE_RANDOMNESS,
+    security,
+    "Randomness must not be predictable or influenced by block producers."
+}
+
+impl_lint_pass!(InsecureRandomness => [INSECURE_RANDOMNESS]);
+
+#[derive(Clone, Default)]
+pub struct InsecureRandomness;
+
+/// The crate names of the collective flip pallet, which was renamed to make its insecurity clear.
+const COLLECTIVE_FLIP_CRATES: [&str; 2] = [
+    "pallet_insecure_randomness_collective_flip",
+    "pallet_randomness_collective_flip",
+];
+
+/// The crates that define hash functions, like `sp_io::hashing::blake2_256` or the `Hash` trait of
+/// `T::Hashing`.
+const HASH_CRATES: [&str; 5] = ["sp_io", "sp_core", "sp_core_hashing", "sp_runtime", "frame_support"];
+
+/// The hash functions of `HASH_CRATES`.
+const HASH_FNS: [&str; 11] = [
+    "hash",
+    "hash_of",
+    "blake2_64",
+    "blake2_128",
+    "blake2_256",
+    "keccak_256",
+    "keccak_512",
+    "sha2_256",
+    "twox_64",
+    "twox_128",
+    "twox_256",
+];
+
+const NOTE_COLLECTIVE_FLIP: &str =
+    "the collective flip combines the hashes of the last 81 blocks, which block producers can influence and anyone can read";
+const HELP: &str = "use a source of randomness based on a VRF, like `pallet_babe::RandomnessFromOneEpochAgo`, or a commit-reveal scheme";
+
+impl<'tcx> LateLintPass<'tcx> for InsecureRandomness {
+    fn check_item(&mut self, cx: &LateContext<'tcx>, item: &'tcx hir::Item<'_>) {
+        let hir::ItemKind::Impl(impl_block) = item.kind else { return };
+        let Some(trait_ref) = &impl_block.of_trait else { return };
+        let Some(trait_id) = trait_ref.trait_def_id() else { return };
+
+        for impl_item_ref in impl_block.items {
+            let impl_item = cx.tcx.hir().impl_item(impl_item_ref.id);
+            if let hir::ImplItemKind::Type(hir_ty) = impl_item.kind
+                && uses_collective_flip(cx, cx.tcx.type_of(impl_item.owner_id))
+            {
+                span_lint_and_then(
+                    cx,
+                    INSECURE_RANDOMNESS,
+                    hir_ty.span,
+                    &format!(
+                        "substrace: `{}::{}` is bound to `pallet_insecure_randomness_collective_flip`, which is predictable",
+                        cx.tcx.item_name(trait_id),
+                        impl_item.ident
+                    ),
+                    |diag| {
+                        diag.note(NOTE_COLLECTIVE_FLIP);
+                        diag.help(HELP);
+                    },
+                );
+            }
+        }
+    }
+
+    fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx hir::Expr<'_>) {
+        if expr.span.from_expansion() {
+            return;
+        }
+        let Some((fn_id, substs)) = called_fn(cx.typeck_results(), expr) else { return };
+
+        // `RandomnessCollectiveFlip::random(..)` and the inherent functions of the pallet.
+        let self_ty = cx.tcx.trait_of_item(fn_id).and_then(|_| substs.types().next());
+        if is_collective_flip(cx, fn_id.krate) || self_ty.map_or(false, |ty| uses_collective_flip(cx, ty)) {
+            span_lint_and_then(
+                cx,
+                INSECURE_RANDOMNESS,
+                expr.span,
+                "substrace: randomness from `pallet_insecure_randomness_collective_flip` is predictable",
+                |diag| {
+                    diag.note(NOTE_COLLECTIVE_FLIP);
+                    diag.help(HELP);
+                },
+            );
+            return;
+        }
+
+        // Hashes of hashes are checked once, at the outermost hash.
+        if !is_hash_fn(cx, fn_id)
+            || cx.tcx.hir().parent_iter(expr.hir_id).any(|(_, node)| {
+                matches!(node, hir::Node::Expr(parent)
+                    if called_fn(cx.typeck_results(), parent).map_or(false, |(fn_id, _)| is_hash_fn(cx, fn_id)))
+            })
+        {
+            return;
+        }
+        let mut sources = Vec::new();
+        let args: Vec<&hir::Expr<'_>> = match expr.kind {
+            hir::ExprKind::Call(_, args) => args.iter().collect(),
+            hir::ExprKind::MethodCall(_, receiver, args, _) => [receiver].into_iter().chain(args).collect(),
+            _ => return,
+        };
+        let mut visited = FxHashSet::default();
+        for arg in args {
+            predictable_sources(cx, arg, &mut visited, &mut sources);
+        }
+        if sources.is_empty() {
+            return;
+        }
+
+        let mut descrs: Vec<&str> = Vec::new();
+        for &(descr, _) in &sources {
+            if !descrs.contains(&descr) {
+                descrs.push(descr);
+            }
+        }
+        span_lint_and_then(
+            cx,
+            INSECURE_RANDOMNESS,
+            expr.span,
+            &format!("substrace: randomness derived from {} is predictable", descrs.join(" and ")),
+            |diag| {
+                let spans: Vec<Span> = sources.iter().map(|&(_, span)| span).collect();
+                diag.span_note(
+                    MultiSpan::from_spans(spans),
+                    "block producers can influence this, and anyone can read it before sending an extrinsic",
+                );
+                diag.help(HELP);
+                diag.help("if the hash isn't used as randomness, e.g. as a unique id, allow this lint");
+            },
+        );
+    }
+}
+
+fn is_collective_flip(cx: &LateContext<'_>, krate: CrateNum) -> bool {
+    COLLECTIVE_FLIP_CRATES.contains(&cx.tcx.crate_name(krate).as_str())
+}
+
+// Whether `ty` is, or is generic over, a type of the collective flip pallet, like
+// `RandomnessCollectiveFlip` or `pallet_insecure_randomness_collective_flip::Pallet<Runtime>`.
+fn uses_collective_flip<'tcx>(cx: &LateContext<'tcx>, ty: Ty<'tcx>) -> bool {
+    ty.walk().any(|arg| {
+        matches!(arg.unpack(), ty::GenericArgKind::Type(ty)
+            if matches!(ty.kind(), ty::Adt(adt, _) if is_collective_flip(cx, adt.did().krate)))
+    })
+}
+
+fn is_hash_fn(cx: &LateContext<'_>, fn_id: DefId) -> bool {
+    let krate = cx.tcx.trait_of_item(fn_id).unwrap_or(fn_id).krate;
+    HASH_FNS.contains(&cx.tcx.item_name(fn_id).as_str()) && HASH_CRATES.contains(&cx.tcx.crate_name(krate).as_str())
+}
+
+// Describes the values that are known before an extrinsic is included, if `fn_id` returns one.
+fn predictable_source(cx: &LateContext<'_>, fn_id: DefId) -> Option<&'static str> {
+    let name = cx.tcx.item_name(fn_id);
+    match (cx.tcx.crate_name(fn_id.krate).as_str(), name.as_str()) {
+        ("frame_system", "block_number") => return Some("the block number"),
+        ("frame_system", "parent_hash" | "block_hash") => return Some("a block hash"),
+        ("frame_system", "extrinsic_index") => return Some("the extrinsic index"),
+        ("pallet_timestamp", "now" | "get") => return Some("the timestamp"),
+        _ => {},
+    }
+    if name.as_str() == "now"
+        && let Some(trait_id) = cx.tcx.trait_of_item(fn_id)
+        && match_any_def_paths(cx, trait_id, &[&paths::TIME, &paths::UNIX_TIME]).is_some()
+    {
+        Some("the timestamp")
+    } else {
+        None
+    }
+}
+
+// Collects the predictable values in `expr`, following the locals it uses to their initializers.
+fn predictable_sources<'tcx>(
+    cx: &LateContext<'tcx>,
+    expr: &'tcx hir::Expr<'tcx>,
+    visited: &mut FxHashSet<hir::HirId>,
+    sources: &mut Vec<(&'static str, Span)>,
+) {
+    for_each_expr_with_closures(cx, expr, |e| {
+        if let Some((fn_id, _)) = called_fn(cx.typeck_results(), e)
+            && let Some(descr) = predictable_source(cx, fn_id)
+        {
+            sources.push((descr, e.span));
+        } else if let Some(local) = path_to_local(e)
+            && visited.insert(local)
+            && let Some(init) = find_binding_init(cx, local)
+        {
+            predictable_sources(cx, init, visited, sources);
+        }
+        ControlFlow::<()>::Continue(())
+    });
+}
@@ -0,0 +1,388 @@
+use super::auxiliary::call_graph::{called_fn, entry_points, reachable, reachable_note};
+use super::auxiliary::pallet::{extrinsic_pallet, pallets, Pallet, PalletCache};
+use substrace_utils::diagnostics::span_lint_hir_and_then;
+use substrace_utils::source::snippet_with_applicability;
+use substrace_utils::sugg::Sugg;
+use substrace_utils::last_path_segment;
+use substrace_utils::ty::{int_ty_bits, is_type_diagnostic_item, peel_mid_ty_refs};
+use substrace_utils::visitors::for_each_expr_with_closures;
+use rustc_data_structures::fx::FxHashSet;
+use rustc_errors::Applicability;
+use rustc_hir as hir;
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::LocalDefId;
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_session::{declare_tool_lint, impl_lint_pass};
+use rustc_span::sym;
+use std::ops::ControlFlow;
+
+declare_substrace_lint! {
+    /// ### What it does
+    /// Checks for conversions of balances and weights that can lose the value in functions
+    /// reachable from dispatchables, hooks and runtime APIs: `as` casts to a narrower integer or one
+    /// of the other signedness, which truncate or wrap, and `saturated_into`, `saturated_from`,
+    /// `unique_saturated_into` and `unique_saturated_from` to a type that can't hold every value,
+    /// which saturate. Conversions from a generic type like `T::Balance` are only fine into `u128`.
+    ///
+    /// A balance or a weight is a value whose type is named like `Balance`, `BalanceOf<T>` or
+    /// `Weight`, on either side of the conversion. The parts of a weight, `Weight::ref_time`,
+    /// `Weight::proof_size` and the `read` and `write` weights of a `RuntimeDbWeight`, are weights too.
+    ///
+    /// ### Why is this bad?
+    /// A truncated balance or weight is silently wrong, and a saturated one hides the overflow that
+    /// should have failed the call.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// let amount = balance as u64;
+    /// let index: u32 = balance.unique_saturated_into();
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// let amount = u64::try_from(balance).map_err(|_| Error::<T>::Overflow)?;
+    /// let index: u32 = balance.try_into().map_err(|_| Error::<T>::Overflow)?;
+    /// ```
+    pub LOSSY_CONVERSIONS,
+    security,
+    "Numeric conversions in runtime code must not truncate or saturate."
+}
+
+impl_lint_pass!(LossyConversions => [LOSSY_CONVERSIONS]);
+
+#[derive(Clone)]
+pub struct LossyConversions {
+    pallets: PalletCache,
+}
+
+impl LossyConversions {
+    pub fn new(pallets: PalletCache) -> Self {
+        Self { pallets }
+    }
+}
+
+/// The saturating conversions of `sp_arithmetic`, also re-exported by `sp_runtime`.
+const SATURATING_FNS: [&str; 4] = [
+    "saturated_into",
+    "saturated_from",
+    "unique_saturated_into",
+    "unique_saturated_from",
+];
+
+struct Conversion<'tcx> {
+    expr: &'tcx hir::Expr<'tcx>,
+    /// The value being converted.
+    value: &'tcx hir::Expr<'tcx>,
+    from: Ty<'tcx>,
+    to: Ty<'tcx>,
+    /// The saturating function, or `None` for an `as` cast.
+    saturating: Option<&'static str>,
+    /// Whether the conversion is in a function that returns a `Result`.
+    can_fail: bool,
+}
+
+impl<'tcx> LateLintPass<'tcx> for LossyConversions {
+    fn check_crate_post(&mut self, cx: &LateContext<'tcx>) {
+        let pallets = pallets(cx, &self.pallets);
+
+        let mut reported = FxHashSet::default();
+        for entry in entry_points(cx, &self.pallets) {
+            for reached in reachable(cx, entry.def_id) {
+                for conversion in find_conversions(cx, reached.def_id) {
+                    if !reported.insert(conversion.expr.hir_id) {
+                        continue;
+                    }
+                    let error = pallet_error(cx, pallets, entry.def_id, reached.def_id);
+
+                    let msg = match conversion.saturating {
+                        Some(name) => format!(
+                            "substrace: `{name}` from `{}` to `{}` saturates, hiding the overflow",
+                            conversion.from, conversion.to
+                        ),
+                        None => {
+                            let narrows = int_ty_bits(cx.tcx, conversion.to) < int_ty_bits(cx.tcx, conversion.from);
+                            format!(
+                                "substrace: casting `{}` to `{}` with `as` can {} the value",
+                                conversion.from,
+                                conversion.to,
+                                if narrows { "truncate" } else { "wrap" }
+                            )
+                        },
+                    };
+                    span_lint_hir_and_then(
+                        cx,
+                        LOSSY_CONVERSIONS,
+                        conversion.expr.hir_id,
+                        conversion.expr.span,
+                        &msg,
+                        |diag| {
+                            diag.span_note(
+                                cx.tcx.def_span(entry.def_id),
+                                reachable_note(cx, &entry, &reached),
+                            );
+                            suggest(cx, diag, &conversion, error.as_deref());
+                        },
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn find_conversions<'tcx>(cx: &LateContext<'tcx>, def_id: LocalDefId) -> Vec<Conversion<'tcx>> {
+    let Some(body_id) = cx.tcx.hir().maybe_body_owned_by(def_id) else { return Vec::new() };
+    let typeck = cx.tcx.typeck(def_id);
+    let returns_result = matches!(cx.tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn)
+        && is_type_diagnostic_item(cx, cx.tcx.fn_sig(def_id).skip_binder().output(), sym::Result);
+
+    let mut found = Vec::new();
+    for_each_expr_with_closures(cx, cx.tcx.hir().body(body_id).value, |expr| {
+        let conversion = match expr.kind {
+            hir::ExprKind::Cast(value, _) => Some((value, None)),
+            hir::ExprKind::MethodCall(_, receiver, [], _) => {
+                saturating_fn(cx, typeck, expr).map(|name| (receiver, Some(name)))
+            },
+            hir::ExprKind::Call(_, [arg]) => saturating_fn(cx, typeck, expr).map(|name| (arg, Some(name))),
+            _ => None,
+        };
+        if let Some((value, saturating)) = conversion
+            && !expr.span.from_expansion()
+        {
+            let (from, _) = peel_mid_ty_refs(typeck.expr_ty(value));
+            let to = typeck.expr_ty(expr);
+            if is_lossy(cx.tcx, from, to, saturating.is_some())
+                && (is_balance_or_weight(cx, from, declared_ty(cx, typeck, value))
+                    || is_weight_part(cx, typeck, value)
+                    || is_balance_or_weight(cx, to, target_ty(cx, expr)))
+            {
+                found.push(Conversion {
+                    expr,
+                    value,
+                    from,
+                    to,
+                    saturating,
+                    // A `?` in a closure would return from the closure instead.
+                    can_fail: returns_result && cx.tcx.hir().enclosing_body_owner(expr.hir_id) == def_id,
+                });
+            }
+        }
+        ControlFlow::<()>::Continue(())
+    });
+    found
+}
+
+fn saturating_fn(cx: &LateContext<'_>, typeck: &ty::TypeckResults<'_>, expr: &hir::Expr<'_>) -> Option<&'static str> {
+    let (fn_id, _) = called_fn(typeck, expr)?;
+    let trait_id = cx.tcx.trait_of_item(fn_id)?;
+    if cx.tcx.crate_name(trait_id.krate).as_str() != "sp_arithmetic" {
+        return None;
+    }
+    let name = cx.tcx.item_name(fn_id);
+    SATURATING_FNS.into_iter().find(|saturating| name.as_str() == *saturating)
+}
+
+// Casts lose the value when they narrow an integer, and wrap it when they change its signedness
+// without widening an unsigned one. Saturating conversions also lose it when converting a generic
+// type, like `T::Balance`, which is an unsigned integer of at least 32 bits, into anything but the
+// widest unsigned integer, or a negative or wider than 32 bits integer into a generic type.
+fn is_lossy<'tcx>(tcx: TyCtxt<'tcx>, from: Ty<'tcx>, to: Ty<'tcx>, saturating: bool) -> bool {
+    if from == to {
+        return false;
+    }
+    let is_generic = |ty: Ty<'_>| matches!(ty.kind(), ty::Param(_) | ty::Projection(_));
+    match (int_ty_bits(tcx, from), int_ty_bits(tcx, to)) {
+        (Some(from_bits), Some(to_bits)) => {
+            to_bits < from_bits || (from.is_signed() && !to.is_signed()) || (!from.is_signed() && to.is_signed() && to_bits == from_bits)
+        },
+        (None, Some(to_bits)) => saturating && is_generic(from) && (to_bits < 128 || to.is_signed()),
+        (Some(from_bits), None) => saturating && is_generic(to) && (from_bits > 32 || from.is_signed()),
+        (None, None) => saturating && is_generic(from) && is_generic(to),
+    }
+}
+
+// Whether `ty`, or the type it was written as, is a balance or a weight. Type aliases like
+// `BalanceOf<T>`, and `Weight` before it became a struct, are already resolved in `ty`.
+fn is_balance_or_weight(cx: &LateContext<'_>, ty: Ty<'_>, written: Option<&hir::Ty<'_>>) -> bool {
+    let name = match ty.kind() {
+        ty::Param(param) => Some(param.name),
+        ty::Projection(projection) => Some(cx.tcx.item_name(projection.item_def_id)),
+        ty::Adt(adt, _) => Some(cx.tcx.item_name(adt.did())),
+        _ => None,
+    };
+    name.map_or(false, |name| is_balance_or_weight_name(name.as_str()))
+        || written.map_or(false, is_balance_or_weight_hir_ty)
+}
+
+fn is_balance_or_weight_hir_ty(ty: &hir::Ty<'_>) -> bool {
+    match &ty.kind {
+        hir::TyKind::Path(qpath) => is_balance_or_weight_name(last_path_segment(qpath).ident.as_str()),
+        hir::TyKind::Rptr(_, mut_ty) => is_balance_or_weight_hir_ty(mut_ty.ty),
+        _ => false,
+    }
+}
+
+fn is_balance_or_weight_name(name: &str) -> bool {
+    name.contains("Balance") || name.ends_with("Weight")
+}
+
+// Whether `expr` reads a part of a weight, which is a plain `u64`: `Weight::ref_time`,
+// `Weight::proof_size`, or the `read` or `write` field of a `RuntimeDbWeight`.
+fn is_weight_part(cx: &LateContext<'_>, typeck: &ty::TypeckResults<'_>, expr: &hir::Expr<'_>) -> bool {
+    let is_adt_named = |ty: Ty<'_>, name: &str| matches!(ty.kind(), ty::Adt(adt, _) if cx.tcx.item_name(adt.did()).as_str() == name);
+    match expr.kind {
+        hir::ExprKind::Call(..) | hir::ExprKind::MethodCall(..) => called_fn(typeck, expr).map_or(false, |(fn_id, _)| {
+            matches!(cx.tcx.item_name(fn_id).as_str(), "ref_time" | "proof_size")
+                && cx
+                    .tcx
+                    .impl_of_method(fn_id)
+                    .map_or(false, |impl_id| is_adt_named(cx.tcx.type_of(impl_id), "Weight"))
+        }),
+        hir::ExprKind::Field(base, name) => {
+            matches!(name.as_str(), "read" | "write")
+                && is_adt_named(typeck.expr_ty_adjusted(base).peel_refs(), "RuntimeDbWeight")
+        },
+        hir::ExprKind::AddrOf(_, _, inner) | hir::ExprKind::Unary(hir::UnOp::Deref, inner) => {
+            is_weight_part(cx, typeck, inner)
+        },
+        _ => false,
+    }
+}
+
+// The type `expr` was declared with: of the parameter or `let` binding it reads, of the field it
+// accesses or the return type of the function it calls.
+fn declared_ty<'tcx>(
+    cx: &LateContext<'tcx>,
+    typeck: &ty::TypeckResults<'tcx>,
+    expr: &hir::Expr<'_>,
+) -> Option<&'tcx hir::Ty<'tcx>> {
+    let hir = cx.tcx.hir();
+    match expr.kind {
+        hir::ExprKind::Path(hir::QPath::Resolved(None, path)) => {
+            let hir::def::Res::Local(binding) = path.res else { return None };
+            match hir.find(hir.get_parent_node(binding))? {
+                hir::Node::Local(local) => local.ty,
+                hir::Node::Param(param) => {
+                    let owner = hir.get_parent_node(param.hir_id);
+                    let body = hir.body(hir.maybe_body_owned_by(hir.local_def_id(owner))?);
+                    let index = body.params.iter().position(|body_param| body_param.hir_id == param.hir_id)?;
+                    hir.fn_decl_by_hir_id(owner)?.inputs.get(index)
+                },
+                _ => None,
+            }
+        },
+        hir::ExprKind::Field(base, name) => {
+            let ty::Adt(adt, _) = typeck.expr_ty_adjusted(base).peel_refs().kind() else { return None };
+            let field = adt.all_fields().find(|field| field.name == name.name)?;
+            match hir.get_if_local(field.did)? {
+                hir::Node::Field(field) => Some(field.ty),
+                _ => None,
+            }
+        },
+        hir::ExprKind::Call(..) | hir::ExprKind::MethodCall(..) => {
+            let (fn_id, _) = called_fn(typeck, expr)?;
+            match hir.fn_decl_by_hir_id(hir.local_def_id_to_hir_id(fn_id.as_local()?))?.output {
+                hir::FnRetTy::Return(ty) => Some(ty),
+                hir::FnRetTy::DefaultReturn(_) => None,
+            }
+        },
+        hir::ExprKind::AddrOf(_, _, inner) | hir::ExprKind::Unary(hir::UnOp::Deref, inner) => {
+            declared_ty(cx, typeck, inner)
+        },
+        _ => None,
+    }
+}
+
+// The type a conversion was written to convert into: of the cast, of the turbofish on a saturating
+// method, the type a saturating function is called on, or of the `let` binding it initializes.
+fn target_ty<'tcx>(cx: &LateContext<'tcx>, expr: &'tcx hir::Expr<'tcx>) -> Option<&'tcx hir::Ty<'tcx>> {
+    let written = match expr.kind {
+        hir::ExprKind::Cast(_, ty) => Some(ty),
+        hir::ExprKind::MethodCall(segment, ..) => segment.args.and_then(|args| {
+            args.args.iter().find_map(|arg| match arg {
+                hir::GenericArg::Type(ty) => Some(*ty),
+                _ => None,
+            })
+        }),
+        hir::ExprKind::Call(
+            hir::Expr {
+                kind: hir::ExprKind::Path(hir::QPath::TypeRelative(ty, _)),
+                ..
+            },
+            _,
+        ) => Some(*ty),
+        _ => None,
+    };
+    let hir = cx.tcx.hir();
+    written.or_else(|| match hir.find(hir.get_parent_node(expr.hir_id))? {
+        hir::Node::Local(local) => local.ty,
+        _ => None,
+    })
+}
+
+// The `#[pallet::error]` enum of the pallet, as a path to its overflow variant, like
+// `Error::<T>::Overflow`. It is looked up next to the `Pallet` struct of the dispatchable `entry`,
+// or else next to the function `def_id` with the conversion.
+fn pallet_error(cx: &LateContext<'_>, pallets: &[Pallet], entry: LocalDefId, def_id: LocalDefId) -> Option<String> {
+    let module = extrinsic_pallet(pallets, entry.to_def_id())
+        .and_then(|pallet| pallet.pallet.as_local())
+        .map_or_else(
+            || cx.tcx.parent_module_from_def_id(def_id),
+            |pallet| cx.tcx.parent_module_from_def_id(pallet),
+        );
+    cx.tcx.hir().module_items(module).find_map(|id| {
+        let item = cx.tcx.hir().item(id);
+        let hir::ItemKind::Enum(ref enum_def, generics) = item.kind else { return None };
+        if item.ident.as_str() != "Error" {
+            return None;
+        }
+        let variant = enum_def
+            .variants
+            .iter()
+            .find(|variant| variant.ident.as_str().contains("Overflow"))?;
+        let generics = if generics.params.is_empty() { "" } else { "::<T>" };
+        Some(format!("Error{generics}::{}", variant.ident))
+    })
+}
+
+fn suggest(
+    cx: &LateContext<'_>,
+    diag: &mut rustc_errors::Diagnostic,
+    conversion: &Conversion<'_>,
+    error: Option<&str>,
+) {
+    let mut applicability = Applicability::MaybeIncorrect;
+    // Generic numeric types are bounded by `TryInto`, but the integers don't implement `TryFrom` them.
+    let try_from = if int_ty_bits(cx.tcx, conversion.from).is_some() && int_ty_bits(cx.tcx, conversion.to).is_some() {
+        let value = snippet_with_applicability(cx, conversion.value.span, "..", &mut applicability);
+        format!("{}::try_from({value})", conversion.to)
+    } else {
+        let value = Sugg::hir_with_applicability(cx, conversion.value, "..", &mut applicability);
+        format!("{}.try_into()", value.maybe_par())
+    };
+
+    match (conversion.can_fail, error) {
+        (true, Some(error)) => {
+            diag.span_suggestion(
+                conversion.expr.span,
+                "use `TryInto` and return the pallet's error",
+                format!("{try_from}.map_err(|_| {error})?"),
+                applicability,
+            );
+        },
+        (true, None) => {
+            diag.span_suggestion(
+                conversion.expr.span,
+                "use `TryInto` and return an overflow error of the pallet",
+                format!("{try_from}.map_err(|_| Error::<T>::Overflow)?"),
+                Applicability::HasPlaceholders,
+            );
+        },
+        (false, _) if conversion.saturating.is_none() => {
+            diag.help("use `TryInto` and handle the error, or `saturated_into` if saturating is intended");
+        },
+        (false, _) => {
+            diag.help("use `TryInto` and handle the error");
+        },
+    }
+}
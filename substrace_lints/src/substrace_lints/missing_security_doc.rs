@@ -1,58 +1,243 @@
+use super::auxiliary::call_graph::called_fn;
 use super::auxiliary::paths;
-use substrace_utils::diagnostics::{span_lint_and_help, span_lint_and_sugg};
-use substrace_utils::source::first_line_of_span;
+use super::auxiliary::storage::{storage_call, storage_keys, storage_prefix};
+use substrace_utils::diagnostics::{span_lint_and_help, span_lint_hir_and_then};
+use substrace_utils::match_def_path;
+use substrace_utils::source::{first_line_of_span, indent_of};
+use substrace_utils::visitors::for_each_expr_with_closures;
 use itertools::Itertools;
 use rustc_ast::ast::Attribute;
 use rustc_errors::Applicability;
 use rustc_ast::token::CommentKind;
-use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_hir as hir;
+use rustc_hir::def::{DefKind, Res};
+use rustc_hir::def_id::DefId;
 use rustc_lint::{LateContext, LateLintPass};
-use rustc_session::{declare_lint, impl_lint_pass};
+use rustc_middle::ty::subst::GenericArgKind;
+use rustc_middle::ty::{self, SubstsRef, Ty};
+use rustc_session::{declare_tool_lint, impl_lint_pass};
 use rustc_span::source_map::{BytePos, Span};
 use rustc_span::{sym, Pos};
-use std::ops::Range;
+use std::collections::hash_map::Entry;
+use std::ops::{ControlFlow, Range};
 
-declare_lint! {
+declare_substrace_lint! {
+    /// ### What it does
+    /// Checks the hasher of every key of a storage map. Keys hashed with `Twox64Concat`, `Twox128`,
+    /// `Twox256` or `Identity` need a `# Security` doc comment. Maps whose values are iterated with
+    /// `iter_values`, `iter_prefix_values` or `translate_values` are also reported if the keys of the
+    /// iterated values are hashed with a hasher that doesn't store the key, like `Blake2_128`, as the
+    /// values can't be matched with their keys then. FRAME only allows iterating the keys themselves
+    /// with a hasher that stores them.
+    ///
+    /// ### Why is this bad?
+    /// An attacker who can choose the keys can find keys that are placed next to each other in the storage
+    /// trie, which makes reading and iterating the map more expensive than the weights account for.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// #[pallet::storage]
+    /// pub type Balances<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, u64>;
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// #[pallet::storage]
+    /// pub type Balances<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, u64>;
+    /// ```
     pub MISSING_SECURITY_DOC,
-    Warn,
+    security,
     "Using the Identity or Twox64Concat hasher requires a doc describing it's secure usage"
 }
 
 impl_lint_pass!(DocMarkdown => [MISSING_SECURITY_DOC]);
 
+/// Methods of the storage maps that iterate the values of every key, without the keys. These are
+/// the only iterators of maps whose hashers don't store the key.
+const VALUE_ITERATORS: [&str; 2] = ["iter_values", "translate_values"];
+
+/// Methods of the storage maps that iterate the values of the keys following the given prefix of
+/// keys, without those keys.
+const PREFIX_VALUE_ITERATORS: [&str; 1] = ["iter_prefix_values"];
+
 #[derive(Clone, Default)]
-pub struct DocMarkdown;
+pub struct DocMarkdown {
+    /// Storage items, by prefix, whose values are iterated, with the first key that isn't given to
+    /// the iterator and where.
+    iterated: FxHashMap<DefId, (usize, Span)>,
+}
 
 impl<'tcx> LateLintPass<'tcx> for DocMarkdown {
-    fn check_item(&mut self, cx: &LateContext<'tcx>, item: &'tcx hir::Item<'_>) {
-        if let hir::ItemKind::TyAlias(ty, ..) = item.kind
-            && let hir::TyKind::Path(hir::QPath::Resolved(_, path)) = ty.kind
-            && let hir::def::Res::Def(_, id) = path.res
-            && paths::is_like_storage_map(cx, id) {
-            
-            let attrs = cx.tcx.hir().attrs(item.hir_id());
-            let headers = check_attrs(cx, &Default::default(), attrs);
-
-            for segment in path.segments {
-                if let Some(args) = segment.args
-                    && paths::is_insecure_hash_function(cx, args) && !headers.security {
-                    
-                    span_lint_and_sugg(
-                        cx,
-                        MISSING_SECURITY_DOC,
-                        item.span,
-                        "substrace: Twox{64, 128, 256} and Identity are at not secure!",
-                        "use Blake2, or add a # Security doc comment describing why the usage is correct",
-                        "/// # Security
-/// Twox64Concat is safe because the ..."
-                            .to_string(),
-                        Applicability::HasPlaceholders,
-                    );
+    fn check_crate(&mut self, cx: &LateContext<'tcx>) {
+        for def_id in cx.tcx.hir().body_owners() {
+            // Closures are visited as part of the body they are defined in.
+            if cx.tcx.is_closure(def_id.to_def_id()) {
+                continue;
+            }
+            let Some(body_id) = cx.tcx.hir().maybe_body_owned_by(def_id) else { continue };
+            let typeck = cx.tcx.typeck(def_id);
+
+            for_each_expr_with_closures(cx, cx.tcx.hir().body(body_id).value, |expr| {
+                if let Some((fn_id, substs)) = called_fn(typeck, expr)
+                    && let Some(call) = storage_call(cx, fn_id, substs)
+                    && let Some(prefix) = storage_prefix(call.storage) {
+                    let method = call.method.as_str();
+                    let first_key = if VALUE_ITERATORS.contains(&method) {
+                        Some(0)
+                    } else if PREFIX_VALUE_ITERATORS.contains(&method) {
+                        Some(prefix_len(cx, fn_id, substs, call.storage))
+                    } else {
+                        None
+                    };
+
+                    if let Some(first_key) = first_key {
+                        match self.iterated.entry(prefix) {
+                            Entry::Vacant(entry) => {
+                                entry.insert((first_key, expr.span));
+                            },
+                            Entry::Occupied(mut entry) if entry.get().0 > first_key => {
+                                entry.insert((first_key, expr.span));
+                            },
+                            Entry::Occupied(_) => {},
+                        }
+                    }
                 }
+                ControlFlow::<()>::Continue(())
+            });
+        }
+    }
+
+    fn check_item(&mut self, cx: &LateContext<'tcx>, item: &'tcx hir::Item<'_>) {
+        let hir::ItemKind::TyAlias(hir_ty, _) = item.kind else { return };
+
+        // Aliases of local aliases are checked where the hashers are written.
+        if let hir::TyKind::Path(hir::QPath::Resolved(_, path)) = hir_ty.kind
+            && let Res::Def(DefKind::TyAlias, id) = path.res
+            && id.is_local() {
+            return;
+        }
+
+        // The type the alias resolves to, so hashers behind re-exports and aliases are found too.
+        let storage = cx.tcx.type_of(item.owner_id);
+        let keys = storage_keys(cx, storage);
+        if keys.is_empty() {
+            return;
+        }
+
+        let attrs = cx.tcx.hir().attrs(item.hir_id());
+        let headers = check_attrs(cx, &Default::default(), attrs);
+        let iterated = storage_prefix(storage).and_then(|prefix| self.iterated.get(&prefix));
+
+        // Point at the hashers if they are written in the alias itself.
+        let hasher_spans = hasher_spans(cx, hir_ty);
+        let hasher_spans = if hasher_spans.len() == keys.len() {
+            hasher_spans
+        } else {
+            vec![hir_ty.span; keys.len()]
+        };
+
+        for (index, (key, &span)) in keys.iter().zip(&hasher_spans).enumerate() {
+            let ty::Adt(hasher, _) = key.hasher.kind() else { continue };
+            let hasher_name = cx.tcx.item_name(hasher.did());
+
+            if !headers.security && paths::is_insecure_hasher(cx, hasher.did()) {
+                span_lint_hir_and_then(
+                    cx,
+                    MISSING_SECURITY_DOC,
+                    item.hir_id(),
+                    span,
+                    &format!(
+                        "substrace: the key `{}` of `{}` is hashed with `{hasher_name}`, which is not secure",
+                        key.key, item.ident
+                    ),
+                    |diag| {
+                        let indent = " ".repeat(indent_of(cx, item.span).unwrap_or(0));
+                        diag.span_suggestion(
+                            item.span.shrink_to_lo(),
+                            "use Blake2, or add a # Security doc comment describing why the usage is correct",
+                            format!(
+                                "/// # Security\n{indent}/// `{hasher_name}` is safe for `{}` because ...\n{indent}",
+                                key.key
+                            ),
+                            Applicability::HasPlaceholders,
+                        );
+                    },
+                );
             }
+
+            if let Some(&(first_key, iter_span)) = iterated
+                && index >= first_key
+                && paths::is_non_reversible_hasher(cx, hasher.did()) {
+                span_lint_hir_and_then(
+                    cx,
+                    MISSING_SECURITY_DOC,
+                    item.hir_id(),
+                    span,
+                    &format!(
+                        "substrace: the key `{}` of `{}` is hashed with `{hasher_name}`, so the iterated values can't be matched with it",
+                        key.key, item.ident
+                    ),
+                    |diag| {
+                        diag.span_note(iter_span, "the values are iterated here");
+                        diag.help("if the keys are needed, use a hasher that stores the key after the hash, such as `Blake2_128Concat`, and iterate with `iter` or `iter_prefix`");
+                    },
+                );
+            }
+        }
+    }
+}
+
+// Number of keys given to a prefix iterator: the first key of a `StorageDoubleMap`, or the keys in
+// the (tuple) prefix of a `StorageNMap`.
+fn prefix_len<'tcx>(cx: &LateContext<'tcx>, fn_id: DefId, substs: SubstsRef<'tcx>, storage: Ty<'tcx>) -> usize {
+    let ty::Adt(adt, _) = storage.kind() else { return 1 };
+    if !match_def_path(cx, adt.did(), &paths::STORAGE_N_MAP) {
+        return 1;
+    }
+    let parent_count = cx.tcx.generics_of(fn_id).parent_count;
+    let partial_key = substs[parent_count..].iter().find_map(|arg| match arg.unpack() {
+        GenericArgKind::Type(ty) => Some(ty),
+        _ => None,
+    });
+    match partial_key.map(Ty::kind) {
+        Some(ty::Tuple(prefix)) => prefix.len(),
+        _ => 1,
+    }
+}
+
+// The spans of the hashers written in a storage type, in the same order as `storage_keys`.
+fn hasher_spans(cx: &LateContext<'_>, ty: &hir::Ty<'_>) -> Vec<Span> {
+    let hir::TyKind::Path(hir::QPath::Resolved(_, path)) = ty.kind else { return Vec::new() };
+    let Res::Def(_, id) = path.res else { return Vec::new() };
+    fn arg<'hir>(path: &hir::Path<'hir>, index: usize) -> Option<&'hir hir::Ty<'hir>> {
+        match path.segments.last()?.args?.args.get(index)? {
+            hir::GenericArg::Type(ty) => Some(ty),
+            _ => None,
         }
     }
+
+
+    if match_def_path(cx, id, &paths::STORAGE_MAP) {
+        arg(path, 1).map(|hasher| hasher.span).into_iter().collect()
+    } else if match_def_path(cx, id, &paths::STORAGE_DOUBLE_MAP) {
+        [arg(path, 1), arg(path, 3)].into_iter().flatten().map(|hasher| hasher.span).collect()
+    } else if match_def_path(cx, id, &paths::STORAGE_N_MAP) {
+        let Some(keys) = arg(path, 1) else { return Vec::new() };
+        let keys = match &keys.kind {
+            hir::TyKind::Tup(keys) => keys.iter().collect(),
+            _ => vec![keys],
+        };
+        // `Key<Hasher, Key>`
+        keys.into_iter()
+            .filter_map(|key| match &key.kind {
+                hir::TyKind::Path(hir::QPath::Resolved(_, path)) => arg(path, 0).map(|hasher| hasher.span),
+                _ => None,
+            })
+            .collect()
+    } else {
+        Vec::new()
+    }
 }
 
 /// Cleanup documentation decoration.
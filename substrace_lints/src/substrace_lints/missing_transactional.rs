@@ -1,25 +1,54 @@
+use super::auxiliary::frame_version::{FrameVersion, TRANSACTIONAL_BY_DEFAULT};
+use super::auxiliary::pallet::{is_extrinsic, pallets, PalletCache};
 use super::auxiliary::paths;
-use substrace_utils::diagnostics::span_lint_and_sugg;
+use substrace_utils::diagnostics::{span_lint_and_help, span_lint_and_sugg};
 use substrace_utils::source::{snippet_opt, line_span};
 use substrace_utils::match_def_path;
 use rustc_errors::Applicability;
 use rustc_hir as hir;
 use rustc_lint::{LateContext, LateLintPass};
-use rustc_session::{declare_lint, impl_lint_pass};
+use rustc_session::{declare_tool_lint, impl_lint_pass};
+use rustc_span::hygiene::{ExpnKind, MacroKind};
 use rustc_span::source_map::Span;
 
-use super::extrinsics_must_be_tagged::is_extrinsic_name;
-
-declare_lint! {
+declare_substrace_lint! {
+    /// ### What it does
+    /// Checks that extrinsics are wrapped in a storage transaction with `#[transactional]`. Since
+    /// `polkadot-v0.9.25` every extrinsic is transactional, so on those FRAME versions it instead checks
+    /// for `#[transactional]` or `with_transaction` around the whole extrinsic.
+    ///
+    /// ### Why is this bad?
+    /// On older FRAME versions, an extrinsic that returns an error after writing to storage keeps those
+    /// writes. On newer versions, the extra transaction is not needed.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// pub fn transfer(origin: OriginFor<T>) -> DispatchResult { .. }
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// #[transactional]
+    /// pub fn transfer(origin: OriginFor<T>) -> DispatchResult { .. }
+    /// ```
     pub MISSING_TRANSACTIONAL,
-    Warn,
-    "All extrinsics must use the #[transactional] macro."
+    storage,
+    "All extrinsics must use the #[transactional] macro. Since FRAME made every extrinsic transactional, an explicit transaction around the whole extrinsic is flagged instead."
 }
 
 impl_lint_pass!(MissingTransactional => [MISSING_TRANSACTIONAL]);
 
-#[derive(Clone, Default)]
-pub struct MissingTransactional;
+#[derive(Clone)]
+pub struct MissingTransactional {
+    pallets: PalletCache,
+    frame_version: Option<FrameVersion>,
+}
+
+impl MissingTransactional {
+    pub fn new(pallets: PalletCache, frame_version: Option<FrameVersion>) -> Self {
+        Self { pallets, frame_version }
+    }
+}
 
 // Check if extrinsics use with_transaction
 impl<'tcx> LateLintPass<'tcx> for MissingTransactional {
@@ -29,22 +58,58 @@ impl<'tcx> LateLintPass<'tcx> for MissingTransactional {
         _: &'tcx hir::FnDecl<'tcx>,
         fn_body: &'tcx hir::Body<'tcx>,
         _: Span,
-        _: hir::hir_id::HirId) {
-        if let hir::intravisit::FnKind::Method(rustc_span::symbol::Ident {name, ..}, fn_sig) = fn_kind 
-            && is_extrinsic_name(name, cx) {
-
-            // If it uses a with_transaction function as final expression, then no flag.
-            if let hir::ExprKind::Block(body_block, _) = fn_body.value.kind
-                && let Some(block_expr) = body_block.expr 
-                && let hir::ExprKind::Call(call_expr, _) = block_expr.kind 
-                && let hir::ExprKind::Path(qpath) = &call_expr.kind 
+        hir_id: hir::hir_id::HirId) {
+        if let hir::intravisit::FnKind::Method(_, fn_sig) = fn_kind
+            && is_extrinsic(pallets(cx, &self.pallets), cx.tcx.hir().local_def_id(hir_id).to_def_id()) {
+
+            // If it uses a with_transaction function as final expression, then it is transactional.
+            let transaction = if let hir::ExprKind::Block(body_block, _) = fn_body.value.kind
+                && let Some(block_expr) = body_block.expr
+                && let hir::ExprKind::Call(call_expr, _) = block_expr.kind
+                && let hir::ExprKind::Path(qpath) = &call_expr.kind
                 && let hir::QPath::Resolved(_, path) = qpath
                 && let hir::def::Res::Def(_, def_id) = path.res
                 && match_def_path(cx, def_id, &paths::WITH_TRANSACTION) {
+                Some(block_expr)
+            } else {
+                None
+            };
+
+            let transactional_by_default = self
+                .frame_version
+                .as_ref()
+                .map_or(false, |version| version.has(&TRANSACTIONAL_BY_DEFAULT));
 
+            if transactional_by_default {
+                let Some(transaction) = transaction else { return };
+
+                if let ExpnKind::Macro(MacroKind::Attr, name) = transaction.span.ctxt().outer_expn_data().kind
+                    && name.as_str() == "transactional" {
+                    span_lint_and_help(
+                        cx,
+                        MISSING_TRANSACTIONAL,
+                        fn_sig.span,
+                        "substrace: #[transactional] on extrinsic is not needed",
+                        None,
+                        "remove the #[transactional] macro, this FRAME version already runs every extrinsic in a storage transaction",
+                    );
+                } else {
+                    span_lint_and_help(
+                        cx,
+                        MISSING_TRANSACTIONAL,
+                        transaction.span,
+                        "substrace: `with_transaction` around the whole extrinsic is not needed",
+                        None,
+                        "run the body directly, this FRAME version already runs every extrinsic in a storage transaction",
+                    );
+                }
+                return;
+            }
+
+            if transaction.is_some() {
                 return;
             }
-            
+
             let fn_sig_span_str = snippet_opt(cx, line_span(cx, fn_sig.span)).expect("Should be a valid span.");
 
             let suggestion = format!("#[transactional]\n{fn_sig_span_str}");
@@ -59,4 +124,4 @@ impl<'tcx> LateLintPass<'tcx> for MissingTransactional {
             );
         }
     }
-}
\ No newline at end of file
+}
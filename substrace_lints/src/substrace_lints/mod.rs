@@ -1,6 +1,24 @@
+pub mod constant_weights;
+pub mod db_weight_mismatch;
 pub mod enable_singlepass_benchmarks;
 pub mod extrinsics_must_be_tagged;
+pub mod flat_weights;
+pub mod floating_point;
+pub mod ignored_currency_results;
+pub mod insecure_randomness;
+pub mod lossy_conversions;
 pub mod missing_security_doc;
 pub mod missing_transactional;
 pub mod no_panics;
-mod auxiliary;
\ No newline at end of file
+pub mod nondeterminism;
+pub mod reachable_panics;
+pub mod storage_iter_insert;
+pub mod storage_write_before_check;
+pub mod unbounded_extrinsic_params;
+pub mod unbounded_storage;
+pub mod unbounded_storage_iteration;
+pub mod unbounded_storage_removal;
+pub mod unchecked_arithmetic;
+pub mod unchecked_origin;
+pub mod xcm_config_check;
+pub(crate) mod auxiliary;
\ No newline at end of file
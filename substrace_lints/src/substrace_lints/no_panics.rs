@@ -1,133 +1,308 @@
-use substrace_utils::diagnostics::span_lint_and_sugg;
-use enumset::{EnumSet, EnumSetType};
-use if_chain::if_chain;
-use rustc_ast::{self as ast, *};
-use rustc_errors::Applicability;
+use crate::utils::conf::lookup_clippy_conf_file;
+use substrace_utils::diagnostics::span_lint_and_then;
 use substrace_utils::source::snippet_opt;
+use rustc_ast::NestedMetaItem;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_errors::Applicability;
+use rustc_hir::CRATE_HIR_ID;
+use rustc_lint::{LateContext, LateLintPass, LintContext};
+use rustc_session::lint::Level;
+use rustc_session::{declare_tool_lint, impl_lint_pass};
+use rustc_span::{hygiene::SyntaxContext, BytePos, Span, Symbol};
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::Value;
 
-use rustc_lint::{LateContext, LateLintPass};
-use rustc_session::{declare_lint, impl_lint_pass};
-use rustc_span::{hygiene::SyntaxContext, BytePos, Span};
-use std::str::FromStr;
-
-declare_lint! {
+declare_substrace_lint! {
+    /// ### What it does
+    /// Checks that the Clippy lints against panicking code are enabled in pallet and runtime crates, through
+    /// crate attributes, the `[lints]` table in `Cargo.toml` or the `rustflags`.
+    ///
+    /// ### Why is this bad?
+    /// A panic in the runtime can halt block production.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// #![cfg_attr(not(feature = "std"), no_std)]
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// #![cfg_attr(not(feature = "std"), no_std)]
+    /// #![warn(
+    ///     clippy::disallowed_methods,
+    ///     clippy::indexing_slicing,
+    ///     clippy::todo,
+    ///     clippy::unwrap_used,
+    ///     clippy::panic,
+    /// )]
+    /// ```
     pub PANICS,
-    Warn,
+    security,
     "any type of panicking code may not be present in the runtime."
 }
 
 impl_lint_pass!(Panics => [PANICS]);
 
-/// Clippy attributes which must be enabled in crates made for the substrate runtime or pallets.
-#[derive(EnumSetType, Debug)]
-pub enum RequiredAttributes {
-    DisallowedMethod,
-    IndexingSlicing,
-    Todo,
-    UnwrapUsed,
-    Panic,
+/// Checks that the Clippy lints against panics, `panics-required-lints` in `substrace.toml`, are
+/// enabled in pallet and runtime crates.
+///
+/// The lints are read from, in increasing order of precedence, the `[lints]` table in `Cargo.toml`
+/// (possibly inherited from the workspace), the lint flags passed to rustc (like the `rustflags` in
+/// `.cargo/config.toml`) and the attributes of the crate.
+#[derive(Debug, Default)]
+pub struct Panics {
+    required_lints: Vec<String>,
 }
 
-impl FromStr for RequiredAttributes {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        use RequiredAttributes::*;
-
-        match s {
-            "disallowed_methods" => Ok(DisallowedMethod),
-            "indexing_slicing" => Ok(IndexingSlicing),
-            "todo" => Ok(Todo),
-            "unwrap_used" => Ok(UnwrapUsed),
-            "panic" => Ok(Panic),
-            _ => Err(()),
+impl Panics {
+    pub fn new(required_lints: &[String]) -> Self {
+        Self {
+            required_lints: required_lints.iter().map(|lint| lint_name(lint).to_owned()).collect(),
         }
     }
 }
 
-impl ToString for RequiredAttributes {
-    fn to_string(&self) -> String {
-        let s = match self {
-            RequiredAttributes::DisallowedMethod => "disallowed_methods",
-            RequiredAttributes::IndexingSlicing => "indexing_slicing",
-            RequiredAttributes::Todo => "todo",
-            RequiredAttributes::UnwrapUsed => "unwrap_used",
-            RequiredAttributes::Panic => "panic",
-        };
-        s.to_owned()
-    }
+/// A `[lints.clippy]` table in a `Cargo.toml`.
+struct CargoLints {
+    manifest: PathBuf,
+    /// `[lints.clippy]` or `[workspace.lints.clippy]`
+    table: &'static str,
+    levels: Vec<(String, String)>,
 }
 
-fn format_help(diff: EnumSet<RequiredAttributes>) -> String {
-    let mut help = String::from("#![warn(\n");
-    for item in diff {
-        help.push_str(&format!("    clippy::{},\n", item.to_string()));
-    }
-    help.push_str(")]\n");
-    help
-}
+impl<'tcx> LateLintPass<'tcx> for Panics {
+    fn check_crate_post(&mut self, cx: &LateContext<'tcx>) {
+        if !depends_on_frame(cx) {
+            return;
+        }
 
-#[derive(Debug, Default)]
-pub struct Panics {
-    seen_attributes: EnumSet<RequiredAttributes>,
-}
+        let mut enabled = FxHashMap::default();
 
-impl Panics {
-    pub fn new() -> Self {
-        Default::default()
-    }
-}
+        let cargo_lints = cargo_lints();
+        for (lint, level) in cargo_lints.iter().flat_map(|cargo_lints| &cargo_lints.levels) {
+            enabled.insert(lint.clone(), matches!(level.as_str(), "warn" | "deny" | "forbid"));
+        }
 
-impl<'hir> LateLintPass<'hir> for Panics {
-    fn check_attribute(&mut self, _: &LateContext<'_>, attr: &ast::Attribute) {
-        if let Some(items) = &attr.meta_item_list() {
-            if let Some(ident) = attr.ident() {
-                let ident = ident.as_str();
-                if matches!(ident, "warn" | "deny" | "forbid") {
-                    items.iter().for_each(|item| {
-                        if let Some(attr1) = extract_clippy_lint(item) {
-                            self.seen_attributes.insert(attr1);
-                        }
+        // The flag of the first Clippy lint enabled on the command line, e.g. `-D`.
+        let mut in_flags = false;
+        let mut flag = None;
+        for (lint, level) in &cx.sess().opts.lint_opts {
+            if let Some(lint) = lint.strip_prefix("clippy::") {
+                in_flags = true;
+                let is_enabling = !matches!(level, Level::Allow | Level::Expect(_));
+                if is_enabling {
+                    flag.get_or_insert(match level {
+                        Level::Deny => "-D",
+                        Level::Forbid => "-F",
+                        _ => "-W",
                     });
                 }
+                enabled.insert(lint.replace('-', "_"), is_enabling);
             }
         }
-    }
 
-    fn check_crate_post(&mut self, cx: &LateContext<'hir>) {
-        let diff = EnumSet::<RequiredAttributes>::all().difference(self.seen_attributes);
-
-        let span = Span::new(BytePos(0), BytePos(1), SyntaxContext::root(), None);
-
-        if !diff.is_empty()
-            && let Some(span_text) = snippet_opt(cx, span) {
-            
-            let mut suggestion = format_help(diff);
-            suggestion.push_str(&span_text);
-
-            span_lint_and_sugg(
-                cx,
-                PANICS,
-                span,
-                "substrace: clippy must be configured to warn or deny about any panicking code",
-                "insert attributes at the root of the crate",
-                suggestion,
-                Applicability::MachineApplicable,
-            );
+        // The first crate attribute that enables Clippy lints.
+        let mut lint_attr = None;
+        for attr in cx.tcx.hir().attrs(CRATE_HIR_ID) {
+            let Some(level) = attr.ident().map(|ident| ident.name) else { continue };
+            let is_enabling = matches!(level.as_str(), "warn" | "deny" | "forbid");
+            if !(is_enabling || level.as_str() == "allow") {
+                continue;
+            }
+            let Some(items) = attr.meta_item_list() else { continue };
+            for item in &items {
+                if let Some(lint) = clippy_lint(item) {
+                    if is_enabling {
+                        lint_attr.get_or_insert((attr, items.last().map(NestedMetaItem::span)));
+                    }
+                    enabled.insert(lint.to_string(), is_enabling);
+                }
+            }
+        }
+
+        let missing: Vec<&str> = self
+            .required_lints
+            .iter()
+            .map(String::as_str)
+            .filter(|lint| !enabled.get(*lint).copied().unwrap_or(false))
+            .collect();
+        // `clippy::disallowed_methods` does nothing without a list of methods in `clippy.toml`.
+        let unconfigured_disallowed_methods = self.required_lints.iter().any(|lint| lint == "disallowed_methods")
+            && !missing.contains(&"disallowed_methods")
+            && !has_disallowed_methods();
+
+        if missing.is_empty() && !unconfigured_disallowed_methods {
+            return;
         }
+
+        let crate_start = Span::new(BytePos(0), BytePos(1), SyntaxContext::root(), None);
+        let span = lint_attr.map_or(crate_start, |(attr, _)| attr.span);
+
+        span_lint_and_then(
+            cx,
+            PANICS,
+            span,
+            "substrace: clippy must be configured to warn or deny about any panicking code",
+            |diag| {
+                if unconfigured_disallowed_methods {
+                    diag.note(
+                        "`clippy::disallowed_methods` only warns about the methods listed in `disallowed-methods` in `clippy.toml`",
+                    );
+                }
+                if missing.is_empty() {
+                    return;
+                }
+
+                if let Some(cargo_lints) = cargo_lints.filter(|cargo_lints| !cargo_lints.levels.is_empty()) {
+                    let level = cargo_lints
+                        .levels
+                        .iter()
+                        .map(|(_, level)| level.as_str())
+                        .find(|level| matches!(*level, "warn" | "deny" | "forbid"))
+                        .unwrap_or("warn");
+                    let mut help = format!(
+                        "add the lints to the `{}` table in `{}`:",
+                        cargo_lints.table,
+                        relative(&cargo_lints.manifest).display()
+                    );
+                    for lint in &missing {
+                        let _ = write!(help, "\n{lint} = \"{level}\"");
+                    }
+                    diag.help(help);
+                } else if let Some((attr, Some(last_item))) = lint_attr {
+                    let suggestion: String = missing.iter().map(|lint| format!(", clippy::{lint}")).collect();
+                    diag.span_suggestion(
+                        last_item.shrink_to_hi(),
+                        format!("add the lints to the `{}` attribute", attr.name_or_empty()),
+                        suggestion,
+                        Applicability::MachineApplicable,
+                    );
+                } else if in_flags {
+                    let flags = missing
+                        .iter()
+                        .map(|lint| format!("\"{}clippy::{lint}\"", flag.unwrap_or("-W")))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    let location = rustflags_config().map_or_else(
+                        || String::from("`RUSTFLAGS`"),
+                        |config| format!("the `rustflags` in `{}`", relative(&config).display()),
+                    );
+                    diag.help(format!("add the lints to {location}: {flags}"));
+                } else if let Some(crate_text) = snippet_opt(cx, crate_start) {
+                    let mut suggestion = String::from("#![warn(\n");
+                    for lint in &missing {
+                        let _ = writeln!(suggestion, "    clippy::{lint},");
+                    }
+                    suggestion.push_str(")]\n");
+                    suggestion.push_str(&crate_text);
+                    diag.span_suggestion(
+                        crate_start,
+                        "insert attributes at the root of the crate",
+                        suggestion,
+                        Applicability::MachineApplicable,
+                    );
+                }
+            },
+        );
     }
 }
 
-fn extract_clippy_lint(lint: &NestedMetaItem) -> Option<RequiredAttributes> {
-    if_chain! {
-        if let Some(meta_item) = lint.meta_item();
-        if meta_item.path.segments.len() > 1;
-        if let tool_name = meta_item.path.segments[0].ident;
-        if tool_name.name.as_str() == "clippy";
-        let lint_name = meta_item.path.segments.last().unwrap().ident.name;
-        then {
-            return RequiredAttributes::from_str(lint_name.as_str()).ok();
-        }
+// Only pallets and runtimes, which depend on `frame_support` themselves, need to be checked.
+fn depends_on_frame(cx: &LateContext<'_>) -> bool {
+    cx.tcx.crates(()).iter().any(|&krate| {
+        cx.tcx.crate_name(krate).as_str() == "frame_support"
+            && cx
+                .tcx
+                .extern_crate(krate.as_def_id())
+                .map_or(false, |extern_crate| extern_crate.is_direct())
+    })
+}
+
+fn lint_name(lint: &str) -> &str {
+    lint.strip_prefix("clippy::").unwrap_or(lint)
+}
+
+fn clippy_lint(item: &NestedMetaItem) -> Option<Symbol> {
+    let meta_item = item.meta_item()?;
+    match &meta_item.path.segments[..] {
+        [tool, lint] if tool.ident.name.as_str() == "clippy" => Some(lint.ident.name),
+        _ => None,
     }
-    None
+}
+
+fn manifest_dir() -> Option<PathBuf> {
+    fs::canonicalize(env::var_os("CARGO_MANIFEST_DIR")?).ok()
+}
+
+// Shows `path` relative to the directory cargo is run from, which is where the user is.
+fn relative(path: &Path) -> &Path {
+    env::current_dir()
+        .ok()
+        .and_then(|current_dir| path.strip_prefix(current_dir).ok())
+        .unwrap_or(path)
+}
+
+fn read_toml(path: &Path) -> Option<Value> {
+    toml::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+// Reads the `[lints.clippy]` table of the crate, or of the workspace with `[lints] workspace = true`.
+fn cargo_lints() -> Option<CargoLints> {
+    let manifest_dir = manifest_dir()?;
+    let manifest = manifest_dir.join("Cargo.toml");
+    let lints = read_toml(&manifest)?.get("lints")?.clone();
+
+    let (manifest, table, lints) = if lints.get("workspace").and_then(Value::as_bool) == Some(true) {
+        manifest_dir.ancestors().skip(1).find_map(|dir| {
+            let manifest = dir.join("Cargo.toml");
+            let lints = read_toml(&manifest)?.get("workspace")?.get("lints").cloned();
+            Some((manifest, "[workspace.lints.clippy]", lints))
+        })?
+    } else {
+        (manifest, "[lints.clippy]", Some(lints))
+    };
+
+    let levels = lints
+        .as_ref()
+        .and_then(|lints| lints.get("clippy")?.as_table().cloned())
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(lint, level)| {
+            // `lint = "deny"` or `lint = { level = "deny", priority = 1 }`
+            let level = level.as_str().or_else(|| level.get("level")?.as_str())?.to_owned();
+            Some((lint.replace('-', "_"), level))
+        })
+        .collect();
+
+    Some(CargoLints { manifest, table, levels })
+}
+
+// Finds the `.cargo/config.toml` whose `rustflags` configure Clippy lints.
+fn rustflags_config() -> Option<PathBuf> {
+    manifest_dir()?.ancestors().find_map(|dir| {
+        ["config.toml", "config"].iter().find_map(|name| {
+            let config = dir.join(".cargo").join(name);
+            let value = read_toml(&config)?;
+            let build = value.get("build").into_iter();
+            let targets = value.get("target").and_then(Value::as_table).into_iter().flat_map(|targets| targets.values());
+            build
+                .chain(targets)
+                .filter_map(|table| table.get("rustflags")?.as_array())
+                .flatten()
+                .any(|flag| flag.as_str().map_or(false, |flag| flag.contains("clippy::")))
+                .then_some(config)
+        })
+    })
+}
+
+fn has_disallowed_methods() -> bool {
+    lookup_clippy_conf_file()
+        .ok()
+        .flatten()
+        .and_then(|clippy_toml| read_toml(&clippy_toml))
+        .and_then(|conf| Some(!conf.get("disallowed-methods")?.as_array()?.is_empty()))
+        .unwrap_or(false)
 }
@@ -0,0 +1,193 @@
+use super::auxiliary::attrs::is_runtime_crate;
+use super::auxiliary::call_graph::called_fn;
+use super::auxiliary::paths;
+use substrace_utils::diagnostics::span_lint_and_then;
+use substrace_utils::higher::ForLoop;
+use substrace_utils::ty::is_type_diagnostic_item;
+use substrace_utils::{is_expn_of, match_any_def_paths, match_def_path};
+use rustc_hir as hir;
+use rustc_hir::def::{DefKind, Res};
+use rustc_hir::def_id::DefId;
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::ty::{self, Ty};
+use rustc_session::{declare_tool_lint, impl_lint_pass};
+use rustc_span::{sym, Span};
+
+declare_substrace_lint! {
+    /// ### What it does
+    /// Checks for APIs that can behave differently between native and wasm execution, or between
+    /// nodes, in crates compiled for the runtime (`no_std` without the `std` feature):
+    /// - iterating over a `HashMap` or `HashSet`,
+    /// - `SystemTime` and `Instant`,
+    /// - the `rand` crate,
+    /// - global state: `static mut`, `thread_local!`, `lazy_static!` and `once_cell` globals.
+    ///
+    /// ### Why is this bad?
+    /// Nodes that compute a different state root for the same block fork off, which halts a
+    /// parachain or splits the network.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// let mut votes: HashMap<T::AccountId, u32> = HashMap::new();
+    /// for (who, count) in votes.iter() { .. }
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// let mut votes: BTreeMap<T::AccountId, u32> = BTreeMap::new();
+    /// for (who, count) in votes.iter() { .. }
+    /// ```
+    pub NONDETERMINISM,
+    security,
+    "The runtime must behave the same on every node, natively and in wasm."
+}
+
+impl_lint_pass!(Nondeterminism => [NONDETERMINISM]);
+
+#[derive(Clone, Default)]
+pub struct Nondeterminism {
+    /// Whether the crate is compiled for the runtime.
+    runtime_crate: bool,
+}
+
+/// The methods of `HashMap` and `HashSet` that iterate in the order of the hashes of the keys.
+const HASH_ITER_METHODS: [&str; 9] = [
+    "iter",
+    "iter_mut",
+    "keys",
+    "values",
+    "values_mut",
+    "into_iter",
+    "into_keys",
+    "into_values",
+    "drain",
+];
+
+/// The crates that produce random numbers from the entropy of the node.
+const RANDOM_CRATES: [&str; 2] = ["rand", "getrandom"];
+
+/// The crates with lazily initialized globals.
+const LAZY_CRATES: [&str; 2] = ["once_cell", "lazy_static"];
+
+impl<'tcx> LateLintPass<'tcx> for Nondeterminism {
+    fn check_crate(&mut self, cx: &LateContext<'tcx>) {
+        self.runtime_crate = is_runtime_crate(cx);
+    }
+
+    fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx hir::Expr<'_>) {
+        if !self.runtime_crate {
+            return;
+        }
+        // The `for` loop itself is desugared, the iterated expression isn't.
+        if let Some(for_loop) = ForLoop::hir(expr) {
+            let ty = cx.typeck_results().expr_ty(for_loop.arg).peel_refs();
+            if !for_loop.arg.span.from_expansion() && is_hash_collection(cx, ty) {
+                lint_hash_iteration(cx, for_loop.arg.span, ty);
+            }
+            return;
+        }
+        if expr.span.from_expansion() {
+            return;
+        }
+
+        if let hir::ExprKind::Path(ref qpath) = expr.kind
+            && let Res::Def(DefKind::Static(_) | DefKind::Const, def_id) = cx.qpath_res(qpath, expr.hir_id)
+            && let Some(global) = global_state(cx, def_id)
+        {
+            span_lint_and_then(
+                cx,
+                NONDETERMINISM,
+                expr.span,
+                &format!("substrace: `{}` is {global}", cx.tcx.item_name(def_id)),
+                |diag| {
+                    diag.span_note(cx.tcx.def_span(def_id), "defined here");
+                    diag.note("it outlives the block, and isn't shared between the nodes or between native and wasm execution");
+                    diag.help("keep the state in a storage item instead");
+                },
+            );
+            return;
+        }
+
+        let Some((fn_id, substs)) = called_fn(cx.typeck_results(), expr) else { return };
+        let name = cx.tcx.item_name(fn_id);
+        let self_ty = cx
+            .tcx
+            .impl_of_method(fn_id)
+            .map(|impl_id| cx.tcx.bound_type_of(impl_id).subst(cx.tcx, substs))
+            .or_else(|| cx.tcx.trait_of_item(fn_id).and_then(|_| substs.types().next()))
+            .map(Ty::peel_refs);
+
+        if let Some(self_ty) = self_ty
+            && is_hash_collection(cx, self_ty)
+            && HASH_ITER_METHODS.contains(&name.as_str())
+        {
+            lint_hash_iteration(cx, expr.span, self_ty);
+        } else if let Some(self_ty) = self_ty
+            && let ty::Adt(adt, _) = self_ty.kind()
+            && match_any_def_paths(cx, adt.did(), &[&paths::INSTANT, &paths::SYSTEM_TIME]).is_some()
+        {
+            span_lint_and_then(
+                cx,
+                NONDETERMINISM,
+                expr.span,
+                &format!("substrace: `{self_ty}::{name}` reads the clock of the node"),
+                |diag| {
+                    diag.note("every node has its own clock, and it isn't available in wasm");
+                    diag.help("use the timestamp of the block, e.g. from `pallet_timestamp` or `UnixTime`");
+                },
+            );
+        } else if RANDOM_CRATES.contains(&cx.tcx.crate_name(fn_id.krate).as_str()) {
+            span_lint_and_then(
+                cx,
+                NONDETERMINISM,
+                expr.span,
+                &format!("substrace: `{}` returns a different value on every node", cx.tcx.def_path_str(fn_id)),
+                |diag| {
+                    diag.help("use on-chain randomness, e.g. from a `Randomness` implementation based on a VRF");
+                },
+            );
+        }
+    }
+}
+
+fn is_hash_collection(cx: &LateContext<'_>, ty: Ty<'_>) -> bool {
+    is_type_diagnostic_item(cx, ty, sym::HashMap) || is_type_diagnostic_item(cx, ty, sym::HashSet)
+}
+
+fn lint_hash_iteration(cx: &LateContext<'_>, span: Span, ty: Ty<'_>) {
+    let (kind, ordered) = if is_type_diagnostic_item(cx, ty, sym::HashMap) {
+        ("HashMap", "BTreeMap")
+    } else {
+        ("HashSet", "BTreeSet")
+    };
+    span_lint_and_then(
+        cx,
+        NONDETERMINISM,
+        span,
+        &format!("substrace: iterating over a `{kind}` is nondeterministic"),
+        |diag| {
+            diag.note("the order depends on a random seed that differs between nodes");
+            diag.help(format!("use a `{ordered}` instead"));
+        },
+    );
+}
+
+// Describes the static or constant `def_id` if it holds state outside of the storage.
+fn global_state(cx: &LateContext<'_>, def_id: DefId) -> Option<&'static str> {
+    if cx.tcx.is_mutable_static(def_id) {
+        return Some("a `static mut`");
+    }
+    match cx.tcx.type_of(def_id).kind() {
+        ty::Adt(adt, _) if match_def_path(cx, adt.did(), &paths::LOCAL_KEY) => Some("a `thread_local!`"),
+        ty::Adt(adt, _) if LAZY_CRATES.contains(&cx.tcx.crate_name(adt.did().krate).as_str()) => {
+            Some("a lazily initialized global")
+        },
+        _ if cx.tcx.def_kind(def_id) != DefKind::Const
+            && def_id.is_local()
+            && is_expn_of(cx.tcx.def_span(def_id), "lazy_static").is_some() =>
+        {
+            Some("a lazily initialized global")
+        },
+        _ => None,
+    }
+}
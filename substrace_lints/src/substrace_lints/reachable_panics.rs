@@ -0,0 +1,184 @@
+use super::auxiliary::call_graph::{entry_points, reachable, reachable_note};
+use super::auxiliary::pallet::PalletCache;
+use substrace_utils::diagnostics::span_lint_hir_and_then;
+use substrace_utils::macros::{is_panic, root_macro_call_first_node};
+use substrace_utils::ty::{is_type_diagnostic_item, peel_mid_ty_refs};
+use substrace_utils::visitors::{for_each_expr_with_closures, Descend};
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir as hir;
+use rustc_hir::def_id::LocalDefId;
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::ty;
+use rustc_session::{declare_tool_lint, impl_lint_pass};
+use rustc_span::{sym, Span};
+use std::ops::ControlFlow;
+
+declare_substrace_lint! {
+    /// ### What it does
+    /// Checks for `unwrap`, `expect`, panicking macros and indexing that can panic in functions reachable
+    /// from dispatchables, hooks and runtime APIs.
+    ///
+    /// ### Why is this bad?
+    /// A panic in the runtime can halt block production.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// pub fn transfer(origin: OriginFor<T>, amounts: Vec<u64>, index: usize) -> DispatchResult {
+    ///     let amount = amounts[index];
+    ///     ..
+    /// }
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// pub fn transfer(origin: OriginFor<T>, amounts: Vec<u64>, index: usize) -> DispatchResult {
+    ///     let amount = amounts.get(index).ok_or(Error::<T>::InvalidIndex)?;
+    ///     ..
+    /// }
+    /// ```
+    pub REACHABLE_PANICS,
+    security,
+    "Code reachable from dispatchables, hooks and runtime APIs should not panic, as a panic in the runtime can halt the chain."
+}
+
+impl_lint_pass!(ReachablePanics => [REACHABLE_PANICS]);
+
+#[derive(Clone)]
+pub struct ReachablePanics {
+    pallets: PalletCache,
+}
+
+impl ReachablePanics {
+    pub fn new(pallets: PalletCache) -> Self {
+        Self { pallets }
+    }
+}
+
+enum PanicKind {
+    /// `unwrap`, `expect`, ...
+    Method(String),
+    /// `panic!`, `assert!`, ...
+    Macro(String),
+    Indexing,
+}
+
+struct Panic {
+    hir_id: hir::HirId,
+    span: Span,
+    kind: PanicKind,
+}
+
+impl<'tcx> LateLintPass<'tcx> for ReachablePanics {
+    fn check_crate_post(&mut self, cx: &LateContext<'tcx>) {
+        let mut reported = FxHashSet::default();
+
+        for entry in entry_points(cx, &self.pallets) {
+            for reached in reachable(cx, entry.def_id) {
+                for panic in find_panics(cx, reached.def_id) {
+                    if !reported.insert(panic.hir_id) {
+                        continue;
+                    }
+
+                    let (what, help) = match &panic.kind {
+                        PanicKind::Method(name) => (
+                            format!("`{name}`"),
+                            "return an error instead, e.g. with `ok_or` and `?`",
+                        ),
+                        PanicKind::Macro(name) => (
+                            format!("`{name}!`"),
+                            "return an error instead, e.g. with `ensure!`",
+                        ),
+                        PanicKind::Indexing => (
+                            String::from("indexing"),
+                            "use `get` and handle the `None` case instead",
+                        ),
+                    };
+
+                    span_lint_hir_and_then(
+                        cx,
+                        REACHABLE_PANICS,
+                        panic.hir_id,
+                        panic.span,
+                        &format!("substrace: {what} can panic in the runtime"),
+                        |diag| {
+                            diag.span_note(cx.tcx.def_span(entry.def_id), reachable_note(cx, &entry, &reached));
+                            diag.help(help);
+                        },
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn find_panics(cx: &LateContext<'_>, def_id: LocalDefId) -> Vec<Panic> {
+    let Some(body_id) = cx.tcx.hir().maybe_body_owned_by(def_id) else { return Vec::new() };
+    let typeck = cx.tcx.typeck(def_id);
+
+    let mut panics = Vec::new();
+    for_each_expr_with_closures(cx, cx.tcx.hir().body(body_id).value, |expr| {
+        if let Some(macro_call) = root_macro_call_first_node(cx, expr)
+            && let Some(name) = panic_macro_name(cx, macro_call.def_id) {
+            panics.push(Panic {
+                hir_id: expr.hir_id,
+                span: macro_call.span,
+                kind: PanicKind::Macro(name),
+            });
+            return ControlFlow::<(), _>::Continue(Descend::No);
+        }
+
+        match expr.kind {
+            hir::ExprKind::MethodCall(path, receiver, _, _)
+                if matches!(path.ident.as_str(), "unwrap" | "expect" | "unwrap_err" | "expect_err") =>
+            {
+                let (receiver_ty, _) = peel_mid_ty_refs(typeck.expr_ty_adjusted(receiver));
+                if is_type_diagnostic_item(cx, receiver_ty, sym::Option)
+                    || is_type_diagnostic_item(cx, receiver_ty, sym::Result)
+                {
+                    panics.push(Panic {
+                        hir_id: expr.hir_id,
+                        span: path.ident.span.to(expr.span.shrink_to_hi()),
+                        kind: PanicKind::Method(path.ident.to_string()),
+                    });
+                }
+            },
+            hir::ExprKind::Index(indexed, index) => {
+                let (indexed_ty, _) = peel_mid_ty_refs(typeck.expr_ty_adjusted(indexed));
+                let can_panic = match indexed_ty.kind() {
+                    // Constant indices into arrays are checked by the compiler.
+                    ty::Array(..) => !matches!(index.kind, hir::ExprKind::Lit(_)),
+                    ty::Slice(_) | ty::Str => true,
+                    _ => [sym::Vec, sym::VecDeque, sym::HashMap, sym::BTreeMap]
+                        .into_iter()
+                        .any(|item| is_type_diagnostic_item(cx, indexed_ty, item)),
+                };
+                if can_panic {
+                    panics.push(Panic {
+                        hir_id: expr.hir_id,
+                        span: expr.span,
+                        kind: PanicKind::Indexing,
+                    });
+                }
+            },
+            _ => {},
+        }
+        ControlFlow::Continue(Descend::Yes)
+    });
+    panics
+}
+
+fn panic_macro_name(cx: &LateContext<'_>, macro_id: rustc_hir::def_id::DefId) -> Option<String> {
+    let is_panicking = is_panic(cx, macro_id)
+        || matches!(
+            cx.tcx.get_diagnostic_name(macro_id),
+            Some(
+                sym::unreachable_macro
+                    | sym::todo_macro
+                    | sym::unimplemented_macro
+                    | sym::assert_macro
+                    | sym::assert_eq_macro
+                    | sym::assert_ne_macro
+            )
+        );
+    is_panicking.then(|| cx.tcx.item_name(macro_id).to_string())
+}
@@ -0,0 +1,270 @@
+use super::auxiliary::storage::{storage_call, storage_name};
+use substrace_utils::diagnostics::span_lint_hir_and_then;
+use substrace_utils::fn_has_unsatisfiable_preds;
+use substrace_utils::mir::{visit_local_usage, PossibleBorrowerMap};
+use substrace_utils::ty::implements_trait_with_env;
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir as hir;
+use rustc_hir::def_id::DefId;
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::mir::{self, BasicBlock, Local, Location, Operand, Rvalue, StatementKind, TerminatorKind};
+use rustc_middle::ty::{self, Ty};
+use rustc_session::{declare_tool_lint, impl_lint_pass};
+use rustc_span::source_map::Span;
+use rustc_span::sym;
+
+declare_substrace_lint! {
+    /// ### What it does
+    /// Checks for writes to a storage item while an iterator over the same storage item is still in use.
+    ///
+    /// ### Why is this bad?
+    /// The iterators of FRAME read the storage lazily, so writing to the storage while iterating over it
+    /// results in skipped or repeated items.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// for (key, value) in Something::<T>::iter() {
+    ///     Something::<T>::insert(key, value + 1);
+    /// }
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// for (key, value) in Something::<T>::iter().collect::<Vec<_>>() {
+    ///     Something::<T>::insert(key, value + 1);
+    /// }
+    /// ```
+    pub STORAGE_ITER_INSERT,
+    storage,
+    "Storage items must not be modified while an iterator over them is still in use."
+}
+
+impl_lint_pass!(StorageIterInsert => [STORAGE_ITER_INSERT]);
+
+#[derive(Clone, Default)]
+pub struct StorageIterInsert;
+
+/// Methods of the storage maps that return a lazy iterator over the storage.
+const ITERATORS: [&str; 8] = [
+    "iter",
+    "iter_keys",
+    "iter_values",
+    "drain",
+    "iter_prefix",
+    "iter_prefix_values",
+    "iter_key_prefix",
+    "drain_prefix",
+];
+
+/// Methods of the storage maps that write to the storage.
+const MUTATORS: [&str; 14] = [
+    "insert",
+    "remove",
+    "mutate",
+    "try_mutate",
+    "mutate_exists",
+    "try_mutate_exists",
+    "take",
+    "swap",
+    "append",
+    "try_append",
+    "remove_prefix",
+    "clear_prefix",
+    "remove_all",
+    "clear",
+];
+
+/// An iterator over a storage item, created at `span` and stored in `local`.
+struct StorageIter<'tcx> {
+    local: Local,
+    storage: Ty<'tcx>,
+    span: Span,
+}
+
+// The iterators of FRAME read the storage lazily, so writing to the same storage item while an
+// iterator is still being used results in skipped or repeated items.
+impl<'tcx> LateLintPass<'tcx> for StorageIterInsert {
+    fn check_fn(&mut self,
+        cx: &LateContext<'tcx>,
+        _: hir::intravisit::FnKind<'tcx>,
+        _: &'tcx hir::FnDecl<'tcx>,
+        _: &'tcx hir::Body<'tcx>,
+        _: Span,
+        hir_id: hir::hir_id::HirId) {
+        let def_id = cx.tcx.hir().local_def_id(hir_id).to_def_id();
+
+        // Building MIR for `fn`s with unsatisfiable preds results in ICE.
+        if fn_has_unsatisfiable_preds(cx, def_id) {
+            return;
+        }
+
+        let mir = cx.tcx.optimized_mir(def_id);
+        let Some(iterator_trait) = cx.tcx.get_diagnostic_item(sym::Iterator) else { return };
+
+        let iterators = storage_iterators(cx, mir);
+        if iterators.is_empty() {
+            return;
+        }
+
+        let mut possible_borrowers = PossibleBorrowerMap::new(cx, mir);
+        let mut linted = FxHashSet::default();
+
+        for iterator in &iterators {
+            let derived = derived_locals(cx, mir, def_id, iterator_trait, iterator.local, &possible_borrowers);
+
+            for (block, data) in mir.basic_blocks.iter_enumerated() {
+                let location = mir.terminator_loc(block);
+                let TerminatorKind::Call { args, .. } = &data.terminator().kind else { continue };
+
+                // `Foo::iter().for_each(|(k, v)| Foo::insert(k, v + 1))`
+                if args.iter().any(|arg| operand_local(arg).is_some_and(|local| derived.contains(&local))) {
+                    for arg in args {
+                        if let ty::Closure(closure_id, _) = arg.ty(mir, cx.tcx).kind() {
+                            let closure_mir = cx.tcx.optimized_mir(*closure_id);
+                            for (closure_block, closure_data) in closure_mir.basic_blocks.iter_enumerated() {
+                                if is_mutation_of(cx, closure_mir, closure_block, iterator.storage) {
+                                    let source_info = closure_data.terminator().source_info;
+                                    if linted.insert(source_info.span) {
+                                        emit_lint(cx, closure_mir, source_info, iterator);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                if is_mutation_of(cx, mir, block, iterator.storage)
+                    && derived.iter().any(|local| {
+                        possible_borrowers.local_is_alive_at(*local, location) && is_used_after(mir, *local, location)
+                    })
+                {
+                    let source_info = data.terminator().source_info;
+                    if linted.insert(source_info.span) {
+                        emit_lint(cx, mir, source_info, iterator);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn emit_lint(cx: &LateContext<'_>, mir: &mir::Body<'_>, source_info: mir::SourceInfo, iterator: &StorageIter<'_>) {
+    let name = storage_name(cx, iterator.storage);
+    let lint_root = mir.source_scopes[source_info.scope]
+        .local_data
+        .as_ref()
+        .assert_crate_local()
+        .lint_root;
+
+    span_lint_hir_and_then(
+        cx,
+        STORAGE_ITER_INSERT,
+        lint_root,
+        source_info.span,
+        &format!("substrace: `{name}` is modified while it is being iterated"),
+        |diag| {
+            diag.span_note(iterator.span, &format!("the iterator over `{name}` is created here and still in use"));
+            diag.help(&format!("collect the items into a `Vec` before modifying `{name}`"));
+        },
+    );
+}
+
+// Finds all the calls in `mir` that create an iterator over a storage item.
+fn storage_iterators<'tcx>(cx: &LateContext<'tcx>, mir: &mir::Body<'tcx>) -> Vec<StorageIter<'tcx>> {
+    let mut iterators = Vec::new();
+    for data in mir.basic_blocks.iter() {
+        let terminator = data.terminator();
+        if let TerminatorKind::Call { func, destination, .. } = &terminator.kind
+            && let Some((fn_id, substs)) = func.const_fn_def()
+            && let Some(call) = storage_call(cx, fn_id, substs)
+            && ITERATORS.contains(&call.method.as_str()) {
+            iterators.push(StorageIter {
+                local: destination.local,
+                storage: call.storage,
+                span: terminator.source_info.span,
+            });
+        }
+    }
+    iterators
+}
+
+// Whether the terminator of `block` writes to `storage`.
+fn is_mutation_of<'tcx>(cx: &LateContext<'tcx>, mir: &mir::Body<'tcx>, block: BasicBlock, storage: Ty<'tcx>) -> bool {
+    if let TerminatorKind::Call { func, .. } = &mir.basic_blocks[block].terminator().kind
+        && let Some((fn_id, substs)) = func.const_fn_def()
+        && let Some(call) = storage_call(cx, fn_id, substs) {
+        call.storage == storage && MUTATORS.contains(&call.method.as_str())
+    } else {
+        false
+    }
+}
+
+// Collects every local that (transitively) holds on to the iterator stored in `origin`: moves and
+// borrows of it, and iterator adapters like `map` or `filter` that are built from it.
+fn derived_locals<'tcx>(
+    cx: &LateContext<'tcx>,
+    mir: &mir::Body<'tcx>,
+    def_id: DefId,
+    iterator_trait: DefId,
+    origin: Local,
+    possible_borrowers: &PossibleBorrowerMap<'_, 'tcx>,
+) -> FxHashSet<Local> {
+    let param_env = cx.tcx.param_env(def_id);
+    let mut derived = FxHashSet::default();
+    derived.insert(origin);
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+
+        for data in mir.basic_blocks.iter() {
+            for statement in &data.statements {
+                if let StatementKind::Assign(box (place, rvalue)) = &statement.kind
+                    && let Rvalue::Use(Operand::Copy(from) | Operand::Move(from))
+                        | Rvalue::Ref(_, _, from)
+                        | Rvalue::Cast(_, Operand::Copy(from) | Operand::Move(from), _) = rvalue
+                    && derived.contains(&from.local) {
+                    changed |= derived.insert(place.local);
+                }
+            }
+
+            if let TerminatorKind::Call { args, destination, .. } = &data.terminator().kind
+                && args.iter().any(|arg| operand_local(arg).is_some_and(|local| derived.contains(&local)))
+                && implements_trait_with_env(
+                    cx.tcx,
+                    param_env,
+                    destination.ty(mir, cx.tcx).ty,
+                    iterator_trait,
+                    &[],
+                ) {
+                changed |= derived.insert(destination.local);
+            }
+        }
+
+        let borrowers: Vec<Local> = derived
+            .iter()
+            .filter_map(|local| possible_borrowers.map.get(local))
+            .flat_map(|borrowers| borrowers.iter())
+            .collect();
+        for borrower in borrowers {
+            changed |= derived.insert(borrower);
+        }
+    }
+
+    derived
+}
+
+fn operand_local(operand: &Operand<'_>) -> Option<Local> {
+    match operand {
+        Operand::Copy(place) | Operand::Move(place) => Some(place.local),
+        Operand::Constant(_) => None,
+    }
+}
+
+// Whether `local` may be used on some path starting right after `location`. Locations inside a loop
+// are always followed by another use of the iterator, which `visit_local_usage` gives up on.
+fn is_used_after(mir: &mir::Body<'_>, local: Local, location: Location) -> bool {
+    visit_local_usage(&[local], mir, location).map_or(true, |usage| {
+        usage.iter().any(|usage| !usage.local_use_locs.is_empty())
+    })
+}
@@ -0,0 +1,263 @@
+use super::auxiliary::call_graph::{called_fn, entry_points, reachable, reachable_note, resolve, EntryKind};
+use super::auxiliary::pallet::PalletCache;
+use super::auxiliary::frame_version::{FrameVersion, TRANSACTIONAL_BY_DEFAULT};
+use super::auxiliary::paths;
+use super::auxiliary::storage::storage_call;
+use substrace_utils::diagnostics::span_lint_hir_and_then;
+use substrace_utils::{fn_has_unsatisfiable_preds, match_def_path};
+use substrace_utils::ty::is_type_diagnostic_item;
+use substrace_utils::visitors::for_each_expr_with_closures;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::mir::{self, AggregateKind, BasicBlock, Rvalue, StatementKind, TerminatorKind};
+use rustc_middle::ty::{self, SubstsRef};
+use rustc_session::{declare_tool_lint, impl_lint_pass};
+use rustc_span::{sym, Span};
+use std::collections::VecDeque;
+use std::ops::ControlFlow;
+
+declare_substrace_lint! {
+    /// ### What it does
+    /// Checks for storage writes, directly or through a local function, that are followed by a `?`,
+    /// `ensure!` or `Err(..)` return on some path through the function. It checks:
+    /// - dispatchables and the functions they call, when the FRAME version doesn't run every
+    ///   extrinsic in a storage transaction yet, unless the dispatchable is `#[transactional]`,
+    /// - functions that use `with_storage_layer` outside of a dispatchable, such as in hooks, since
+    ///   only the closure runs in the storage layer.
+    ///
+    /// ### Why is this bad?
+    /// Without a storage layer, the writes made before returning an error are kept, leaving the
+    /// storage partially updated.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// Balances::<T>::insert(&who, new_balance);
+    /// ensure!(new_balance >= T::ExistentialDeposit::get(), Error::<T>::BelowMinimum);
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// ensure!(new_balance >= T::ExistentialDeposit::get(), Error::<T>::BelowMinimum);
+    /// Balances::<T>::insert(&who, new_balance);
+    /// ```
+    pub STORAGE_WRITE_BEFORE_CHECK,
+    storage,
+    "Storage must only be written after every check that can fail, unless a storage layer rolls the writes back."
+}
+
+impl_lint_pass!(StorageWriteBeforeCheck => [STORAGE_WRITE_BEFORE_CHECK]);
+
+#[derive(Clone)]
+pub struct StorageWriteBeforeCheck {
+    pallets: PalletCache,
+    frame_version: Option<FrameVersion>,
+}
+
+impl StorageWriteBeforeCheck {
+    pub fn new(pallets: PalletCache, frame_version: Option<FrameVersion>) -> Self {
+        Self { pallets, frame_version }
+    }
+}
+
+const FROM_RESIDUAL: [&str; 5] = ["core", "ops", "try_trait", "FromResidual", "from_residual"];
+
+/// The methods of the FRAME storage types that write to storage.
+const WRITE_METHODS: [&str; 19] = [
+    "put",
+    "set",
+    "kill",
+    "insert",
+    "remove",
+    "take",
+    "mutate",
+    "mutate_exists",
+    "mutate_extant",
+    "try_mutate",
+    "try_mutate_exists",
+    "append",
+    "try_append",
+    "swap",
+    "translate",
+    "translate_values",
+    "drain",
+    "clear_prefix",
+    "remove_all",
+];
+
+impl<'tcx> LateLintPass<'tcx> for StorageWriteBeforeCheck {
+    fn check_crate_post(&mut self, cx: &LateContext<'tcx>) {
+        // Only lint dispatchables when the FRAME version is known to lack transactional dispatch.
+        let transactional_dispatch = self.frame_version.as_ref().map_or(true, |version| version.has(&TRANSACTIONAL_BY_DEFAULT));
+
+        let mut writers = FxHashMap::default();
+        let mut checked = FxHashSet::default();
+        for entry in entry_points(cx, &self.pallets) {
+            let all_reachable = entry.kind == EntryKind::Dispatchable
+                && !transactional_dispatch
+                && !calls(cx, entry.def_id, &paths::WITH_TRANSACTION);
+            if entry.kind == EntryKind::Dispatchable && !all_reachable {
+                continue;
+            }
+
+            for reached in reachable(cx, entry.def_id) {
+                if !(all_reachable || calls(cx, reached.def_id, &paths::WITH_STORAGE_LAYER))
+                    || !checked.insert(reached.def_id)
+                {
+                    continue;
+                }
+
+                let hir_id = cx.tcx.hir().local_def_id_to_hir_id(reached.def_id);
+                for (write, failure) in writes_before_failures(cx, reached.def_id, &mut writers) {
+                    span_lint_hir_and_then(
+                        cx,
+                        STORAGE_WRITE_BEFORE_CHECK,
+                        hir_id,
+                        write,
+                        "substrace: storage is written before a check that can still fail",
+                        |diag| {
+                            diag.span_note(failure, "the function can return an error here, keeping the write");
+                            diag.span_note(
+                                cx.tcx.def_span(entry.def_id),
+                                reachable_note(cx, &entry, &reached),
+                            );
+                            if all_reachable {
+                                diag.help("do every check that can fail before writing to storage, or make the extrinsic `#[transactional]`");
+                            } else {
+                                diag.help("do every check that can fail before writing to storage, or write inside the `with_storage_layer` closure");
+                            }
+                        },
+                    );
+                }
+            }
+        }
+    }
+}
+
+// Finds each storage write in the MIR of `def_id` that is followed by a return of an error on some
+// path, with the first such return.
+fn writes_before_failures(
+    cx: &LateContext<'_>,
+    def_id: LocalDefId,
+    writers: &mut FxHashMap<LocalDefId, bool>,
+) -> Vec<(Span, Span)> {
+    if !matches!(cx.tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn) {
+        return Vec::new();
+    }
+    // Building MIR for `fn`s with unsatisfiable preds results in ICE.
+    if fn_has_unsatisfiable_preds(cx, def_id.to_def_id()) {
+        return Vec::new();
+    }
+    let body = cx.tcx.optimized_mir(def_id.to_def_id());
+    if !is_type_diagnostic_item(cx, body.return_ty(), sym::Result) {
+        return Vec::new();
+    }
+
+    let mut found = Vec::new();
+    for data in body.basic_blocks.iter() {
+        if data.is_cleanup {
+            continue;
+        }
+        let terminator = data.terminator();
+        let TerminatorKind::Call { ref func, target: Some(target), .. } = terminator.kind else { continue };
+        let Some((fn_id, substs)) = func.const_fn_def() else { continue };
+        if !(is_storage_write(cx, fn_id, substs) || writes_storage(cx, resolve(cx, def_id, fn_id, substs), writers)) {
+            continue;
+        }
+
+        let write = terminator.source_info.span.source_callsite();
+        if let Some(failure) = first_failure(cx, body, target, write) {
+            found.push((write, failure));
+        }
+    }
+    found
+}
+
+// Walks the control flow graph from `start`, without unwinding, to the first return of an error.
+// Skips the `?` on the write itself, as a failing `try_mutate` writes nothing.
+fn first_failure<'tcx>(cx: &LateContext<'tcx>, body: &mir::Body<'tcx>, start: BasicBlock, write: Span) -> Option<Span> {
+    let mut seen = FxHashSet::default();
+    let mut queue = VecDeque::from([start]);
+    while let Some(bb) = queue.pop_front() {
+        if !seen.insert(bb) {
+            continue;
+        }
+        let data = &body.basic_blocks[bb];
+        if data.is_cleanup {
+            continue;
+        }
+
+        for statement in &data.statements {
+            // `Err(..)`, also from `ensure!`, built into the return value. The optimized MIR sets
+            // the discriminant separately.
+            let (place, variant) = match &statement.kind {
+                StatementKind::Assign(box (place, Rvalue::Aggregate(box AggregateKind::Adt(_, variant, ..), _))) => {
+                    (place, *variant)
+                },
+                StatementKind::SetDiscriminant { place, variant_index } => (&**place, *variant_index),
+                _ => continue,
+            };
+            let ty = place.ty(&body.local_decls, cx.tcx).ty;
+            if (place.local == mir::RETURN_PLACE || ty == body.return_ty())
+                && let ty::Adt(adt, _) = ty.kind()
+                && cx.tcx.is_diagnostic_item(sym::Result, adt.did())
+                && adt.variant(variant).name == sym::Err
+            {
+                return Some(statement.source_info.span.source_callsite());
+            }
+        }
+
+        let terminator = data.terminator();
+        // The `FromResidual::from_residual` call of `?`.
+        if let TerminatorKind::Call { ref func, .. } = terminator.kind
+            && let Some((fn_id, _)) = func.const_fn_def()
+            && match_def_path(cx, fn_id, &FROM_RESIDUAL)
+            && let span = terminator.source_info.span.source_callsite()
+            && !span.contains(write)
+        {
+            return Some(span);
+        }
+        queue.extend(terminator.successors());
+    }
+    None
+}
+
+fn is_storage_write<'tcx>(cx: &LateContext<'tcx>, fn_id: DefId, substs: SubstsRef<'tcx>) -> bool {
+    storage_call(cx, fn_id, substs).map_or(false, |call| WRITE_METHODS.contains(&call.method.as_str()))
+}
+
+// Whether the local function `fn_id`, or a function it calls, writes to storage.
+fn writes_storage(cx: &LateContext<'_>, fn_id: DefId, writers: &mut FxHashMap<LocalDefId, bool>) -> bool {
+    let Some(fn_id) = fn_id.as_local() else { return false };
+    if let Some(&writes) = writers.get(&fn_id) {
+        return writes;
+    }
+
+    let writes = reachable(cx, fn_id).iter().any(|reached| {
+        let Some(body_id) = cx.tcx.hir().maybe_body_owned_by(reached.def_id) else { return false };
+        let typeck = cx.tcx.typeck(reached.def_id);
+        for_each_expr_with_closures(cx, cx.tcx.hir().body(body_id).value, |expr| {
+            match called_fn(typeck, expr) {
+                Some((fn_id, substs)) if is_storage_write(cx, fn_id, substs) => ControlFlow::Break(()),
+                _ => ControlFlow::Continue(()),
+            }
+        })
+        .is_some()
+    });
+    writers.insert(fn_id, writes);
+    writes
+}
+
+// Whether the body of `def_id` calls the function at `path`.
+fn calls(cx: &LateContext<'_>, def_id: LocalDefId, path: &[&str]) -> bool {
+    let Some(body_id) = cx.tcx.hir().maybe_body_owned_by(def_id) else { return false };
+    let typeck = cx.tcx.typeck(def_id);
+    for_each_expr_with_closures(cx, cx.tcx.hir().body(body_id).value, |expr| {
+        match called_fn(typeck, expr) {
+            Some((fn_id, _)) if match_def_path(cx, fn_id, path) => ControlFlow::Break(()),
+            _ => ControlFlow::Continue(()),
+        }
+    })
+    .is_some()
+}
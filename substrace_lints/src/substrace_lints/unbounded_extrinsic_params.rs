@@ -0,0 +1,236 @@
+use super::auxiliary::pallet::{pallets, PalletCache};
+use super::auxiliary::unbounded::{find_unbounded, GET_CRATES};
+use substrace_utils::diagnostics::span_lint_hir_and_then;
+use substrace_utils::last_path_segment;
+use substrace_utils::source::snippet_with_applicability;
+use rustc_errors::Applicability;
+use rustc_hir as hir;
+use rustc_hir::def_id::DefId;
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::ty;
+use rustc_session::{declare_tool_lint, impl_lint_pass};
+use rustc_span::symbol::Symbol;
+
+declare_substrace_lint! {
+    /// ### What it does
+    /// Checks for dispatchable parameters that are, or contain, a collection without a maximum
+    /// length, like `Vec<u8>`, `Vec<T::AccountId>`, `String` or `BTreeMap`. This includes
+    /// collections in an `Option`, a tuple or the fields of a type of the crate, like a struct
+    /// with a `Vec` field, and bounded collections with a bound like `ConstU32<{u32::MAX}>`.
+    ///
+    /// ### Why is this bad?
+    /// Any caller can send an arbitrarily large payload, which the weight of the extrinsic doesn't
+    /// account for, and which ends up in the block and often in storage.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// pub fn set_members(origin: OriginFor<T>, members: Vec<T::AccountId>) -> DispatchResult
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// pub fn set_members(origin: OriginFor<T>, members: BoundedVec<T::AccountId, T::MaxMembers>) -> DispatchResult
+    /// ```
+    pub UNBOUNDED_EXTRINSIC_PARAMS,
+    security,
+    "Dispatchables must only take collections with a maximum length."
+}
+
+impl_lint_pass!(UnboundedExtrinsicParams => [UNBOUNDED_EXTRINSIC_PARAMS]);
+
+#[derive(Clone)]
+pub struct UnboundedExtrinsicParams {
+    pallets: PalletCache,
+}
+
+impl UnboundedExtrinsicParams {
+    pub fn new(pallets: PalletCache) -> Self {
+        Self { pallets }
+    }
+}
+
+impl<'tcx> LateLintPass<'tcx> for UnboundedExtrinsicParams {
+    fn check_crate_post(&mut self, cx: &LateContext<'tcx>) {
+        for call in pallets(cx, &self.pallets).iter().flat_map(|pallet| &pallet.calls) {
+            let Some(def_id) = call.def_id.as_local() else { continue };
+            let hir_id = cx.tcx.hir().local_def_id_to_hir_id(def_id);
+            let (Some(decl), Some(body_id)) = (cx.tcx.hir().fn_decl_by_hir_id(hir_id), cx.tcx.hir().maybe_body_owned_by(def_id)) else { continue };
+            let params = cx.tcx.hir().body(body_id).params;
+            let inputs = cx.tcx.fn_sig(def_id).skip_binder().inputs();
+            let config = pallet_config(cx, call.def_id);
+
+            // The first parameter is the origin.
+            for ((hir_ty, param), &ty) in decl.inputs.iter().zip(params).zip(inputs).skip(1) {
+                let Some(unbounded) = find_unbounded(cx, ty) else { continue };
+                let name = match param.pat.kind {
+                    hir::PatKind::Binding(_, _, ident, None) => Some(ident.name),
+                    _ => None,
+                };
+                let bound = config.as_ref().and_then(|config| config.bound_for(name));
+
+                span_lint_hir_and_then(
+                    cx,
+                    UNBOUNDED_EXTRINSIC_PARAMS,
+                    hir_id,
+                    hir_ty.span,
+                    &format!(
+                        "substrace: parameter `{}` of `{}` has no maximum length",
+                        name.map_or_else(|| "_".to_owned(), |name| name.to_string()),
+                        call.name
+                    ),
+                    |diag| {
+                        if let Some(field) = unbounded.field {
+                            diag.span_note(
+                                cx.tcx.def_span(field),
+                                format!("`{}` contains a `{}` here", ty, unbounded.ty),
+                            );
+                        }
+                        if let Some(too_large) = unbounded.bound {
+                            diag.note(format!("`{too_large}` is too large to bound `{}`", unbounded.ty));
+                        }
+                        let param = config.as_ref().map_or("T", |config| config.param.as_str());
+                        let bound = bound.map(|constant| {
+                            let bound = format!("{param}::{}", cx.tcx.item_name(constant));
+                            diag.span_note(cx.tcx.def_span(constant), format!("`{bound}` can bound it"));
+                            bound
+                        });
+                        // Only a parameter that is itself a `Vec` or `String` can be replaced as is.
+                        let bounded_vec = (unbounded.field.is_none() && ty == unbounded.ty)
+                            .then(|| bounded_vec(cx, hir_ty))
+                            .flatten();
+                        match (bounded_vec, bound) {
+                            (Some((elem, applicability)), Some(bound)) => {
+                                diag.span_suggestion(
+                                    hir_ty.span,
+                                    "use a `BoundedVec` instead",
+                                    format!("BoundedVec<{elem}, {bound}>"),
+                                    applicability,
+                                );
+                            },
+                            (Some((elem, _)), None) => {
+                                diag.span_suggestion(
+                                    hir_ty.span,
+                                    "use a `BoundedVec`, with a new `Get<u32>` constant in `Config` as its bound",
+                                    format!("BoundedVec<{elem}, {param}::MaxLen>"),
+                                    Applicability::HasPlaceholders,
+                                );
+                            },
+                            (None, Some(bound)) => {
+                                diag.help(format!("use a bounded collection, like `BoundedVec<_, {bound}>`"));
+                            },
+                            (None, None) => {
+                                diag.help(format!(
+                                    "use a bounded collection, like `BoundedVec<_, {param}::MaxLen>`, with a new `Get<u32>` constant in `Config`"
+                                ));
+                            },
+                        }
+                    },
+                );
+            }
+        }
+    }
+}
+
+// The element type of a parameter written as `Vec<_>` or `String`, to suggest a `BoundedVec` with.
+fn bounded_vec(cx: &LateContext<'_>, hir_ty: &hir::Ty<'_>) -> Option<(String, Applicability)> {
+    let hir::TyKind::Path(ref qpath) = hir_ty.kind else { return None };
+    let segment = last_path_segment(qpath);
+    let mut applicability = Applicability::MachineApplicable;
+    match segment.ident.as_str() {
+        "Vec" => segment.args?.args.iter().find_map(|arg| match arg {
+            hir::GenericArg::Type(ty) => Some((
+                snippet_with_applicability(cx, ty.span, "_", &mut applicability).into_owned(),
+                applicability,
+            )),
+            _ => None,
+        }),
+        // A `String` isn't checked to be UTF-8 anymore.
+        "String" => Some(("u8".to_owned(), Applicability::MaybeIncorrect)),
+        _ => None,
+    }
+}
+
+/// The `Config` trait of a pallet.
+struct PalletConfig {
+    /// The name of the generic parameter that implements it, usually `T`.
+    param: String,
+    /// The associated types bounded by `Get<u32>`, with the singular lowercase words of their names
+    /// without `Max`.
+    constants: Vec<(DefId, Vec<String>)>,
+}
+
+impl PalletConfig {
+    // The constant whose name matches the parameter word for word, like `MaxMembers` for `members`,
+    // `MaxRemarkLen` for `remark` or `MaxProposals` for `proposal_ids`. Names that only share a
+    // prefix, like `MaxAuthorities` and `a`, don't match.
+    fn bound_for(&self, param: Option<Symbol>) -> Option<DefId> {
+        let param: Vec<String> = param?.as_str().split('_').filter(|word| !word.is_empty()).map(singular).collect();
+        if param.is_empty() {
+            return None;
+        }
+        self.constants
+            .iter()
+            .find(|(_, name)| {
+                !name.is_empty() && (name.starts_with(&param) || param.starts_with(name) || param.ends_with(name))
+            })
+            .map(|&(constant, _)| constant)
+    }
+}
+
+// The words of a `CamelCase` name, like `remark` and `len` for `RemarkLen`.
+fn camel_case_words(name: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    for c in name.chars() {
+        match words.last_mut() {
+            Some(word) if !c.is_uppercase() => word.push(c),
+            _ => words.push(c.to_string()),
+        }
+    }
+    words.into_iter().map(|word| singular(&word)).collect()
+}
+
+fn singular(word: &str) -> String {
+    let word = word.to_lowercase();
+    word.strip_suffix('s').map_or_else(|| word.clone(), ToOwned::to_owned)
+}
+
+// Finds the `Config` trait that the pallet of the extrinsic `def_id` is generic over, with its
+// `Get<u32>` constants.
+fn pallet_config(cx: &LateContext<'_>, def_id: DefId) -> Option<PalletConfig> {
+    cx.tcx.param_env(def_id).caller_bounds().iter().find_map(|predicate| {
+        let ty::PredicateKind::Trait(trait_predicate) = predicate.kind().skip_binder() else { return None };
+        let trait_id = trait_predicate.def_id();
+        let ty::Param(param) = trait_predicate.self_ty().kind() else { return None };
+        if !trait_id.is_local() || cx.tcx.item_name(trait_id).as_str() != "Config" {
+            return None;
+        }
+
+        let constants = cx
+            .tcx
+            .associated_items(trait_id)
+            .in_definition_order()
+            .filter(|item| item.kind == ty::AssocKind::Type && is_get_u32(cx, item.def_id))
+            .map(|item| (item.def_id, camel_case_words(item.name.as_str().trim_start_matches("Max"))))
+            .collect();
+        Some(PalletConfig {
+            param: param.name.to_string(),
+            constants,
+        })
+    })
+}
+
+// Whether the associated type `def_id` is bounded by `Get<u32>`.
+fn is_get_u32(cx: &LateContext<'_>, def_id: DefId) -> bool {
+    cx.tcx.explicit_item_bounds(def_id).iter().any(|(predicate, _)| {
+        if let ty::PredicateKind::Trait(trait_predicate) = predicate.kind().skip_binder()
+            && let trait_id = trait_predicate.def_id()
+            && cx.tcx.item_name(trait_id).as_str() == "Get"
+            && GET_CRATES.contains(&cx.tcx.crate_name(trait_id.krate).as_str())
+            && let Some(value) = trait_predicate.trait_ref.substs.types().nth(1)
+        {
+            *value.kind() == ty::Uint(ty::UintTy::U32)
+        } else {
+            false
+        }
+    })
+}
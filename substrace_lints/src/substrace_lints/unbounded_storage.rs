@@ -0,0 +1,285 @@
+use super::auxiliary::attrs::{has_doc_header, is_pallet_attr};
+use super::auxiliary::paths;
+use super::auxiliary::storage::{storage_keys, storage_value};
+use super::auxiliary::unbounded::find_unbounded;
+use substrace_utils::diagnostics::{span_lint_and_then, span_lint_hir_and_then};
+use substrace_utils::get_trait_def_id;
+use substrace_utils::source::indent_of;
+use substrace_utils::ty::implements_trait_with_env;
+use rustc_ast::ast;
+use rustc_data_structures::fx::FxHashSet;
+use rustc_errors::Applicability;
+use rustc_hir as hir;
+use rustc_hir::def::{DefKind, Res};
+use rustc_lint::{EarlyContext, EarlyLintPass, LateContext, LateLintPass, LintContext};
+use rustc_middle::ty::{self, GenericArgKind, ParamEnv, ToPredicate, Ty};
+use rustc_session::{declare_tool_lint, impl_lint_pass};
+use rustc_span::Symbol;
+use std::sync::{Arc, Mutex};
+
+declare_substrace_lint! {
+    /// ### What it does
+    /// Checks the key and value types of every storage item for a maximum encoded length: types
+    /// that don't implement `MaxEncodedLen`, or contain a `Vec`, `BTreeMap`, `String` or a bounded
+    /// collection with a bound like `ConstU32<{u32::MAX}>`. Storage items can explain why they
+    /// can't grow too large in a `# Unbounded` doc comment, which `#[pallet::unbounded]` and
+    /// `#[pallet::without_storage_info]` require.
+    ///
+    /// ### Why is this bad?
+    /// The proof size of a block is limited on parachains, and the weights can only account for
+    /// reading a storage item if its size is bounded.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// #[pallet::storage]
+    /// #[pallet::unbounded]
+    /// pub type Members<T: Config> = StorageValue<_, Vec<T::AccountId>, ValueQuery>;
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// #[pallet::storage]
+    /// pub type Members<T: Config> = StorageValue<_, BoundedVec<T::AccountId, T::MaxMembers>, ValueQuery>;
+    /// ```
+    pub UNBOUNDED_STORAGE,
+    storage,
+    "Storage items must have a maximum encoded length, or document why they can't grow too large."
+}
+
+impl_lint_pass!(UnboundedStorage => [UNBOUNDED_STORAGE]);
+impl_lint_pass!(UnboundedStorageAttrs => [UNBOUNDED_STORAGE]);
+
+/// The storage items that `UnboundedStorageAttrs` reported for their `#[pallet::unbounded]`, which
+/// `UnboundedStorage` doesn't report again, by `StorageItemKey`.
+pub type ReportedAttrs = Arc<Mutex<FxHashSet<StorageItemKey>>>;
+
+/// A storage item, by the name hash of the source file it is written in and its path from the
+/// module of that file, like `pallet::Members`. The pallet macro re-emits the storage items with
+/// other spans, and the pre-expansion pass runs on each file alone, without knowing where in the
+/// crate its module is.
+pub type StorageItemKey = (u128, String);
+
+#[derive(Clone, Default)]
+pub struct UnboundedStorage {
+    reported_attrs: ReportedAttrs,
+}
+
+impl UnboundedStorage {
+    pub fn new(reported_attrs: ReportedAttrs) -> Self {
+        Self { reported_attrs }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct UnboundedStorageAttrs {
+    reported_attrs: ReportedAttrs,
+    /// The inline modules of the file around the current item.
+    modules: Vec<Symbol>,
+}
+
+impl UnboundedStorageAttrs {
+    pub fn new(reported_attrs: ReportedAttrs) -> Self {
+        Self {
+            reported_attrs,
+            modules: Vec::new(),
+        }
+    }
+}
+
+/// The doc comment section that explains why a storage item can't grow too large.
+const HEADER: &str = "Unbounded";
+
+impl<'tcx> LateLintPass<'tcx> for UnboundedStorage {
+    fn check_item(&mut self, cx: &LateContext<'tcx>, item: &'tcx hir::Item<'_>) {
+        let hir::ItemKind::TyAlias(hir_ty, _) = item.kind else { return };
+
+        // Aliases of local aliases are checked where the storage type is written.
+        if let hir::TyKind::Path(hir::QPath::Resolved(_, path)) = hir_ty.kind
+            && let Res::Def(DefKind::TyAlias, id) = path.res
+            && id.is_local() {
+            return;
+        }
+
+        let storage = cx.tcx.type_of(item.owner_id);
+        let Some(value) = storage_value(cx, storage) else { return };
+        if has_doc_header(cx.tcx.hir().attrs(item.hir_id()), HEADER)
+            || self.reported_attrs.lock().unwrap().contains(&storage_item_key(cx, item))
+        {
+            return;
+        }
+
+        // `MaxEncodedLen` moved from `frame_support` to `parity-scale-codec`, and doesn't exist in
+        // older FRAME versions.
+        let max_encoded_len = get_trait_def_id(cx, &paths::MAX_ENCODED_LEN)
+            .or_else(|| get_trait_def_id(cx, &paths::FRAME_MAX_ENCODED_LEN));
+        let param_env = storage_param_env(cx, item, storage);
+
+        let keys = storage_keys(cx, storage).into_iter().map(|key| ("key", key.key));
+        for (kind, ty) in keys.chain([("value", value)]) {
+            let unbounded = find_unbounded(cx, ty);
+            let missing_max_encoded_len = !has_unbounded_param(cx, param_env, ty)
+                && max_encoded_len
+                    .map_or(false, |trait_id| !implements_trait_with_env(cx.tcx, param_env, ty, trait_id, &[]));
+            if unbounded.is_none() && !missing_max_encoded_len {
+                continue;
+            }
+
+            span_lint_hir_and_then(
+                cx,
+                UNBOUNDED_STORAGE,
+                item.hir_id(),
+                hir_ty.span,
+                &format!(
+                    "substrace: the {kind} `{ty}` of `{}` has no maximum encoded length",
+                    item.ident
+                ),
+                |diag| {
+                    match unbounded {
+                        Some(unbounded) => {
+                            if let Some(field) = unbounded.field {
+                                diag.span_note(
+                                    cx.tcx.def_span(field),
+                                    format!("`{ty}` contains a `{}` here", unbounded.ty),
+                                );
+                            }
+                            if let Some(too_large) = unbounded.bound {
+                                diag.note(format!("`{too_large}` is too large to bound `{}`", unbounded.ty));
+                            }
+                        },
+                        None => {
+                            diag.note(format!("`{ty}` doesn't implement `MaxEncodedLen`"));
+                        },
+                    }
+
+                    let indent = " ".repeat(indent_of(cx, item.span).unwrap_or(0));
+                    diag.span_suggestion(
+                        item.span.shrink_to_lo(),
+                        "bound it, e.g. with a `BoundedVec`, or add an # Unbounded doc comment describing why it can't grow too large",
+                        format!("/// # {HEADER}\n{indent}/// `{ty}` can't grow too large because ...\n{indent}"),
+                        Applicability::HasPlaceholders,
+                    );
+                },
+            );
+        }
+    }
+}
+
+fn storage_item_key(cx: &LateContext<'_>, item: &hir::Item<'_>) -> StorageItemKey {
+    let source_map = cx.sess().source_map();
+    let file = source_map.lookup_source_file(item.ident.span.lo());
+    let mut path = vec![item.ident.name];
+    // Out-of-line modules are declared in the file of their parent.
+    let mut module = cx.tcx.parent_module_from_def_id(item.owner_id.def_id);
+    while !module.is_top_level_module()
+        && source_map.lookup_source_file(cx.tcx.def_span(module).lo()).name_hash == file.name_hash
+    {
+        path.push(cx.tcx.item_name(module.to_def_id()));
+        module = cx.tcx.parent_module_from_def_id(module);
+    }
+    (file.name_hash, join_path(path.iter().rev()))
+}
+
+fn join_path<'a>(path: impl Iterator<Item = &'a Symbol>) -> String {
+    path.map(Symbol::as_str).collect::<Vec<_>>().join("::")
+}
+
+// The bounds of the storage alias `item`, like `T: Config`. The bounds of a type alias aren't
+// enforced, so older FRAME versions leave them out, but the projections like `<T as Config>::AccountId`
+// in `storage` imply them.
+fn storage_param_env<'tcx>(cx: &LateContext<'tcx>, item: &hir::Item<'_>, storage: Ty<'tcx>) -> ParamEnv<'tcx> {
+    let param_env = cx.tcx.param_env(item.owner_id);
+    let implied = storage.walk().filter_map(|arg| match arg.unpack() {
+        GenericArgKind::Type(ty) => match ty.kind() {
+            ty::Projection(projection) => Some(
+                ty::Binder::dummy(projection.trait_ref(cx.tcx))
+                    .without_const()
+                    .to_predicate(cx.tcx),
+            ),
+            _ => None,
+        },
+        _ => None,
+    });
+    ParamEnv::new(
+        cx.tcx
+            .mk_predicates(param_env.caller_bounds().iter().chain(implied)),
+        param_env.reveal(),
+        param_env.constness(),
+    )
+}
+
+// Whether `ty` has a type parameter, outside of projections, that nothing but `Sized` bounds, like
+// the `K` of a generic alias for a storage type. Only the aliases that use it know what it is.
+fn has_unbounded_param<'tcx>(cx: &LateContext<'tcx>, param_env: ParamEnv<'tcx>, ty: Ty<'tcx>) -> bool {
+    let sized = cx.tcx.lang_items().sized_trait();
+    let mut walker = ty.walk();
+    while let Some(arg) = walker.next() {
+        let GenericArgKind::Type(ty) = arg.unpack() else { continue };
+        match ty.kind() {
+            ty::Param(_) => {
+                let bounded = param_env.caller_bounds().iter().any(|predicate| {
+                    matches!(predicate.kind().skip_binder(), ty::PredicateKind::Trait(trait_predicate)
+                        if trait_predicate.self_ty() == ty && Some(trait_predicate.def_id()) != sized)
+                });
+                if !bounded {
+                    return true;
+                }
+            },
+            ty::Projection(_) => walker.skip_current_subtree(),
+            _ => {},
+        }
+    }
+    false
+}
+
+// Runs before the pallet macro is expanded, so the `pallet::unbounded` and
+// `pallet::without_storage_info` attributes are still there.
+impl EarlyLintPass for UnboundedStorageAttrs {
+    fn check_item(&mut self, cx: &EarlyContext<'_>, item: &ast::Item) {
+        if is_inline_mod(item) {
+            self.modules.push(item.ident.name);
+        }
+        if has_doc_header(&item.attrs, HEADER) {
+            return;
+        }
+        let Some((attr, name)) = item.attrs.iter().find_map(|attr| {
+            ["unbounded", "without_storage_info"]
+                .into_iter()
+                .find(|name| is_pallet_attr(attr, name))
+                .map(|name| (attr, name))
+        }) else {
+            return;
+        };
+        let file = cx.sess().source_map().lookup_source_file(item.ident.span.lo());
+        let path = join_path(self.modules.iter().chain([&item.ident.name]));
+        self.reported_attrs.lock().unwrap().insert((file.name_hash, path));
+
+        span_lint_and_then(
+            cx,
+            UNBOUNDED_STORAGE,
+            attr.span,
+            &format!(
+                "substrace: `#[pallet::{name}]` on `{}` lacks an # Unbounded doc comment",
+                item.ident
+            ),
+            |diag| {
+                let indent = " ".repeat(indent_of(cx, item.span).unwrap_or(0));
+                diag.span_suggestion(
+                    item.span.shrink_to_lo(),
+                    "add an # Unbounded doc comment describing why the storage can't grow too large",
+                    format!("/// # {HEADER}\n{indent}/// The storage can't grow too large because ...\n{indent}"),
+                    Applicability::HasPlaceholders,
+                );
+            },
+        );
+    }
+
+    fn check_item_post(&mut self, _: &EarlyContext<'_>, item: &ast::Item) {
+        if is_inline_mod(item) {
+            self.modules.pop();
+        }
+    }
+}
+
+fn is_inline_mod(item: &ast::Item) -> bool {
+    matches!(item.kind, ast::ItemKind::Mod(_, ast::ModKind::Loaded(_, ast::Inline::Yes, _)))
+}
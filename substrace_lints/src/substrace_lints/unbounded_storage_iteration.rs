@@ -0,0 +1,266 @@
+use super::auxiliary::call_graph::{called_fn, entry_points, reachable, reachable_note, EntryKind, EntryPoint, Reached};
+use super::auxiliary::pallet::PalletCache;
+use super::auxiliary::storage::{storage_call, storage_name};
+use substrace_utils::diagnostics::span_lint_hir_and_then;
+use substrace_utils::visitors::for_each_expr_with_closures;
+use substrace_utils::{get_parent_expr, is_in_cfg_test, is_in_test_function};
+use rustc_ast::ast::{Attribute, NestedMetaItem};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir as hir;
+use rustc_hir::def_id::LocalDefId;
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_session::{declare_tool_lint, impl_lint_pass};
+use rustc_span::sym;
+use std::ops::ControlFlow;
+
+declare_substrace_lint! {
+    /// ### What it does
+    /// Checks for iterations over storage maps, like `iter`, `iter_values`, `iter_keys`, `drain`
+    /// and `iter_prefix`, that aren't limited with `take(n)`, in functions reachable from
+    /// dispatchables and the `on_initialize`, `on_finalize` and `on_idle` hooks. Iterations in
+    /// tests, benchmarks, `try_state` and migrations, which users can't trigger, are left to
+    /// `unbounded_storage_iteration_maintenance`.
+    ///
+    /// ### Why is this bad?
+    /// Anyone who can add entries to the map can make the iteration read more storage than fits in
+    /// a block, which the weight doesn't account for.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// for (who, deposit) in Deposits::<T>::iter() { .. }
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// for (who, deposit) in Deposits::<T>::iter().take(T::MaxRefunds::get() as usize) { .. }
+    /// ```
+    pub UNBOUNDED_STORAGE_ITERATION,
+    security,
+    "Iterating over a storage map in runtime code must be bounded."
+}
+
+declare_substrace_lint! {
+    /// ### What it does
+    /// Checks for the same unbounded iterations over storage maps as `unbounded_storage_iteration`,
+    /// but in code that users can't trigger: tests, benchmarks, `try_state` and migrations. It is
+    /// allowed by default.
+    ///
+    /// ### Why is this bad?
+    /// A migration runs in a single block, so iterating over a large map can exceed the block
+    /// weight. Tests, benchmarks and `try_state` don't run on chain, but become slow.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// fn on_runtime_upgrade() -> Weight {
+    ///     for (who, deposit) in Deposits::<T>::drain() { .. }
+    /// }
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// fn on_runtime_upgrade() -> Weight {
+    ///     for (who, deposit) in Deposits::<T>::drain().take(T::MaxMigrated::get() as usize) { .. }
+    /// }
+    /// ```
+    pub UNBOUNDED_STORAGE_ITERATION_MAINTENANCE,
+    maintenance,
+    "Iterating over a storage map in tests, benchmarks, try_state and migrations should be bounded if the map can grow large."
+}
+
+impl_lint_pass!(UnboundedStorageIteration => [UNBOUNDED_STORAGE_ITERATION, UNBOUNDED_STORAGE_ITERATION_MAINTENANCE]);
+
+#[derive(Clone)]
+pub struct UnboundedStorageIteration {
+    pallets: PalletCache,
+}
+
+impl UnboundedStorageIteration {
+    pub fn new(pallets: PalletCache) -> Self {
+        Self { pallets }
+    }
+}
+
+/// The methods of the FRAME storage maps that iterate over the entries.
+const ITER_METHODS: [&str; 13] = [
+    "iter",
+    "iter_keys",
+    "iter_values",
+    "iter_from",
+    "iter_keys_from",
+    "drain",
+    "iter_prefix",
+    "iter_prefix_values",
+    "iter_key_prefix",
+    "iter_prefix_from",
+    "drain_prefix",
+    "translate",
+    "translate_values",
+];
+
+/// The iterator methods that stop after a fixed number of entries.
+const BOUNDING_METHODS: [&str; 3] = ["take", "next", "nth"];
+
+/// Code that users can't trigger, where an unbounded iteration is less of a problem.
+#[derive(Clone, Copy)]
+enum Maintenance {
+    Test,
+    Benchmark,
+    TryState,
+    Migration,
+}
+
+impl Maintenance {
+    fn descr(self) -> &'static str {
+        match self {
+            Self::Test => "a test",
+            Self::Benchmark => "a benchmark",
+            Self::TryState => "`try_state`",
+            Self::Migration => "a migration",
+        }
+    }
+
+    fn help(self) -> &'static str {
+        match self {
+            Self::Test | Self::Benchmark | Self::TryState => {
+                "this doesn't run on chain, but bound it if the map can grow large enough to make it slow"
+            },
+            Self::Migration => {
+                "a migration runs in a single block, bound it or use a multi-block migration if the map can grow large"
+            },
+        }
+    }
+}
+
+impl<'tcx> LateLintPass<'tcx> for UnboundedStorageIteration {
+    fn check_crate_post(&mut self, cx: &LateContext<'tcx>) {
+        // The entry point users can trigger each function from, and the functions run as migrations
+        // or by `try_state`.
+        let mut triggered: FxHashMap<LocalDefId, (EntryPoint, Reached)> = FxHashMap::default();
+        let mut maintenance: FxHashMap<LocalDefId, Maintenance> = FxHashMap::default();
+        for entry in entry_points(cx, &self.pallets) {
+            let name = cx.tcx.item_name(entry.def_id.to_def_id());
+            let kind = match entry.kind {
+                EntryKind::Dispatchable => None,
+                EntryKind::Hook if name.as_str() == "on_runtime_upgrade" => Some(Maintenance::Migration),
+                EntryKind::Hook => None,
+                EntryKind::RuntimeApi => continue,
+            };
+            for reached in reachable(cx, entry.def_id) {
+                match kind {
+                    Some(kind) => {
+                        maintenance.entry(reached.def_id).or_insert(kind);
+                    },
+                    None => {
+                        triggered.entry(reached.def_id).or_insert((entry, reached));
+                    },
+                }
+            }
+        }
+        for def_id in cx.tcx.hir().body_owners() {
+            // The functions that check the storage or test a migration, which users can't call.
+            let Some(name) = cx.tcx.opt_item_name(def_id.to_def_id()) else { continue };
+            let kind = match name.as_str() {
+                "try_state" | "do_try_state" => Maintenance::TryState,
+                "pre_upgrade" | "post_upgrade" => Maintenance::Migration,
+                _ => continue,
+            };
+            for reached in reachable(cx, def_id) {
+                maintenance.entry(reached.def_id).or_insert(kind);
+            }
+        }
+
+        for def_id in cx.tcx.hir().body_owners() {
+            // Closures are visited as part of the body they are defined in.
+            if cx.tcx.is_closure(def_id.to_def_id()) {
+                continue;
+            }
+            let Some(body_id) = cx.tcx.hir().maybe_body_owned_by(def_id) else { continue };
+            let typeck = cx.tcx.typeck(def_id);
+            let hir_id = cx.tcx.hir().local_def_id_to_hir_id(def_id);
+
+            for_each_expr_with_closures(cx, cx.tcx.hir().body(body_id).value, |expr| {
+                if let Some((fn_id, substs)) = called_fn(typeck, expr)
+                    && let Some(call) = storage_call(cx, fn_id, substs)
+                    && ITER_METHODS.contains(&call.method.as_str())
+                    && !expr.span.from_expansion()
+                    && !is_bounded(cx, expr)
+                {
+                    let name = storage_name(cx, call.storage);
+                    let method = call.method;
+                    if let Some((entry, reached)) = triggered.get(&def_id) {
+                        span_lint_hir_and_then(
+                            cx,
+                            UNBOUNDED_STORAGE_ITERATION,
+                            expr.hir_id,
+                            expr.span,
+                            &format!("substrace: iterating over `{name}` with `{method}` is unbounded"),
+                            |diag| {
+                                diag.span_note(cx.tcx.def_span(entry.def_id), reachable_note(cx, entry, reached));
+                                diag.help("limit the number of entries with `.take(n)`, and account for it in the weight");
+                            },
+                        );
+                    } else if let Some(kind) = maintenance.get(&def_id).copied().or_else(|| test_or_benchmark(cx, hir_id)) {
+                        span_lint_hir_and_then(
+                            cx,
+                            UNBOUNDED_STORAGE_ITERATION_MAINTENANCE,
+                            expr.hir_id,
+                            expr.span,
+                            &format!(
+                                "substrace: iterating over `{name}` with `{method}` is unbounded, in {}",
+                                kind.descr()
+                            ),
+                            |diag| {
+                                diag.help(kind.help());
+                            },
+                        );
+                    }
+                }
+                ControlFlow::<()>::Continue(())
+            });
+        }
+    }
+}
+
+// Whether the iterator returned by `expr` is limited by a method like `take` further down the chain,
+// e.g. `Map::iter().filter(..).take(10)`.
+fn is_bounded(cx: &LateContext<'_>, expr: &hir::Expr<'_>) -> bool {
+    let mut current = expr;
+    while let Some(parent) = get_parent_expr(cx, current)
+        && let hir::ExprKind::MethodCall(path, receiver, ..) = parent.kind
+        && receiver.hir_id == current.hir_id
+    {
+        if BOUNDING_METHODS.contains(&path.ident.as_str()) {
+            return true;
+        }
+        current = parent;
+    }
+    false
+}
+
+fn test_or_benchmark(cx: &LateContext<'_>, hir_id: hir::HirId) -> Option<Maintenance> {
+    if is_in_cfg_test(cx.tcx, hir_id) || is_in_test_function(cx.tcx, hir_id) {
+        Some(Maintenance::Test)
+    } else if cx
+        .tcx
+        .hir()
+        .parent_iter(hir_id)
+        .flat_map(|(parent_id, _)| cx.tcx.hir().attrs(parent_id))
+        .chain(cx.tcx.hir().attrs(hir_id))
+        .any(is_cfg_benchmarks)
+    {
+        Some(Maintenance::Benchmark)
+    } else {
+        None
+    }
+}
+
+// `#[cfg(feature = "runtime-benchmarks")]`, also inside `any(..)` or `all(..)`.
+fn is_cfg_benchmarks(attr: &Attribute) -> bool {
+    fn mentions_benchmarks(items: &[NestedMetaItem]) -> bool {
+        items.iter().any(|item| {
+            (item.has_name(sym::feature) && item.value_str().map_or(false, |value| value.as_str() == "runtime-benchmarks"))
+                || item.meta_item_list().map_or(false, mentions_benchmarks)
+        })
+    }
+    attr.has_name(sym::cfg) && attr.meta_item_list().map_or(false, |items| mentions_benchmarks(&items))
+}
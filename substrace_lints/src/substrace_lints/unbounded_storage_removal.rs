@@ -0,0 +1,173 @@
+use super::auxiliary::call_graph::called_fn;
+use super::auxiliary::storage::{storage_call, storage_name};
+use super::auxiliary::unbounded::PRACTICALLY_UNBOUNDED;
+use substrace_utils::consts::{constant, FullInt};
+use substrace_utils::diagnostics::span_lint_and_then;
+use substrace_utils::source::snippet;
+use substrace_utils::visitors::for_each_expr_with_closures;
+use substrace_utils::{get_parent_expr, get_parent_node, is_res_lang_ctor, path_res, path_to_local_id};
+use rustc_hir as hir;
+use rustc_hir::LangItem::{OptionNone, OptionSome};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_session::{declare_tool_lint, impl_lint_pass};
+use std::ops::ControlFlow;
+
+declare_substrace_lint! {
+    /// ### What it does
+    /// Checks for calls to `clear`, `clear_prefix`, `remove_prefix` and `remove_all` of the FRAME
+    /// storage maps that pass `None` or a limit like `u32::MAX`, and for calls with a limit whose
+    /// result is ignored: the `maybe_cursor` of the returned `MultiRemovalResults`, or the
+    /// `KillStorageResult` of the older functions.
+    ///
+    /// ### Why is this bad?
+    /// Without a limit, removing the keys can take more weight than fits in a block. With a limit,
+    /// the keys beyond it are silently left behind unless the removal continues in a later block.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// let _ = Deposits::<T>::clear(u32::MAX, None);
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// let result = Deposits::<T>::clear(T::MaxRemovals::get(), cursor.as_deref());
+    /// Cursor::<T>::set(result.maybe_cursor);
+    /// ```
+    pub UNBOUNDED_STORAGE_REMOVAL,
+    storage,
+    "Removing the keys of a storage map must be limited, and continue where the limit was reached."
+}
+
+impl_lint_pass!(UnboundedStorageRemoval => [UNBOUNDED_STORAGE_REMOVAL]);
+
+#[derive(Clone, Default)]
+pub struct UnboundedStorageRemoval;
+
+/// The methods of the FRAME storage maps that remove all keys, or all keys with a prefix.
+const REMOVAL_METHODS: [&str; 4] = ["clear", "clear_prefix", "remove_prefix", "remove_all"];
+
+/// The parameter of the removal methods returning `MultiRemovalResults`, with the cursor to continue
+/// from.
+const CURSOR: &str = "maybe_cursor";
+
+impl<'tcx> LateLintPass<'tcx> for UnboundedStorageRemoval {
+    fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx hir::Expr<'_>) {
+        if expr.span.from_expansion() {
+            return;
+        }
+        let Some((fn_id, substs)) = called_fn(cx.typeck_results(), expr) else { return };
+        let Some(call) = storage_call(cx, fn_id, substs) else { return };
+        if !REMOVAL_METHODS.contains(&call.method.as_str()) {
+            return;
+        }
+
+        let args: Vec<&hir::Expr<'_>> = match expr.kind {
+            hir::ExprKind::Call(_, args) => args.iter().collect(),
+            hir::ExprKind::MethodCall(_, receiver, args, _) => [receiver].into_iter().chain(args).collect(),
+            _ => return,
+        };
+        let arg_names = cx.tcx.fn_arg_names(fn_id);
+        let arg = |name: &str| {
+            arg_names
+                .iter()
+                .position(|arg_name| arg_name.name.as_str().trim_start_matches('_') == name)
+                .and_then(|idx| args.get(idx).copied())
+        };
+        // `clear` and `clear_prefix` return a cursor to continue from, `remove_all` and `remove_prefix`
+        // a `KillStorageResult`.
+        let has_cursor = arg(CURSOR).is_some();
+        let help = if has_cursor {
+            "remove the keys over several blocks, e.g. in `on_idle`: pass a limit that fits in the weight, store the returned `maybe_cursor` and pass it to the next call until it is `None`"
+        } else {
+            "remove the keys over several blocks, e.g. in `on_idle`: pass a limit that fits in the weight, and call it again in the next block until it returns `KillStorageResult::AllRemoved`"
+        };
+        let name = storage_name(cx, call.storage);
+        let method = call.method;
+
+        if let Some(limit) = arg("limit")
+            && let Some(unbounded) = unbounded_limit(cx, limit)
+        {
+            span_lint_and_then(
+                cx,
+                UNBOUNDED_STORAGE_REMOVAL,
+                expr.span,
+                &format!("substrace: `{name}::{method}` is called {unbounded}"),
+                |diag| {
+                    diag.note("removing every key in a single block can take more weight than fits in it");
+                    diag.help(help);
+                },
+            );
+        } else if is_result_ignored(cx, expr, has_cursor) {
+            let ignored = if has_cursor { "cursor" } else { "result" };
+            span_lint_and_then(
+                cx,
+                UNBOUNDED_STORAGE_REMOVAL,
+                expr.span,
+                &format!("substrace: the {ignored} returned by `{name}::{method}` is ignored"),
+                |diag| {
+                    diag.note("the keys beyond the limit are left behind");
+                    diag.help(help);
+                },
+            );
+        }
+    }
+}
+
+// Describes `limit` if it is `None` or a constant that is too large to limit the removal.
+fn unbounded_limit(cx: &LateContext<'_>, limit: &hir::Expr<'_>) -> Option<String> {
+    if is_res_lang_ctor(cx, path_res(cx, limit), OptionNone) {
+        return Some("without a limit".to_owned());
+    }
+    let value = match limit.kind {
+        hir::ExprKind::Call(ctor, [value]) if is_res_lang_ctor(cx, path_res(cx, ctor), OptionSome) => value,
+        _ => limit,
+    };
+    // Limits like `u32::MAX` need to be resolved, unlike those `constant_full_int` evaluates.
+    let limit = constant(cx, cx.typeck_results(), value)
+        .and_then(|(limit, _)| limit.int_value(cx, cx.typeck_results().expr_ty(value)));
+    match limit {
+        Some(FullInt::U(limit)) if limit >= PRACTICALLY_UNBOUNDED => Some(format!(
+            "with `{}` as its limit, which is unbounded in practice",
+            snippet(cx, value.span, "..")
+        )),
+        _ => None,
+    }
+}
+
+// Whether the result of the removal `expr` is dropped, or only its fields other than the cursor are
+// read.
+fn is_result_ignored(cx: &LateContext<'_>, expr: &hir::Expr<'_>, has_cursor: bool) -> bool {
+    match get_parent_node(cx.tcx, expr.hir_id) {
+        Some(hir::Node::Stmt(hir::Stmt {
+            kind: hir::StmtKind::Semi(_),
+            ..
+        })) => true,
+        Some(hir::Node::Local(local)) => match local.pat.kind {
+            hir::PatKind::Wild => true,
+            hir::PatKind::Binding(_, local_id, _, None) => !is_result_used(cx, local_id, has_cursor),
+            _ => false,
+        },
+        Some(hir::Node::Expr(parent)) => {
+            has_cursor && matches!(parent.kind, hir::ExprKind::Field(_, field) if field.name.as_str() != CURSOR)
+        },
+        _ => false,
+    }
+}
+
+// Whether the local the result is bound to is read, or only its fields other than the cursor.
+fn is_result_used(cx: &LateContext<'_>, local_id: hir::HirId, has_cursor: bool) -> bool {
+    let Some(body_id) = cx.enclosing_body else { return true };
+    for_each_expr_with_closures(cx, cx.tcx.hir().body(body_id).value, |expr| {
+        if !path_to_local_id(expr, local_id) {
+            return ControlFlow::Continue(());
+        }
+        match get_parent_expr(cx, expr) {
+            Some(hir::Expr {
+                kind: hir::ExprKind::Field(_, field),
+                ..
+            }) if has_cursor && field.name.as_str() != CURSOR => ControlFlow::Continue(()),
+            _ => ControlFlow::Break(()),
+        }
+    })
+    .is_some()
+}
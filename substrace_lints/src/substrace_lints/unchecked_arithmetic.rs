@@ -0,0 +1,258 @@
+use super::auxiliary::call_graph::{entry_points, reachable, reachable_note};
+use super::auxiliary::pallet::PalletCache;
+use substrace_utils::diagnostics::span_lint_hir_and_then;
+use substrace_utils::{get_trait_def_id, last_path_segment};
+use substrace_utils::sugg::Sugg;
+use substrace_utils::ty::{implements_trait_with_env, peel_mid_ty_refs};
+use substrace_utils::visitors::for_each_expr_with_closures;
+use rustc_ast::ast::LitKind;
+use rustc_data_structures::fx::FxHashSet;
+use rustc_errors::Applicability;
+use rustc_hir as hir;
+use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::ty::{self, ParamEnv, Ty};
+use rustc_session::{declare_tool_lint, impl_lint_pass};
+use std::ops::ControlFlow;
+
+declare_substrace_lint! {
+    /// ### What it does
+    /// Checks for `+`, `-`, `*`, `/` and `%`, and their assigning versions, on the numeric types of
+    /// a pallet, like `T::Balance`, `BalanceOf<T>` and `BlockNumberFor<T>`, in functions reachable
+    /// from dispatchables, hooks and runtime APIs. These are the generic types bounded by
+    /// `AtLeast32Bit` or `AtLeast32BitUnsigned`. Types can be skipped with
+    /// `arithmetic-side-effects-allowed` in `substrace.toml`.
+    ///
+    /// ### Why is this bad?
+    /// Overflows panic in debug builds and silently wrap in the release builds of the runtime, and
+    /// dividing by zero always panics.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// let total = balance + amount;
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// let total = balance.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+    /// ```
+    pub UNCHECKED_ARITHMETIC,
+    security,
+    "Arithmetic on balances and other runtime numeric types must be checked or saturating."
+}
+
+impl_lint_pass!(UncheckedArithmetic => [UNCHECKED_ARITHMETIC]);
+
+/// The traits that bound the numeric types of pallets.
+const NUMERIC_TRAITS: [[&str; 3]; 2] = [
+    ["sp_arithmetic", "traits", "AtLeast32Bit"],
+    ["sp_arithmetic", "traits", "AtLeast32BitUnsigned"],
+];
+
+#[derive(Clone)]
+pub struct UncheckedArithmetic {
+    pallets: PalletCache,
+    allowed: FxHashSet<String>,
+}
+
+impl UncheckedArithmetic {
+    pub fn new(pallets: PalletCache, allowed: &FxHashSet<String>) -> Self {
+        Self {
+            pallets,
+            allowed: allowed.clone(),
+        }
+    }
+}
+
+impl<'tcx> LateLintPass<'tcx> for UncheckedArithmetic {
+    fn check_crate_post(&mut self, cx: &LateContext<'tcx>) {
+        let numeric_traits: Vec<DefId> = NUMERIC_TRAITS
+            .iter()
+            .filter_map(|path| get_trait_def_id(cx, path))
+            .collect();
+        if numeric_traits.is_empty() {
+            return;
+        }
+
+        let mut reported = FxHashSet::default();
+        for entry in entry_points(cx, &self.pallets) {
+            for reached in reachable(cx, entry.def_id) {
+                for arithmetic in self.find_arithmetic(cx, reached.def_id, &numeric_traits) {
+                    if !reported.insert(arithmetic.expr.hir_id) {
+                        continue;
+                    }
+
+                    let (op, assign) = arithmetic.op;
+                    span_lint_hir_and_then(
+                        cx,
+                        UNCHECKED_ARITHMETIC,
+                        arithmetic.expr.hir_id,
+                        arithmetic.expr.span,
+                        &format!(
+                            "substrace: `{}` on `{}` can {}",
+                            op.as_str(),
+                            arithmetic.ty,
+                            match op {
+                                hir::BinOpKind::Sub => "underflow",
+                                hir::BinOpKind::Div | hir::BinOpKind::Rem => "divide by zero",
+                                _ => "overflow",
+                            }
+                        ),
+                        |diag| {
+                            diag.span_note(cx.tcx.def_span(entry.def_id), reachable_note(cx, &entry, &reached));
+                            suggest(cx, diag, arithmetic.expr, op, assign);
+                        },
+                    );
+                }
+            }
+        }
+    }
+}
+
+struct Arithmetic<'tcx> {
+    expr: &'tcx hir::Expr<'tcx>,
+    /// The operator, and whether it assigns, like `+=`.
+    op: (hir::BinOpKind, bool),
+    ty: Ty<'tcx>,
+}
+
+impl UncheckedArithmetic {
+    fn find_arithmetic<'tcx>(
+        &self,
+        cx: &LateContext<'tcx>,
+        def_id: LocalDefId,
+        numeric_traits: &[DefId],
+    ) -> Vec<Arithmetic<'tcx>> {
+        let Some(body_id) = cx.tcx.hir().maybe_body_owned_by(def_id) else { return Vec::new() };
+        let typeck = cx.tcx.typeck(def_id);
+        let param_env = cx.tcx.param_env(def_id);
+
+        let mut found = Vec::new();
+        for_each_expr_with_closures(cx, cx.tcx.hir().body(body_id).value, |expr| {
+            let (op, assign, lhs, rhs) = match expr.kind {
+                hir::ExprKind::Binary(op, lhs, rhs) => (op.node, false, lhs, rhs),
+                hir::ExprKind::AssignOp(op, lhs, rhs) => (op.node, true, lhs, rhs),
+                _ => return ControlFlow::<()>::Continue(()),
+            };
+            let is_arithmetic = matches!(
+                op,
+                hir::BinOpKind::Add
+                    | hir::BinOpKind::Sub
+                    | hir::BinOpKind::Mul
+                    | hir::BinOpKind::Div
+                    | hir::BinOpKind::Rem
+            );
+            // Dividing by a constant other than zero can't fail.
+            let is_nonzero_divisor =
+                matches!(op, hir::BinOpKind::Div | hir::BinOpKind::Rem) && is_nonzero_literal(rhs);
+
+            let (ty, _) = peel_mid_ty_refs(typeck.expr_ty(lhs));
+            if is_arithmetic
+                && !is_nonzero_divisor
+                && !expr.span.from_expansion()
+                && is_runtime_numeric(cx, param_env, ty, numeric_traits)
+                && !self.is_allowed(cx, ty)
+            {
+                found.push(Arithmetic {
+                    expr,
+                    op: (op, assign),
+                    ty,
+                });
+            }
+            ControlFlow::Continue(())
+        });
+        found
+    }
+
+    // Matches the allow-list against the full type, e.g. `<T as Config>::Balance`, and its name,
+    // e.g. `Balance`.
+    fn is_allowed(&self, cx: &LateContext<'_>, ty: Ty<'_>) -> bool {
+        let name = match ty.kind() {
+            ty::Param(param) => Some(param.name),
+            ty::Projection(projection) => Some(cx.tcx.item_name(projection.item_def_id)),
+            _ => None,
+        };
+        self.allowed.contains(&ty.to_string()) || name.map_or(false, |name| self.allowed.contains(name.as_str()))
+    }
+}
+
+// A nonzero integer literal, also when converted with `.into()` or `From::from`, like `2u32.into()`.
+fn is_nonzero_literal(expr: &hir::Expr<'_>) -> bool {
+    match expr.kind {
+        hir::ExprKind::Lit(ref lit) => matches!(lit.node, LitKind::Int(value, _) if value != 0),
+        hir::ExprKind::MethodCall(path, receiver, [], _) if path.ident.as_str() == "into" => {
+            is_nonzero_literal(receiver)
+        },
+        hir::ExprKind::Call(
+            hir::Expr {
+                kind: hir::ExprKind::Path(qpath @ (hir::QPath::Resolved(..) | hir::QPath::TypeRelative(..))),
+                ..
+            },
+            [arg],
+        ) if last_path_segment(qpath).ident.as_str() == "from" => is_nonzero_literal(arg),
+        _ => false,
+    }
+}
+
+// The generic numeric types of a pallet, like `T::Balance`. Primitive integers implement the same
+// traits, but are left to `clippy::arithmetic_side_effects`.
+fn is_runtime_numeric<'tcx>(
+    cx: &LateContext<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    ty: Ty<'tcx>,
+    numeric_traits: &[DefId],
+) -> bool {
+    matches!(ty.kind(), ty::Param(_) | ty::Projection(_))
+        && numeric_traits
+            .iter()
+            .any(|&trait_id| implements_trait_with_env(cx.tcx, param_env, ty, trait_id, &[]))
+}
+
+fn suggest(
+    cx: &LateContext<'_>,
+    diag: &mut rustc_errors::Diagnostic,
+    expr: &hir::Expr<'_>,
+    op: hir::BinOpKind,
+    assign: bool,
+) {
+    let (hir::ExprKind::Binary(_, lhs, rhs) | hir::ExprKind::AssignOp(_, lhs, rhs)) = expr.kind else { return };
+    let name = match op {
+        hir::BinOpKind::Add => "add",
+        hir::BinOpKind::Sub => "sub",
+        hir::BinOpKind::Mul => "mul",
+        hir::BinOpKind::Div => "div",
+        _ => "rem",
+    };
+    let lhs = Sugg::hir(cx, lhs, "..");
+    let rhs = Sugg::hir(cx, rhs, "..");
+
+    match (op, assign) {
+        (hir::BinOpKind::Div | hir::BinOpKind::Rem, false) => {
+            diag.span_suggestion(
+                expr.span,
+                format!("use `checked_{name}` and handle the `None` case"),
+                format!("{}.checked_{name}({})", lhs.maybe_par(), rhs.addr()),
+                Applicability::MaybeIncorrect,
+            );
+        },
+        (hir::BinOpKind::Div | hir::BinOpKind::Rem, true) => {
+            diag.help(format!("use `checked_{name}` and handle the `None` case"));
+        },
+        (_, false) => {
+            diag.span_suggestion(
+                expr.span,
+                format!("use `saturating_{name}`, or `checked_{name}` and return an error"),
+                format!("{}.saturating_{name}({rhs})", lhs.maybe_par()),
+                Applicability::MaybeIncorrect,
+            );
+        },
+        (_, true) => {
+            diag.span_suggestion(
+                expr.span,
+                format!("use `saturating_{name}`, or `checked_{name}` and return an error"),
+                format!("{lhs} = {}.saturating_{name}({rhs})", lhs.clone().maybe_par()),
+                Applicability::MaybeIncorrect,
+            );
+        },
+    }
+}
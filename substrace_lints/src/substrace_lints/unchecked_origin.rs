@@ -0,0 +1,219 @@
+use super::auxiliary::call_graph::called_fn;
+use super::auxiliary::pallet::{is_frame_support_trait, pallets, PalletCache};
+use substrace_utils::diagnostics::span_lint_hir_and_then;
+use substrace_utils::path_to_local;
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir as hir;
+use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_hir::intravisit::{walk_expr, walk_local, Visitor};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::hir::nested_filter;
+use rustc_middle::ty::TypeckResults;
+use rustc_session::{declare_tool_lint, impl_lint_pass};
+use rustc_span::symbol::Ident;
+use rustc_span::Span;
+
+declare_substrace_lint! {
+    /// ### What it does
+    /// Checks that every dispatchable passes its origin to `ensure_signed`, `ensure_root`,
+    /// `ensure_none`, `ensure_signed_or_root` or an `EnsureOrigin`, directly or through the
+    /// functions it calls.
+    ///
+    /// ### Why is this bad?
+    /// Anyone can call a dispatchable that doesn't check its origin, with the permissions of
+    /// whoever the dispatchable assumes is calling it.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// pub fn set_admin(_origin: OriginFor<T>, admin: T::AccountId) -> DispatchResult {
+    ///     Admin::<T>::put(admin);
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// pub fn set_admin(origin: OriginFor<T>, admin: T::AccountId) -> DispatchResult {
+    ///     ensure_root(origin)?;
+    ///     Admin::<T>::put(admin);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub UNCHECKED_ORIGIN,
+    security,
+    "Dispatchables must check their origin."
+}
+
+impl_lint_pass!(UncheckedOrigin => [UNCHECKED_ORIGIN]);
+
+#[derive(Clone)]
+pub struct UncheckedOrigin {
+    pallets: PalletCache,
+}
+
+impl UncheckedOrigin {
+    pub fn new(pallets: PalletCache) -> Self {
+        Self { pallets }
+    }
+}
+
+/// The functions of `frame_system` that check an origin.
+const ENSURE_FNS: [&str; 4] = ["ensure_signed", "ensure_root", "ensure_none", "ensure_signed_or_root"];
+
+/// The traits of `frame_support` that check an origin with `ensure_origin` or `try_origin`.
+const ENSURE_TRAITS: [&str; 2] = ["EnsureOrigin", "EnsureOriginWithArg"];
+
+impl<'tcx> LateLintPass<'tcx> for UncheckedOrigin {
+    fn check_crate_post(&mut self, cx: &LateContext<'tcx>) {
+        for call in pallets(cx, &self.pallets).iter().flat_map(|pallet| &pallet.calls) {
+            let Some(def_id) = call.def_id.as_local() else { continue };
+            let Some(body_id) = cx.tcx.hir().maybe_body_owned_by(def_id) else { continue };
+            // The origin is the first parameter of every dispatchable.
+            let Some(origin) = cx.tcx.hir().body(body_id).params.first() else { continue };
+
+            let mut visited = FxHashSet::default();
+            let Err(shadowed) = checks_origin(cx, def_id, 0, &mut visited) else { continue };
+
+            span_lint_hir_and_then(
+                cx,
+                UNCHECKED_ORIGIN,
+                cx.tcx.hir().local_def_id_to_hir_id(def_id),
+                origin.pat.span,
+                &format!("substrace: the origin of `{}` is never checked", call.name),
+                |diag| {
+                    if let Some(shadowed) = shadowed {
+                        diag.span_note(shadowed, "the origin is shadowed here");
+                    }
+                    diag.help(
+                        "check the origin with `ensure_signed`, `ensure_root`, `ensure_none` or an `EnsureOrigin`",
+                    );
+                },
+            );
+        }
+    }
+}
+
+// Whether the parameter at `param` of `def_id` is checked as an origin. Otherwise returns where the
+// parameter is shadowed, if it is.
+fn checks_origin(
+    cx: &LateContext<'_>,
+    def_id: LocalDefId,
+    param: usize,
+    visited: &mut FxHashSet<(LocalDefId, usize)>,
+) -> Result<(), Option<Span>> {
+    if !visited.insert((def_id, param)) {
+        return Err(None);
+    }
+    let Some(body_id) = cx.tcx.hir().maybe_body_owned_by(def_id) else { return Err(None) };
+    let body = cx.tcx.hir().body(body_id);
+    let Some(hir::PatKind::Binding(_, hir_id, ident, None)) = body.params.get(param).map(|param| &param.pat.kind) else {
+        return Err(None);
+    };
+
+    let mut visitor = OriginVisitor {
+        cx,
+        typeck: cx.tcx.typeck(def_id),
+        visited,
+        ident: *ident,
+        origins: FxHashSet::from_iter([*hir_id]),
+        checked: false,
+        shadowed: None,
+    };
+    visitor.visit_expr(body.value);
+    if visitor.checked {
+        Ok(())
+    } else {
+        Err(visitor.shadowed)
+    }
+}
+
+struct OriginVisitor<'a, 'tcx> {
+    cx: &'a LateContext<'tcx>,
+    typeck: &'tcx TypeckResults<'tcx>,
+    visited: &'a mut FxHashSet<(LocalDefId, usize)>,
+    /// The name of the origin parameter.
+    ident: Ident,
+    /// The origin parameter, and the locals it is moved or cloned into.
+    origins: FxHashSet<hir::HirId>,
+    checked: bool,
+    shadowed: Option<Span>,
+}
+
+impl<'a, 'tcx> OriginVisitor<'a, 'tcx> {
+    fn is_origin(&self, expr: &hir::Expr<'_>) -> bool {
+        path_to_local(peel_origin(expr)).map_or(false, |hir_id| self.origins.contains(&hir_id))
+    }
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for OriginVisitor<'a, 'tcx> {
+    type NestedFilter = nested_filter::OnlyBodies;
+
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.cx.tcx.hir()
+    }
+
+    fn visit_local(&mut self, local: &'tcx hir::Local<'tcx>) {
+        if let hir::PatKind::Binding(_, hir_id, ident, None) = local.pat.kind {
+            if local.init.map_or(false, |init| self.is_origin(init)) {
+                self.origins.insert(hir_id);
+            } else if ident.name == self.ident.name {
+                self.shadowed.get_or_insert(local.pat.span);
+            }
+        }
+        walk_local(self, local);
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr<'tcx>) {
+        if self.checked {
+            return;
+        }
+
+        match expr.kind {
+            // Matching on the `RawOrigin` checks it as well.
+            hir::ExprKind::Match(scrutinee, ..) if self.is_origin(scrutinee) => self.checked = true,
+            hir::ExprKind::Let(let_expr) if self.is_origin(let_expr.init) => self.checked = true,
+            hir::ExprKind::Call(_, args) | hir::ExprKind::MethodCall(_, _, args, _) => {
+                if let Some((fn_id, _)) = called_fn(self.typeck, expr) {
+                    let receiver = match expr.kind {
+                        hir::ExprKind::MethodCall(_, receiver, ..) => Some(receiver),
+                        _ => None,
+                    };
+                    for (index, arg) in receiver.into_iter().chain(args).enumerate() {
+                        if self.is_origin(arg)
+                            && (is_origin_check(self.cx, fn_id)
+                                || fn_id.as_local().map_or(false, |callee| {
+                                    checks_origin(self.cx, callee, index, self.visited).is_ok()
+                                }))
+                        {
+                            self.checked = true;
+                            return;
+                        }
+                    }
+                }
+            },
+            _ => {},
+        }
+        walk_expr(self, expr);
+    }
+}
+
+// Peels `&`, `*`, `.clone()` and `.into()` off the origin.
+fn peel_origin<'a>(expr: &'a hir::Expr<'a>) -> &'a hir::Expr<'a> {
+    match expr.kind {
+        hir::ExprKind::AddrOf(_, _, inner) | hir::ExprKind::Unary(hir::UnOp::Deref, inner) => peel_origin(inner),
+        hir::ExprKind::MethodCall(path, receiver, [], _) if matches!(path.ident.as_str(), "clone" | "into") => {
+            peel_origin(receiver)
+        },
+        _ => expr,
+    }
+}
+
+fn is_origin_check(cx: &LateContext<'_>, fn_id: DefId) -> bool {
+    let name = cx.tcx.item_name(fn_id);
+    if let Some(trait_id) = cx.tcx.trait_of_item(fn_id) {
+        matches!(name.as_str(), "ensure_origin" | "try_origin")
+            && ENSURE_TRAITS.iter().any(|ensure_trait| is_frame_support_trait(cx, trait_id, ensure_trait))
+    } else {
+        cx.tcx.crate_name(fn_id.krate).as_str() == "frame_system" && ENSURE_FNS.contains(&name.as_str())
+    }
+}
@@ -0,0 +1,231 @@
+use super::auxiliary::call_graph::called_fn;
+use super::auxiliary::paths;
+use substrace_utils::diagnostics::span_lint_and_then;
+use substrace_utils::{get_trait_def_id, match_any_def_paths, match_def_path, peel_blocks};
+use rustc_ast::ast::LitKind;
+use rustc_hir as hir;
+use rustc_hir::def::{DefKind, Res};
+use rustc_hir::def_id::DefId;
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::ty::{self, Ty};
+use rustc_session::{declare_tool_lint, impl_lint_pass};
+use rustc_span::source_map::Span;
+
+declare_substrace_lint! {
+    /// ### What it does
+    /// Checks that the `Barrier` of the XCM executor config does not allow unpaid or paid execution from
+    /// `Everything`, or from a filter that always passes. Type aliases are followed, and so are filters
+    /// that return a `parameter_types!` parameter.
+    ///
+    /// ### Why is this bad?
+    /// Any chain can then have its messages executed, which is how Karura was exploited.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// pub type Barrier = AllowUnpaidExecutionFrom<Everything>;
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// pub type Barrier = AllowUnpaidExecutionFrom<ParentOrSiblings>;
+    /// ```
+    pub XCM_CONFIG_CHECK,
+    security,
+    "The XCM barrier must not allow execution from every origin."
+}
+
+impl_lint_pass!(XcmConfigCheck => [XCM_CONFIG_CHECK]);
+
+#[derive(Clone, Default)]
+pub struct XcmConfigCheck;
+
+/// A barrier that allows execution from every origin.
+struct PermissiveBarrier {
+    span: Span,
+    barrier: DefId,
+    filter: DefId,
+    /// Whether the barrier is one of several barriers in a tuple.
+    in_tuple: bool,
+}
+
+// Barriers are tried in order until one of them allows the message, so a single barrier that
+// allows everything defeats all the other barriers (see the Karura XCM incident).
+impl<'tcx> LateLintPass<'tcx> for XcmConfigCheck {
+    fn check_item(&mut self, cx: &LateContext<'tcx>, item: &'tcx hir::Item<'_>) {
+        if let hir::ItemKind::Impl(impl_block) = item.kind
+            && let Some(trait_ref) = &impl_block.of_trait
+            && let Some(trait_id) = trait_ref.trait_def_id()
+            && match_def_path(cx, trait_id, &paths::XCM_EXECUTOR_CONFIG) {
+
+            for impl_item_ref in impl_block.items {
+                if impl_item_ref.ident.name.as_str() == "Barrier"
+                    && let hir::ImplItemKind::Type(barrier_ty) = cx.tcx.hir().impl_item(impl_item_ref.id).kind {
+
+                    let mut barriers = Vec::new();
+                    permissive_barriers(cx, barrier_ty, false, &mut barriers);
+
+                    for barrier in barriers {
+                        let barrier_name = cx.tcx.item_name(barrier.barrier);
+                        let filter_name = cx.tcx.item_name(barrier.filter);
+
+                        span_lint_and_then(
+                            cx,
+                            XCM_CONFIG_CHECK,
+                            barrier.span,
+                            &format!("substrace: `{barrier_name}<{filter_name}>` allows XCM execution from every origin"),
+                            |diag| {
+                                if barrier.in_tuple {
+                                    diag.note("barriers in a tuple are tried in order and the first one that passes wins, so the other barriers have no effect");
+                                }
+                                diag.help(&format!("restrict `{barrier_name}` to the origins that should be allowed, e.g. `ParentOrSiblings`"));
+                            },
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Collects the barriers in `ty` that allow everything. Local type aliases are followed so the lint
+// can point at the offending element, aliases from other crates are resolved through their type.
+fn permissive_barriers(cx: &LateContext<'_>, ty: &hir::Ty<'_>, in_tuple: bool, barriers: &mut Vec<PermissiveBarrier>) {
+    match ty.kind {
+        hir::TyKind::Tup(elements) => {
+            for element in elements {
+                permissive_barriers(cx, element, in_tuple || elements.len() > 1, barriers);
+            }
+        },
+        hir::TyKind::Path(hir::QPath::Resolved(None, path)) => match path.res {
+            Res::Def(DefKind::TyAlias, id) => {
+                if let Some(local_id) = id.as_local()
+                    && let hir::ItemKind::TyAlias(alias_ty, _) = cx.tcx.hir().expect_item(local_id).kind {
+                    permissive_barriers(cx, alias_ty, in_tuple, barriers);
+                } else {
+                    permissive_barriers_in_ty(cx, cx.tcx.type_of(id), ty.span, in_tuple, barriers);
+                }
+            },
+            Res::Def(_, id) => {
+                let args = path.segments.last().and_then(|segment| segment.args);
+                let type_args = args.map_or(&[][..], |args| args.args).iter().filter_map(|arg| match arg {
+                    hir::GenericArg::Type(arg) => Some(arg),
+                    _ => None,
+                });
+
+                if is_allow_barrier(cx, id) {
+                    if let Some(filter) = type_args.clone().next()
+                        && let Some(filter) = resolve_filter(cx, filter)
+                        && is_everything(cx, filter) {
+                        barriers.push(PermissiveBarrier {
+                            span: ty.span,
+                            barrier: id,
+                            filter,
+                            in_tuple,
+                        });
+                    }
+                } else {
+                    // Wrappers like `WithComputedOrigin<(..), ..>` contain barriers themselves.
+                    for arg in type_args {
+                        permissive_barriers(cx, arg, in_tuple, barriers);
+                    }
+                }
+            },
+            _ => {},
+        },
+        _ => {},
+    }
+}
+
+fn permissive_barriers_in_ty<'tcx>(
+    cx: &LateContext<'tcx>,
+    ty: Ty<'tcx>,
+    span: Span,
+    in_tuple: bool,
+    barriers: &mut Vec<PermissiveBarrier>,
+) {
+    match ty.kind() {
+        ty::Tuple(elements) => {
+            for element in elements.iter() {
+                permissive_barriers_in_ty(cx, element, span, in_tuple || elements.len() > 1, barriers);
+            }
+        },
+        ty::Adt(adt, substs) => {
+            if is_allow_barrier(cx, adt.did()) {
+                if let Some(ty::Adt(filter, _)) = substs.types().next().map(Ty::kind)
+                    && is_everything(cx, filter.did()) {
+                    barriers.push(PermissiveBarrier {
+                        span,
+                        barrier: adt.did(),
+                        filter: filter.did(),
+                        in_tuple,
+                    });
+                }
+            } else {
+                for arg in substs.types() {
+                    permissive_barriers_in_ty(cx, arg, span, in_tuple, barriers);
+                }
+            }
+        },
+        _ => {},
+    }
+}
+
+fn is_allow_barrier(cx: &LateContext<'_>, def_id: DefId) -> bool {
+    match_any_def_paths(
+        cx,
+        def_id,
+        &[
+            &paths::ALLOW_UNPAID_EXECUTION_FROM,
+            &paths::ALLOW_EXPLICIT_UNPAID_EXECUTION_FROM,
+            &paths::ALLOW_TOP_LEVEL_PAID_EXECUTION_FROM,
+        ],
+    )
+    .is_some()
+}
+
+// Resolves the filter of a barrier, following type aliases.
+fn resolve_filter(cx: &LateContext<'_>, ty: &hir::Ty<'_>) -> Option<DefId> {
+    if let hir::TyKind::Path(hir::QPath::Resolved(None, path)) = ty.kind
+        && let Res::Def(DefKind::TyAlias | DefKind::Struct | DefKind::Enum, id) = path.res
+        && let ty::Adt(adt, _) = cx.tcx.type_of(id).kind() {
+        Some(adt.did())
+    } else {
+        None
+    }
+}
+
+// Whether the filter is `Everything`, or a local `Contains` implementation that always returns `true`.
+fn is_everything(cx: &LateContext<'_>, filter: DefId) -> bool {
+    if match_def_path(cx, filter, &paths::EVERYTHING) {
+        return true;
+    }
+
+    let Some(contains_trait) = get_trait_def_id(cx, &paths::CONTAINS) else { return false };
+    cx.tcx.all_impls(contains_trait).any(|impl_id| {
+        impl_id.is_local()
+            && matches!(cx.tcx.type_of(impl_id).kind(), ty::Adt(adt, _) if adt.did() == filter)
+            && cx.tcx.associated_items(impl_id).in_definition_order().any(|item| {
+                item.name.as_str() == "contains" && returns_true(cx, item.def_id, 0)
+            })
+    })
+}
+
+// Whether the local function `fn_id` returns `true`, either as a literal or through a parameter
+// without arguments, like `AllowAnyOrigin::get()` for `parameter_types! { pub const AllowAnyOrigin: bool = true; }`.
+fn returns_true(cx: &LateContext<'_>, fn_id: DefId, depth: usize) -> bool {
+    let Some(local_id) = fn_id.as_local() else { return false };
+    let Some(body_id) = cx.tcx.hir().maybe_body_owned_by(local_id) else { return false };
+    // Parameters are rarely nested, this only guards against recursion.
+    if depth > 4 {
+        return false;
+    }
+
+    let value = peel_blocks(cx.tcx.hir().body(body_id).value);
+    match &value.kind {
+        hir::ExprKind::Lit(lit) => matches!(lit.node, LitKind::Bool(true)),
+        hir::ExprKind::Call(_, []) => {
+            called_fn(cx.tcx.typeck(local_id), value).map_or(false, |(callee, _)| returns_true(cx, callee, depth + 1))
+        },
+        _ => false,
+    }
+}
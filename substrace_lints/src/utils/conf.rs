@@ -31,6 +31,9 @@ const DEFAULT_DOC_VALID_IDENTS: &[&str] = &[
     "CamelCase",
 ];
 const DEFAULT_DISALLOWED_NAMES: &[&str] = &["foo", "baz", "quux"];
+const DEFAULT_PANICS_REQUIRED_LINTS: &[&str] = &["disallowed_methods", "indexing_slicing", "todo", "unwrap_used", "panic"];
+/// The keys only Substrace reads, which Clippy rejects in `clippy.toml`.
+const SUBSTRACE_ONLY_KEYS: &[&str] = &["panics-required-lints"];
 
 /// Holds information used by `MISSING_ENFORCED_IMPORT_RENAMES` lint.
 #[derive(Clone, Debug, Deserialize)]
@@ -205,9 +208,9 @@ macro_rules! define_Conf {
 }
 
 define_Conf! {
-    /// Lint: Arithmetic.
+    /// Lint: UNCHECKED_ARITHMETIC.
     ///
-    /// Suppress checking of the passed type names.
+    /// Suppress checking of the passed type names, e.g. `Balance` or `<T as Config>::Balance`.
     (arithmetic_side_effects_allowed: rustc_data_structures::fx::FxHashSet<String> = <_>::default()),
     /// Lint: ENUM_VARIANT_NAMES, LARGE_TYPES_PASSED_BY_VALUE, TRIVIALLY_COPY_PASS_BY_REF, UNNECESSARY_WRAPS, UNUSED_SELF, UPPER_CASE_ACRONYMS, WRONG_SELF_CONVENTION, BOX_COLLECTION, REDUNDANT_ALLOCATION, RC_BUFFER, VEC_BOX, OPTION_OPTION, LINKEDLIST, RC_MUTEX.
     ///
@@ -375,23 +378,63 @@ define_Conf! {
     ///
     /// The maximum size of the `Err`-variant in a `Result` returned from a function
     (large_error_threshold: u64 = 128),
+    /// Lint: PANICS.
+    ///
+    /// The Clippy lints that must be enabled in pallet and runtime crates, with or without the `clippy::` prefix
+    (panics_required_lints: Vec<String> = super::DEFAULT_PANICS_REQUIRED_LINTS.iter().map(ToString::to_string).collect()),
 }
 
 /// Search for the configuration file.
+///
+/// Falls back to Clippy's configuration file, which Substrace read before it had its own.
 pub fn lookup_conf_file() -> io::Result<Option<PathBuf>> {
+    /// Possible filename to search for.
+    const CONFIG_FILE_NAMES: [&str; 2] = [".substrace.toml", "substrace.toml"];
+
+    match lookup_file("SUBSTRACE_CONF_DIR", &CONFIG_FILE_NAMES)? {
+        Some(path) => Ok(Some(path)),
+        None => lookup_clippy_conf_file(),
+    }
+}
+
+/// Whether `path` is Clippy's configuration file rather than Substrace's.
+pub fn is_clippy_conf_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map_or(false, |name| name.ends_with("clippy.toml"))
+}
+
+/// The keys only Substrace reads that are set in the configuration file at `path`.
+pub fn substrace_only_keys(path: &Path) -> Vec<&'static str> {
+    let Ok(toml::Value::Table(table)) = fs::read_to_string(path)
+        .map_err(|_| ())
+        .and_then(|content| content.parse().map_err(|_| ())) else { return Vec::new() };
+    SUBSTRACE_ONLY_KEYS
+        .iter()
+        .copied()
+        .filter(|key| table.contains_key(*key))
+        .collect()
+}
+
+/// Search for the configuration file of Clippy, for the lints that check how Clippy is configured.
+pub fn lookup_clippy_conf_file() -> io::Result<Option<PathBuf>> {
     /// Possible filename to search for.
     const CONFIG_FILE_NAMES: [&str; 2] = [".clippy.toml", "clippy.toml"];
 
-    // Start looking for a config file in CLIPPY_CONF_DIR, or failing that, CARGO_MANIFEST_DIR.
+    lookup_file("CLIPPY_CONF_DIR", &CONFIG_FILE_NAMES)
+}
+
+fn lookup_file(conf_dir_var: &str, file_names: &[&str]) -> io::Result<Option<PathBuf>> {
+    // Start looking for a config file in `conf_dir_var`, or failing that, CARGO_MANIFEST_DIR.
     // If neither of those exist, use ".".
-    let mut current = env::var_os("CLIPPY_CONF_DIR")
+    let mut current = env::var_os(conf_dir_var)
         .or_else(|| env::var_os("CARGO_MANIFEST_DIR"))
         .map_or_else(|| PathBuf::from("."), PathBuf::from);
 
     let mut found_config: Option<PathBuf> = None;
 
     loop {
-        for config_file_name in &CONFIG_FILE_NAMES {
+        for config_file_name in file_names {
             if let Ok(config_file) = current.join(config_file_name).canonicalize() {
                 match fs::metadata(&config_file) {
                     Err(e) if e.kind() == io::ErrorKind::NotFound => {},
@@ -17,10 +17,11 @@ use rustc_middle::ty::{
     Predicate, PredicateKind, ProjectionTy, Region, RegionKind, SubstsRef, Ty, TyCtxt, TypeSuperVisitable,
     TypeVisitable, TypeVisitor, UintTy, VariantDef, VariantDiscr,
 };
+use rustc_middle::ty::layout::IntegerExt;
 use rustc_middle::ty::{GenericArg, GenericArgKind};
 use rustc_span::symbol::Ident;
 use rustc_span::{sym, Span, Symbol};
-use rustc_target::abi::{Size, VariantIdx};
+use rustc_target::abi::{Integer, Size, VariantIdx};
 use rustc_trait_selection::infer::InferCtxtExt;
 use rustc_trait_selection::traits::query::normalize::AtExt;
 use std::iter;
@@ -381,6 +382,16 @@ pub fn is_isize_or_usize(typ: Ty<'_>) -> bool {
     matches!(typ.kind(), ty::Int(IntTy::Isize) | ty::Uint(UintTy::Usize))
 }
 
+/// Returns the size in bits of an integer type, using the pointer width of the target for `isize`
+/// and `usize`, or `None` if `ty` isn't an integer.
+pub fn int_ty_bits(tcx: TyCtxt<'_>, ty: Ty<'_>) -> Option<u64> {
+    match ty.kind() {
+        ty::Int(ity) => Some(Integer::from_int_ty(&tcx, *ity).size().bits()),
+        ty::Uint(uty) => Some(Integer::from_uint_ty(&tcx, *uty).size().bits()),
+        _ => None,
+    }
+}
+
 /// Checks if type is struct, enum or union type with the given def path.
 ///
 /// If the type is a diagnostic item, use `is_type_diagnostic_item` instead.
@@ -0,0 +1,31 @@
+// Mocking the `#[transactional]` macro of frame-support-procedural
+// force-host
+// no-prefer-dynamic
+#![crate_type = "proc-macro"]
+
+extern crate proc_macro;
+
+use proc_macro::{Delimiter, Group, TokenStream, TokenTree};
+
+// Wraps the body of the function in `with_transaction`.
+#[proc_macro_attribute]
+pub fn transactional(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut tokens: Vec<TokenTree> = item.into_iter().collect();
+    let Some(TokenTree::Group(body)) = tokens.pop() else { panic!("expected a function") };
+
+    let mut wrapped: TokenStream = "::frame_support::storage::transactional::with_transaction(||)".parse().unwrap();
+    wrapped = wrapped
+        .into_iter()
+        .map(|token| match token {
+            TokenTree::Group(args) if args.delimiter() == Delimiter::Parenthesis => {
+                let mut inner = args.stream();
+                inner.extend([TokenTree::Group(body.clone())]);
+                TokenTree::Group(Group::new(Delimiter::Parenthesis, inner))
+            },
+            token => token,
+        })
+        .collect();
+
+    tokens.push(TokenTree::Group(Group::new(Delimiter::Brace, wrapped)));
+    tokens.into_iter().collect()
+}
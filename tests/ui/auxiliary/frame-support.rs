@@ -1,22 +1,650 @@
 // Mocking file structure of frame-support project
-#![allow(panics)]
+#![allow(substrace::panics)]
 #![allow(clippy::result_unit_err)]
-#![allow(enable_singlepass_benchmarks)]
+#![allow(substrace::enable_singlepass_benchmarks)]
 
 pub mod hash {
+    pub struct Blake2_128;
+    pub struct Blake2_256;
+    pub struct Blake2_128Concat;
+    pub struct Twox128;
+    pub struct Twox256;
     pub struct Twox64Concat;
+    pub struct Identity;
+
+    /// Hashers that store the key after the hash, so it can be read back when iterating.
+    pub trait ReversibleStorageHasher {}
+
+    impl ReversibleStorageHasher for Blake2_128Concat {}
+    impl ReversibleStorageHasher for Twox64Concat {}
+    impl ReversibleStorageHasher for Identity {}
+}
+
+pub use hash::*;
+pub use storage::bounded_vec::BoundedVec;
+
+#[macro_export]
+macro_rules! ensure {
+    ($x:expr, $y:expr $(,)?) => {{
+        if !$x {
+            return Err($y.into());
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! parameter_types {
+    ($(pub const $name:ident: $ty:ty = $value:expr;)*) => {
+        $(
+            pub struct $name;
+
+            impl $name {
+                pub const fn get() -> $ty {
+                    $value
+                }
+            }
+
+            impl<I: From<$ty>> $crate::traits::Get<I> for $name {
+                fn get() -> I {
+                    I::from(Self::get())
+                }
+            }
+        )*
+    };
+}
+
+// What the `#[pallet]` macro generates for the calls of a pallet: the `Call` enum, with its
+// `GetCallName` and `UnfilteredDispatchable` implementations, and its `GetDispatchInfo`
+// implementation when every call has a `#[weight(..)]`.
+#[macro_export]
+macro_rules! pallet_calls {
+    (
+        impl<$($gen:ident $(: $bound:path)?),*> $pallet:ty {
+            type Origin = $origin:ty;
+            $(
+                #[weight($weight:expr)]
+                fn $name:ident($($arg:ident: $ty:ty),* $(,)?);
+            )*
+        }
+    ) => {
+        $crate::pallet_calls! {
+            impl<$($gen $(: $bound)?),*> $pallet {
+                type Origin = $origin;
+                $(fn $name($($arg: $ty),*);)*
+            }
+        }
+
+        impl<$($gen $(: $bound)?),*> $crate::dispatch::GetDispatchInfo for Call<$($gen),*> {
+            fn get_dispatch_info(&self) -> $crate::dispatch::DispatchInfo {
+                match *self {
+                    $(
+                        Self::$name { $(ref $arg),* } => {
+                            let __pallet_base_weight = $weight;
+                            let __pallet_weight = <dyn $crate::dispatch::WeighData<($(&$ty,)*)>>::weigh_data(
+                                &__pallet_base_weight,
+                                ($($arg,)*),
+                            );
+                            $crate::dispatch::DispatchInfo { weight: __pallet_weight }
+                        },
+                    )*
+                    Self::__Ignore(_) => unreachable!("__Ignore cannot be used"),
+                }
+            }
+        }
+    };
+    (
+        impl<$($gen:ident $(: $bound:path)?),*> $pallet:ty {
+            type Origin = $origin:ty;
+            $(fn $name:ident($($arg:ident: $ty:ty),* $(,)?);)*
+        }
+    ) => {
+        pub enum Call<$($gen $(: $bound)?),*> {
+            $($name { $($arg: $ty),* },)*
+            __Ignore(core::marker::PhantomData<($($gen,)*)>),
+        }
+
+        impl<$($gen $(: $bound)?),*> $crate::dispatch::GetCallName for Call<$($gen),*> {
+            fn get_call_names() -> &'static [&'static str] {
+                &[$(stringify!($name)),*]
+            }
+        }
+
+        impl<$($gen $(: $bound)?),*> $crate::traits::UnfilteredDispatchable for Call<$($gen),*> {
+            type Origin = $origin;
+
+            fn dispatch_bypass_filter(self, origin: $origin) -> Result<(), ()> {
+                match self {
+                    $(Self::$name { $($arg),* } => <$pallet>::$name(origin $(, $arg)*).map_err(|_| ()),)*
+                    Self::__Ignore(_) => unreachable!("__Ignore cannot be used"),
+                }
+            }
+        }
+    };
+}
+
+pub mod dispatch {
+    use super::weights::Weight;
+
+    pub trait GetCallName {
+        fn get_call_names() -> &'static [&'static str];
+    }
+
+    pub struct DispatchInfo {
+        pub weight: Weight,
+    }
+
+    pub trait GetDispatchInfo {
+        fn get_dispatch_info(&self) -> DispatchInfo;
+    }
+
+    pub trait WeighData<T> {
+        fn weigh_data(&self, target: T) -> Weight;
+    }
+
+    impl<T> WeighData<T> for Weight {
+        fn weigh_data(&self, _: T) -> Weight {
+            *self
+        }
+    }
+
+    impl<T> WeighData<T> for u64 {
+        fn weigh_data(&self, _: T) -> Weight {
+            Weight::from_ref_time(*self)
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    pub enum DispatchClass {
+        Normal,
+        Operational,
+        Mandatory,
+    }
+
+    #[derive(Clone, Copy)]
+    pub enum Pays {
+        Yes,
+        No,
+    }
+
+    impl<T, W: WeighData<T>> WeighData<T> for (W, DispatchClass) {
+        fn weigh_data(&self, target: T) -> Weight {
+            self.0.weigh_data(target)
+        }
+    }
+
+    impl<T, W: WeighData<T>> WeighData<T> for (W, DispatchClass, Pays) {
+        fn weigh_data(&self, target: T) -> Weight {
+            self.0.weigh_data(target)
+        }
+    }
+}
+
+pub mod weights {
+    #[derive(Clone, Copy)]
+    pub struct Weight {
+        ref_time: u64,
+        proof_size: u64,
+    }
+
+    impl Weight {
+        pub const fn zero() -> Self {
+            Self::from_parts(0, 0)
+        }
+
+        pub const fn from_parts(ref_time: u64, proof_size: u64) -> Self {
+            Self { ref_time, proof_size }
+        }
+
+        pub const fn from_ref_time(ref_time: u64) -> Self {
+            Self::from_parts(ref_time, 0)
+        }
+
+        pub const fn ref_time(&self) -> u64 {
+            self.ref_time
+        }
+
+        pub const fn proof_size(&self) -> u64 {
+            self.proof_size
+        }
+
+        pub const fn saturating_add(self, rhs: Self) -> Self {
+            Self::from_parts(
+                self.ref_time.saturating_add(rhs.ref_time),
+                self.proof_size.saturating_add(rhs.proof_size),
+            )
+        }
+    }
+
+    impl core::ops::Add for Weight {
+        type Output = Self;
+
+        fn add(self, rhs: Self) -> Self {
+            self.saturating_add(rhs)
+        }
+    }
+
+    pub struct RuntimeDbWeight {
+        pub read: u64,
+        pub write: u64,
+    }
+
+    impl RuntimeDbWeight {
+        pub fn reads(&self, r: u64) -> Weight {
+            Weight::from_ref_time(self.read.saturating_mul(r))
+        }
+
+        pub fn writes(&self, w: u64) -> Weight {
+            Weight::from_ref_time(self.write.saturating_mul(w))
+        }
+
+        pub fn reads_writes(&self, r: u64, w: u64) -> Weight {
+            self.reads(r).saturating_add(self.writes(w))
+        }
+    }
 }
 
-pub struct Identity;
+pub mod traits {
+    pub mod misc {
+        pub trait Get<T> {
+            fn get() -> T;
+        }
+
+        pub struct ConstU32<const T: u32>;
+
+        impl<const T: u32> Get<u32> for ConstU32<T> {
+            fn get() -> u32 {
+                T
+            }
+        }
+    }
+
+    pub mod max_encoded_len {
+        pub trait MaxEncodedLen {
+            fn max_encoded_len() -> usize;
+        }
+
+        macro_rules! impl_primitives {
+            ($($ty:ty),*) => {
+                $(impl MaxEncodedLen for $ty {
+                    fn max_encoded_len() -> usize {
+                        core::mem::size_of::<$ty>()
+                    }
+                })*
+            };
+        }
+
+        impl_primitives!((), bool, u8, u32, u64, u128);
+
+        impl<T: MaxEncodedLen, S: super::Get<u32>> MaxEncodedLen for crate::BoundedVec<T, S> {
+            fn max_encoded_len() -> usize {
+                T::max_encoded_len() * S::get() as usize
+            }
+        }
+    }
+
+    pub mod dispatch {
+        pub trait UnfilteredDispatchable {
+            type Origin;
+
+            fn dispatch_bypass_filter(self, origin: Self::Origin) -> Result<(), ()>;
+        }
+    }
+
+    pub mod origin {
+        pub trait EnsureOrigin<OuterOrigin> {
+            type Success;
+
+            fn ensure_origin(o: OuterOrigin) -> Result<Self::Success, ()>;
+        }
+    }
+
+    pub mod hooks {
+        pub trait Hooks<BlockNumber> {
+            fn on_initialize(_n: BlockNumber) -> u64 {
+                0
+            }
+
+            fn on_finalize(_n: BlockNumber) {}
+
+            fn on_idle(_n: BlockNumber, _remaining_weight: u64) -> u64 {
+                0
+            }
+
+            fn on_runtime_upgrade() -> u64 {
+                0
+            }
+
+            fn offchain_worker(_n: BlockNumber) {}
+        }
+
+        pub trait OnRuntimeUpgrade {
+            fn on_runtime_upgrade() -> u64 {
+                0
+            }
+        }
+    }
+
+    pub mod randomness {
+        pub trait Randomness<Output, BlockNumber> {
+            fn random(subject: &[u8]) -> (Output, BlockNumber);
+
+            fn random_seed() -> (Output, BlockNumber) {
+                Self::random(&[][..])
+            }
+        }
+    }
+
+    pub mod time {
+        pub trait Time {
+            type Moment;
+
+            fn now() -> Self::Moment;
+        }
+
+        pub trait UnixTime {
+            fn now() -> core::time::Duration;
+        }
+    }
+
+    pub mod members {
+        pub trait Contains<T> {
+            fn contains(t: &T) -> bool;
+        }
+
+        pub enum Everything {}
+
+        impl<T> Contains<T> for Everything {
+            fn contains(_: &T) -> bool {
+                true
+            }
+        }
+    }
+
+    pub mod tokens {
+        pub mod currency {
+            pub trait Currency<AccountId> {
+                type Balance;
+                type PositiveImbalance;
+                type NegativeImbalance;
+
+                fn free_balance(who: &AccountId) -> Self::Balance;
+
+                fn transfer(source: &AccountId, dest: &AccountId, value: Self::Balance) -> Result<(), ()>;
+
+                fn slash(who: &AccountId, value: Self::Balance) -> (Self::NegativeImbalance, Self::Balance);
+
+                fn deposit_creating(who: &AccountId, value: Self::Balance) -> Self::PositiveImbalance;
+            }
+
+            pub mod reservable {
+                use super::Currency;
+
+                pub trait ReservableCurrency<AccountId>: Currency<AccountId> {
+                    fn reserve(who: &AccountId, value: Self::Balance) -> Result<(), ()>;
+
+                    fn unreserve(who: &AccountId, value: Self::Balance) -> Self::Balance;
+                }
+            }
+
+            pub use reservable::ReservableCurrency;
+        }
+
+        pub mod fungible {
+            pub trait Inspect<AccountId> {
+                type Balance;
+            }
+
+            pub trait Mutate<AccountId>: Inspect<AccountId> {
+                fn mint_into(who: &AccountId, amount: Self::Balance) -> Result<(), ()>;
+
+                fn transfer(source: &AccountId, dest: &AccountId, amount: Self::Balance) -> Result<Self::Balance, ()>;
+            }
+
+            pub trait MutateHold<AccountId>: Inspect<AccountId> {
+                fn hold(who: &AccountId, amount: Self::Balance) -> Result<(), ()>;
+
+                fn release(who: &AccountId, amount: Self::Balance, best_effort: bool) -> Result<Self::Balance, ()>;
+            }
+        }
+    }
+
+    pub use dispatch::UnfilteredDispatchable;
+    pub use origin::EnsureOrigin;
+    pub use tokens::currency::{Currency, ReservableCurrency};
+    pub use tokens::fungible;
+    pub use hooks::{Hooks, OnRuntimeUpgrade};
+    pub use members::{Contains, Everything};
+    pub use max_encoded_len::MaxEncodedLen;
+    pub use misc::{ConstU32, Get};
+    pub use randomness::Randomness;
+    pub use time::{Time, UnixTime};
+}
 
 pub mod storage {
+    pub mod bounded_vec {
+        pub struct BoundedVec<T, S>(Vec<T>, core::marker::PhantomData<S>);
+
+        impl<T, S> core::ops::Deref for BoundedVec<T, S> {
+            type Target = Vec<T>;
+
+            fn deref(&self) -> &Vec<T> {
+                &self.0
+            }
+        }
+    }
+
+    pub use bounded_vec::BoundedVec;
+
+    pub mod transactional {
+        pub fn with_transaction<R>(f: impl FnOnce() -> R) -> R {
+            f()
+        }
+    }
+
+    pub fn with_storage_layer<R, E>(f: impl FnOnce() -> Result<R, E>) -> Result<R, E> {
+        f()
+    }
+
+    pub struct MultiRemovalResults {
+        pub maybe_cursor: Option<Vec<u8>>,
+        pub backend: u32,
+        pub unique: u32,
+        pub loops: u32,
+    }
+
+    pub enum KillStorageResult {
+        AllRemoved(u32),
+        SomeRemaining(u32),
+    }
+
+    pub struct PrefixIterator<T>(core::marker::PhantomData<T>);
+
+    impl<T> Iterator for PrefixIterator<T> {
+        type Item = T;
+
+        fn next(&mut self) -> Option<T> {
+            None
+        }
+    }
+
     pub mod types {
         pub mod map {
+            use super::super::{KillStorageResult, MultiRemovalResults, PrefixIterator};
+            use crate::hash::ReversibleStorageHasher;
+
             pub struct StorageMap<
+                Prefix,
                 Hasher,
                 Key,
                 Value
-            >(core::marker::PhantomData<(Hasher, Key, Value)>);
+            >(core::marker::PhantomData<(Prefix, Hasher, Key, Value)>);
+
+            impl<Prefix, Hasher, Key, Value> StorageMap<Prefix, Hasher, Key, Value> {
+                pub fn get(_key: Key) -> Option<Value> {
+                    None
+                }
+
+                pub fn insert(_key: Key, _val: Value) {}
+
+                pub fn remove(_key: Key) {}
+
+                pub fn take(_key: Key) -> Option<Value> {
+                    None
+                }
+
+                pub fn mutate<R, F: FnOnce(&mut Option<Value>) -> R>(_key: Key, f: F) -> R {
+                    f(&mut None)
+                }
+
+                pub fn try_mutate<R, E, F: FnOnce(&mut Option<Value>) -> Result<R, E>>(_key: Key, f: F) -> Result<R, E> {
+                    f(&mut None)
+                }
+
+                pub fn iter_values() -> PrefixIterator<Value> {
+                    PrefixIterator(core::marker::PhantomData)
+                }
+
+                pub fn clear(_limit: u32, _maybe_cursor: Option<&[u8]>) -> MultiRemovalResults {
+                    MultiRemovalResults { maybe_cursor: None, backend: 0, unique: 0, loops: 0 }
+                }
+
+                pub fn remove_all(_limit: Option<u32>) -> KillStorageResult {
+                    KillStorageResult::AllRemoved(0)
+                }
+            }
+
+            impl<Prefix, Hasher: ReversibleStorageHasher, Key, Value> StorageMap<Prefix, Hasher, Key, Value> {
+                pub fn iter() -> PrefixIterator<(Key, Value)> {
+                    PrefixIterator(core::marker::PhantomData)
+                }
+
+                pub fn iter_keys() -> PrefixIterator<Key> {
+                    PrefixIterator(core::marker::PhantomData)
+                }
+
+                pub fn drain() -> PrefixIterator<(Key, Value)> {
+                    PrefixIterator(core::marker::PhantomData)
+                }
+            }
+        }
+
+        pub mod double_map {
+            use super::super::{KillStorageResult, MultiRemovalResults, PrefixIterator};
+            use crate::hash::ReversibleStorageHasher;
+
+            pub struct StorageDoubleMap<
+                Prefix,
+                Hasher1,
+                Key1,
+                Hasher2,
+                Key2,
+                Value
+            >(core::marker::PhantomData<(Prefix, Hasher1, Key1, Hasher2, Key2, Value)>);
+
+            impl<Prefix, Hasher1, Key1, Hasher2, Key2, Value> StorageDoubleMap<Prefix, Hasher1, Key1, Hasher2, Key2, Value> {
+                pub fn get(_k1: Key1, _k2: Key2) -> Option<Value> {
+                    None
+                }
+
+                pub fn insert(_k1: Key1, _k2: Key2, _val: Value) {}
+
+                pub fn iter_prefix_values(_k1: Key1) -> PrefixIterator<Value> {
+                    PrefixIterator(core::marker::PhantomData)
+                }
+
+                pub fn clear_prefix(_k1: Key1, _limit: u32, _maybe_cursor: Option<&[u8]>) -> MultiRemovalResults {
+                    MultiRemovalResults { maybe_cursor: None, backend: 0, unique: 0, loops: 0 }
+                }
+
+                pub fn remove_prefix(_k1: Key1, _limit: Option<u32>) -> KillStorageResult {
+                    KillStorageResult::AllRemoved(0)
+                }
+            }
+
+            impl<Prefix, Hasher1, Key1, Hasher2: ReversibleStorageHasher, Key2, Value>
+                StorageDoubleMap<Prefix, Hasher1, Key1, Hasher2, Key2, Value>
+            {
+                pub fn iter_prefix(_k1: Key1) -> PrefixIterator<(Key2, Value)> {
+                    PrefixIterator(core::marker::PhantomData)
+                }
+            }
+
+            impl<Prefix, Hasher1: ReversibleStorageHasher, Key1, Hasher2: ReversibleStorageHasher, Key2, Value>
+                StorageDoubleMap<Prefix, Hasher1, Key1, Hasher2, Key2, Value>
+            {
+                pub fn iter() -> PrefixIterator<(Key1, Key2, Value)> {
+                    PrefixIterator(core::marker::PhantomData)
+                }
+            }
+        }
+
+        pub mod value {
+            pub struct StorageValue<Prefix, Value>(core::marker::PhantomData<(Prefix, Value)>);
+
+            impl<Prefix, Value> StorageValue<Prefix, Value> {
+                pub fn get() -> Option<Value> {
+                    None
+                }
+
+                pub fn put(_val: Value) {}
+
+                pub fn kill() {}
+            }
+        }
+
+        pub mod key {
+            use crate::hash::ReversibleStorageHasher;
+
+            pub struct Key<Hasher, KeyType>(core::marker::PhantomData<(Hasher, KeyType)>);
+
+            /// Keys whose suffix after the partial key `P` can be read back when iterating.
+            pub trait HasReversibleKeyPrefix<P> {}
+
+            impl<H1, K1, H2: ReversibleStorageHasher, K2> HasReversibleKeyPrefix<K1> for (Key<H1, K1>, Key<H2, K2>) {}
+
+            impl<H1, K1, H2: ReversibleStorageHasher, K2, H3: ReversibleStorageHasher, K3> HasReversibleKeyPrefix<K1>
+                for (Key<H1, K1>, Key<H2, K2>, Key<H3, K3>)
+            {
+            }
+
+            impl<H1, K1, H2, K2, H3: ReversibleStorageHasher, K3> HasReversibleKeyPrefix<(K1, K2)>
+                for (Key<H1, K1>, Key<H2, K2>, Key<H3, K3>)
+            {
+            }
+        }
+
+        pub mod nmap {
+            use super::super::PrefixIterator;
+            use super::key::HasReversibleKeyPrefix;
+
+            pub struct StorageNMap<
+                Prefix,
+                Key,
+                Value
+            >(core::marker::PhantomData<(Prefix, Key, Value)>);
+
+            impl<Prefix, Key, Value> StorageNMap<Prefix, Key, Value> {
+                pub fn iter_values() -> PrefixIterator<Value> {
+                    PrefixIterator(core::marker::PhantomData)
+                }
+
+                pub fn iter_prefix_values<KP>(_partial_key: KP) -> PrefixIterator<Value> {
+                    PrefixIterator(core::marker::PhantomData)
+                }
+
+                pub fn iter_prefix<KP>(_partial_key: KP) -> PrefixIterator<Value>
+                where
+                    Key: HasReversibleKeyPrefix<KP>,
+                {
+                    PrefixIterator(core::marker::PhantomData)
+                }
+            }
         }
+
+        pub use double_map::StorageDoubleMap;
+        pub use key::Key;
+        pub use map::StorageMap;
+        pub use nmap::StorageNMap;
+        pub use value::StorageValue;
     }
 }
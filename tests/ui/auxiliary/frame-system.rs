@@ -0,0 +1,52 @@
+// Mocking file structure of frame-system project
+#![allow(substrace::panics)]
+#![allow(clippy::result_unit_err)]
+#![allow(substrace::enable_singlepass_benchmarks)]
+
+#[derive(Clone)]
+pub enum RawOrigin<AccountId> {
+    Root,
+    Signed(AccountId),
+    None,
+}
+
+pub fn ensure_signed<AccountId>(o: RawOrigin<AccountId>) -> Result<AccountId, ()> {
+    match o {
+        RawOrigin::Signed(who) => Ok(who),
+        _ => Err(()),
+    }
+}
+
+pub fn ensure_root<AccountId>(o: RawOrigin<AccountId>) -> Result<(), ()> {
+    match o {
+        RawOrigin::Root => Ok(()),
+        _ => Err(()),
+    }
+}
+
+pub fn ensure_none<AccountId>(o: RawOrigin<AccountId>) -> Result<(), ()> {
+    match o {
+        RawOrigin::None => Ok(()),
+        _ => Err(()),
+    }
+}
+
+pub struct Pallet<T>(core::marker::PhantomData<T>);
+
+impl<T> Pallet<T> {
+    pub fn block_number() -> u32 {
+        0
+    }
+
+    pub fn parent_hash() -> [u8; 32] {
+        [0; 32]
+    }
+
+    pub fn block_hash(_n: u32) -> [u8; 32] {
+        [0; 32]
+    }
+
+    pub fn extrinsic_index() -> Option<u32> {
+        None
+    }
+}
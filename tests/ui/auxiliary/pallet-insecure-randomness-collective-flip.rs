@@ -0,0 +1,21 @@
+// Mocking file structure of pallet-insecure-randomness-collective-flip project
+#![allow(substrace::panics)]
+#![allow(substrace::enable_singlepass_benchmarks)]
+
+extern crate frame_support;
+
+use frame_support::traits::Randomness;
+
+pub struct Pallet<T>(core::marker::PhantomData<T>);
+
+impl<T> Pallet<T> {
+    pub fn random_material() -> Vec<[u8; 32]> {
+        Vec::new()
+    }
+}
+
+impl<T> Randomness<[u8; 32], u32> for Pallet<T> {
+    fn random(_subject: &[u8]) -> ([u8; 32], u32) {
+        ([0; 32], 0)
+    }
+}
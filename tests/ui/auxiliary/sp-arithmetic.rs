@@ -0,0 +1,116 @@
+// Mocking file structure of sp-arithmetic project
+#![allow(substrace::panics)]
+#![allow(substrace::enable_singlepass_benchmarks)]
+
+pub mod traits {
+    use core::ops::{Add, AddAssign, Div, Mul, Rem, Sub, SubAssign};
+
+    pub trait Saturating {
+        fn saturating_add(self, rhs: Self) -> Self;
+        fn saturating_sub(self, rhs: Self) -> Self;
+        fn saturating_mul(self, rhs: Self) -> Self;
+    }
+
+    pub trait CheckedDiv: Sized {
+        fn checked_div(&self, v: &Self) -> Option<Self>;
+    }
+
+    pub trait CheckedRem: Sized {
+        fn checked_rem(&self, v: &Self) -> Option<Self>;
+    }
+
+    pub trait UniqueSaturatedFrom<T>: Sized {
+        fn unique_saturated_from(t: T) -> Self;
+    }
+
+    pub trait UniqueSaturatedInto<T>: Sized {
+        fn unique_saturated_into(self) -> T;
+    }
+
+    impl<T: TryInto<S>, S: Default> UniqueSaturatedFrom<T> for S {
+        fn unique_saturated_from(t: T) -> S {
+            t.try_into().unwrap_or_default()
+        }
+    }
+
+    impl<T, S: UniqueSaturatedFrom<T>> UniqueSaturatedInto<S> for T {
+        fn unique_saturated_into(self) -> S {
+            S::unique_saturated_from(self)
+        }
+    }
+
+    pub trait SaturatedConversion {
+        fn saturated_from<T>(t: T) -> Self
+        where
+            Self: UniqueSaturatedFrom<T>,
+        {
+            <Self as UniqueSaturatedFrom<T>>::unique_saturated_from(t)
+        }
+
+        fn saturated_into<T>(self) -> T
+        where
+            Self: UniqueSaturatedInto<T>,
+        {
+            <Self as UniqueSaturatedInto<T>>::unique_saturated_into(self)
+        }
+    }
+
+    impl<T: Sized> SaturatedConversion for T {}
+
+    pub trait AtLeast32Bit:
+        Copy
+        + Add<Output = Self>
+        + Sub<Output = Self>
+        + Mul<Output = Self>
+        + Div<Output = Self>
+        + Rem<Output = Self>
+        + AddAssign
+        + SubAssign
+        + Saturating
+        + CheckedDiv
+        + CheckedRem
+        + From<u32>
+        + UniqueSaturatedInto<u32>
+        + UniqueSaturatedInto<u64>
+        + UniqueSaturatedInto<u128>
+        + UniqueSaturatedFrom<u32>
+        + UniqueSaturatedFrom<u64>
+        + UniqueSaturatedFrom<u128>
+    {
+    }
+
+    pub trait AtLeast32BitUnsigned: AtLeast32Bit {}
+}
+
+pub mod fixed_point {
+    #[derive(Clone, Copy)]
+    pub struct FixedU128(u128);
+
+    impl FixedU128 {
+        pub const fn from_rational(a: u128, b: u128) -> Self {
+            Self(a / b)
+        }
+
+        pub fn from_float(x: f64) -> Self {
+            Self(x as u128)
+        }
+
+        pub fn to_float(self) -> f64 {
+            self.0 as f64
+        }
+    }
+}
+
+pub mod per_things {
+    #[derive(Clone, Copy)]
+    pub struct Perbill(u32);
+
+    impl Perbill {
+        pub const fn from_percent(x: u32) -> Self {
+            Self(x * 10_000_000)
+        }
+    }
+}
+
+pub use fixed_point::FixedU128;
+pub use per_things::Perbill;
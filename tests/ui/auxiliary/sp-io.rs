@@ -0,0 +1,21 @@
+// Mocking file structure of sp-io project
+#![allow(substrace::panics)]
+#![allow(substrace::enable_singlepass_benchmarks)]
+
+pub mod hashing {
+    pub fn blake2_128(_data: &[u8]) -> [u8; 16] {
+        [0; 16]
+    }
+
+    pub fn blake2_256(_data: &[u8]) -> [u8; 32] {
+        [0; 32]
+    }
+
+    pub fn keccak_256(_data: &[u8]) -> [u8; 32] {
+        [0; 32]
+    }
+
+    pub fn twox_128(_data: &[u8]) -> [u8; 16] {
+        [0; 16]
+    }
+}
@@ -0,0 +1,25 @@
+// Mocking file structure of sp-runtime project
+#![allow(substrace::panics)]
+#![allow(substrace::enable_singlepass_benchmarks)]
+
+pub mod traits {
+    pub trait Hash {
+        type Output;
+
+        fn hash(s: &[u8]) -> Self::Output;
+
+        fn hash_of<S: AsRef<[u8]>>(s: &S) -> Self::Output {
+            Self::hash(s.as_ref())
+        }
+    }
+
+    pub struct BlakeTwo256;
+
+    impl Hash for BlakeTwo256 {
+        type Output = [u8; 32];
+
+        fn hash(_s: &[u8]) -> [u8; 32] {
+            [0; 32]
+        }
+    }
+}
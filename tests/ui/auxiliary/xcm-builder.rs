@@ -0,0 +1,17 @@
+// Mocking file structure of xcm-builder project
+#![allow(substrace::panics)]
+#![allow(substrace::enable_singlepass_benchmarks)]
+
+pub mod barriers {
+    use core::marker::PhantomData;
+
+    pub struct TakeWeightCredit;
+    pub struct AllowUnpaidExecutionFrom<T>(PhantomData<T>);
+    pub struct AllowTopLevelPaidExecutionFrom<T>(PhantomData<T>);
+    pub struct AllowKnownQueryResponses<ResponseHandler>(PhantomData<ResponseHandler>);
+    pub struct WithComputedOrigin<InnerBarrier, LocalUniversal, MaxPrefixes>(
+        PhantomData<(InnerBarrier, LocalUniversal, MaxPrefixes)>,
+    );
+}
+
+pub use barriers::*;
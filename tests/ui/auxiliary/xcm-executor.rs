@@ -0,0 +1,11 @@
+// Mocking file structure of xcm-executor project
+#![allow(substrace::panics)]
+#![allow(substrace::enable_singlepass_benchmarks)]
+
+pub mod config {
+    pub trait Config {
+        type Barrier;
+    }
+}
+
+pub use config::Config;
@@ -0,0 +1,115 @@
+#![allow(substrace::panics)]
+#![allow(substrace::enable_singlepass_benchmarks, substrace::missing_transactional, substrace::unchecked_origin)]
+#![allow(substrace::unbounded_extrinsic_params, substrace::db_weight_mismatch)]
+#![allow(dead_code, non_camel_case_types, unused_variables)]
+// aux-build:frame-support.rs
+
+extern crate frame_support;
+
+use frame_support::dispatch::{DispatchClass, Pays};
+use frame_support::traits::Get;
+use frame_support::weights::{RuntimeDbWeight, Weight};
+
+pub trait WeightInfo {
+    fn transfer() -> Weight;
+    fn set_members(m: u32) -> Weight;
+    fn remark(b: u32) -> Weight;
+    fn vote() -> Weight;
+    fn claim() -> Weight;
+    fn close() -> Weight;
+}
+
+pub trait Config {
+    type DbWeight: Get<RuntimeDbWeight>;
+    type WeightInfo: WeightInfo;
+}
+
+pub struct Pallet<T>(core::marker::PhantomData<T>);
+
+impl<T: Config> Pallet<T> {
+    pub fn transfer(origin: u64, dest: u64, amount: u64) -> Result<(), ()> {
+        Ok(())
+    }
+
+    pub fn set_members(origin: u64, members: Vec<u64>) -> Result<(), ()> {
+        Ok(())
+    }
+
+    pub fn remark(origin: u64, remark: Vec<u8>) -> Result<(), ()> {
+        Ok(())
+    }
+
+    pub fn vote(origin: u64, proposal: u32) -> Result<(), ()> {
+        Ok(())
+    }
+
+    pub fn claim(origin: u64) -> Result<(), ()> {
+        Ok(())
+    }
+
+    pub fn close(origin: u64, proposal: u32) -> Result<(), ()> {
+        Ok(())
+    }
+
+    pub fn kill(origin: u64) -> Result<(), ()> {
+        Ok(())
+    }
+
+    pub fn set_code(origin: u64, code: Vec<u8>) -> Result<(), ()> {
+        Ok(())
+    }
+
+    pub fn note_preimage(origin: u64, preimage: Vec<u8>) -> Result<(), ()> {
+        Ok(())
+    }
+
+    pub fn emergency_cancel(origin: u64, proposal: u32) -> Result<(), ()> {
+        Ok(())
+    }
+
+    #[allow(substrace::constant_weights)]
+    pub fn force_transfer(origin: u64, dest: u64, amount: u64) -> Result<(), ()> {
+        Ok(())
+    }
+}
+
+frame_support::pallet_calls! {
+    impl<T: Config> Pallet<T> {
+        type Origin = u64;
+
+        #[weight(Weight::zero())]
+        fn transfer(dest: u64, amount: u64);
+
+        #[weight(T::WeightInfo::set_members(members.len() as u32))]
+        fn set_members(members: Vec<u64>);
+
+        #[weight(0)]
+        fn remark(remark: Vec<u8>);
+
+        #[weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(2, 1))]
+        fn vote(proposal: u32);
+
+        #[weight(Weight::from_ref_time(T::WeightInfo::claim().ref_time()))]
+        fn claim();
+
+        #[weight(T::WeightInfo::close().saturating_add(T::DbWeight::get().reads(1)))]
+        fn close(proposal: u32);
+
+        #[weight(Weight::from_ref_time(1_000_000))]
+        fn kill();
+
+        #[weight((0, DispatchClass::Operational))]
+        fn set_code(code: Vec<u8>);
+
+        #[weight((Weight::zero(), DispatchClass::Normal, Pays::No))]
+        fn note_preimage(preimage: Vec<u8>);
+
+        #[weight((T::WeightInfo::vote(), DispatchClass::Operational))]
+        fn emergency_cancel(proposal: u32);
+
+        #[weight(0)]
+        fn force_transfer(dest: u64, amount: u64);
+    }
+}
+
+fn main() {}
@@ -0,0 +1,166 @@
+#![allow(substrace::panics)]
+#![allow(substrace::enable_singlepass_benchmarks, substrace::missing_transactional, substrace::unchecked_origin)]
+#![allow(substrace::constant_weights, substrace::storage_iter_insert, substrace::unbounded_storage_iteration)]
+#![allow(substrace::flat_weights, substrace::unbounded_extrinsic_params)]
+#![allow(dead_code, non_camel_case_types, unused_variables)]
+// aux-build:frame-support.rs
+
+extern crate frame_support;
+
+use frame_support::storage::types::{StorageMap, StorageValue};
+use frame_support::traits::Get;
+use frame_support::weights::{RuntimeDbWeight, Weight};
+use frame_support::Blake2_128Concat;
+
+pub struct _GeneratedPrefixForStorageMemberCount;
+pub struct _GeneratedPrefixForStorageDeposits;
+pub struct _GeneratedPrefixForStorageLastBump;
+
+pub type MemberCount = StorageValue<_GeneratedPrefixForStorageMemberCount, u32>;
+pub type Deposits = StorageMap<_GeneratedPrefixForStorageDeposits, Blake2_128Concat, u64, u64>;
+pub type LastBump = StorageValue<_GeneratedPrefixForStorageLastBump, u64>;
+
+pub trait Config {
+    type DbWeight: Get<RuntimeDbWeight>;
+}
+
+pub struct Pallet<T>(core::marker::PhantomData<T>);
+
+impl<T: Config> Pallet<T> {
+    pub fn join(origin: u64, deposit: u64) -> Result<(), ()> {
+        if Deposits::get(origin).is_some() {
+            return Err(());
+        }
+        Deposits::insert(origin, deposit);
+        Ok(())
+    }
+
+    pub fn leave(origin: u64) -> Result<(), ()> {
+        let deposit = Deposits::take(origin);
+        let count = MemberCount::get().unwrap_or_default();
+        MemberCount::put(count.saturating_sub(1));
+        Ok(())
+    }
+
+    pub fn bump(origin: u64, amount: u64) -> Result<(), ()> {
+        Deposits::mutate(origin, |deposit| {
+            *deposit = Some(deposit.unwrap_or_default() + amount);
+            Self::note_bump(amount);
+        });
+        Ok(())
+    }
+
+    fn note_bump(amount: u64) {
+        LastBump::put(amount);
+    }
+
+    pub fn deposit_of(origin: u64) -> Result<(), ()> {
+        let deposit = Deposits::get(origin);
+        Ok(())
+    }
+
+    pub fn refund_all(origin: u64) -> Result<(), ()> {
+        for (who, deposit) in Deposits::iter() {
+            Deposits::remove(who);
+        }
+        Ok(())
+    }
+
+    pub fn reset(origin: u64) -> Result<(), ()> {
+        MemberCount::kill();
+        Ok(())
+    }
+
+    pub fn transfer(origin: u64, to: u64, amount: u64) -> Result<(), ()> {
+        let from_deposit = Deposits::get(origin.clone()).unwrap_or_default();
+        let to_deposit = Deposits::get(to).unwrap_or_default();
+        Deposits::insert(origin, from_deposit - amount);
+        Deposits::insert(to, to_deposit + amount);
+        Ok(())
+    }
+
+    pub fn remove_listed(origin: u64, who: Vec<u64>) -> Result<(), ()> {
+        who.iter().for_each(|who| Deposits::remove(*who));
+        Ok(())
+    }
+
+    pub fn refund_listed(origin: u64, who: Vec<u64>) -> Result<(), ()> {
+        who.iter().for_each(|who| Deposits::remove(*who));
+        Ok(())
+    }
+
+    pub fn top_up(origin: u64, amount: u64) -> Result<(), ()> {
+        let deposit = Deposits::get(origin.clone()).unwrap_or_default();
+        Self::add_deposit(&origin, deposit + amount);
+        Ok(())
+    }
+
+    fn add_deposit(account: &u64, amount: u64) {
+        Deposits::mutate(*account, |deposit| *deposit = Some(amount));
+    }
+
+    pub fn swap_deposits(origin: u64, other: u64) -> Result<(), ()> {
+        Self::take_deposit(origin);
+        Self::restore_deposit(other);
+        Ok(())
+    }
+
+    fn take_deposit(who: u64) {
+        Deposits::take(who);
+    }
+
+    fn restore_deposit(who: u64) {
+        Deposits::insert(who, 0);
+    }
+
+    #[allow(substrace::db_weight_mismatch)]
+    pub fn force_leave(origin: u64, who: u64) -> Result<(), ()> {
+        Deposits::remove(who);
+        MemberCount::kill();
+        Ok(())
+    }
+}
+
+frame_support::pallet_calls! {
+    impl<T: Config> Pallet<T> {
+        type Origin = u64;
+
+        #[weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1, 1))]
+        fn join(deposit: u64);
+
+        #[weight(T::DbWeight::get().reads_writes(1, 1))]
+        fn leave();
+
+        #[weight(T::DbWeight::get().reads(1).saturating_add(T::DbWeight::get().writes(1)))]
+        fn bump(amount: u64);
+
+        #[weight(T::DbWeight::get().reads_writes(3, 1))]
+        fn deposit_of();
+
+        #[weight(T::DbWeight::get().reads(1))]
+        fn refund_all();
+
+        #[weight(Weight::from_parts(10_000, 512))]
+        fn reset();
+
+        #[weight(T::DbWeight::get().reads_writes(2, 2))]
+        fn transfer(to: u64, amount: u64);
+
+        #[weight(T::DbWeight::get().writes(10))]
+        fn remove_listed(who: Vec<u64>);
+
+        #[weight(Weight::from_parts(10_000, 0))]
+        fn refund_listed(who: Vec<u64>);
+
+        #[weight(T::DbWeight::get().reads_writes(1, 1))]
+        fn top_up(amount: u64);
+
+        #[weight(T::DbWeight::get().reads_writes(1, 1))]
+        fn swap_deposits(other: u64);
+
+        #[weight(T::DbWeight::get().writes(1))]
+        fn force_leave(who: u64);
+    }
+}
+
+fn main() {}
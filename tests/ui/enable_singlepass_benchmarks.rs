@@ -1,4 +1,6 @@
-#![allow(panics)]
+// run-rustfix
+#![allow(substrace::panics)]
+#![allow(dead_code)]
 // This should not be flagged
 // #[cfg(feature = "runtime-benchmarks")]
 
@@ -38,6 +40,34 @@ fn f() {
 
 }
 
+// This should
+#[cfg(all(feature = "std", feature = "runtime-benchmarks"))]
+fn g() {
+
+}
+
+// This should
+#[cfg_attr(feature = "runtime-benchmarks", derive(Clone))]
+struct H;
+
+// This should not
+#[cfg(not(feature = "runtime-benchmarks"))]
+fn i() {
+
+}
+
+// This should not
+#[cfg(all(feature = "runtime-benchmarks", test))]
+fn j() {
+
+}
+
+// This should not
+#[cfg(any(all(feature = "runtime-benchmarks", feature = "std"), all(test, feature = "std")))]
+fn k() {
+
+}
+
 fn main() {
 
-}
\ No newline at end of file
+}
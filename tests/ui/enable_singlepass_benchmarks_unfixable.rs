@@ -0,0 +1,17 @@
+#![allow(substrace::panics)]
+
+// This should, but can't be fixed by adding `test`
+#[cfg(all(not(test), feature = "runtime-benchmarks"))]
+fn a() {
+
+}
+
+// This should, but can't be fixed by adding `test`
+#[cfg(not(any(test, not(feature = "runtime-benchmarks"))))]
+fn b() {
+
+}
+
+fn main() {
+
+}
@@ -0,0 +1,51 @@
+// run-rustfix
+#![feature(register_tool)]
+#![register_tool(pallet)]
+#![allow(substrace::panics)]
+#![allow(dead_code)]
+
+pub mod pallet {
+    pub struct Pallet<T>(T);
+
+    #[pallet::call]
+    impl<T> Pallet<T> {
+        #[pallet::weight(0)]
+        pub fn untagged(_origin: u32) {}
+
+        #[pallet::weight(0)]
+        #[pallet::call_index(1)]
+        pub fn tagged(_origin: u32) {}
+
+        // The pallet macro numbers this one 2
+        #[pallet::weight(0)]
+        pub fn after_tagged(_origin: u32) {}
+    }
+}
+
+pub mod other_pallet {
+    pub struct Pallet<T>(T);
+
+    #[pallet::call]
+    impl<T> Pallet<T> {
+        #[pallet::call_index(5)]
+        pub fn first(_origin: u32) {}
+
+        // Numbered 6 by the pallet macro
+        pub fn second(_origin: u32) {}
+
+        // Implicitly 7, which conflicts with `fourth`
+        pub fn third(_origin: u32) {}
+
+        #[pallet::call_index(7)]
+        pub fn fourth(_origin: u32) {}
+
+        // Implicitly 8
+        pub fn fifth(_origin: u32) {}
+    }
+
+    impl<T> Pallet<T> {
+        pub fn not_an_extrinsic() {}
+    }
+}
+
+fn main() {}
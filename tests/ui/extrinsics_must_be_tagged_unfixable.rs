@@ -0,0 +1,31 @@
+#![feature(register_tool)]
+#![register_tool(pallet)]
+#![allow(substrace::panics)]
+#![allow(dead_code)]
+
+pub struct Pallet<T>(T);
+
+const REMARK_INDEX: u8 = 2;
+
+#[pallet::call]
+impl<T> Pallet<T> {
+    #[pallet::call_index(0)]
+    pub fn first(_origin: u32) {}
+
+    #[pallet::call_index(1)]
+    pub fn second(_origin: u32) {}
+
+    #[pallet::call_index(0)]
+    pub fn duplicate(_origin: u32) {}
+
+    #[pallet::call_index(256)]
+    pub fn out_of_range(_origin: u32) {}
+
+    // Not a literal, so the index is only known after expansion.
+    #[pallet::call_index(REMARK_INDEX)]
+    pub fn remark(_origin: u32) {}
+
+    pub fn after_remark(_origin: u32) {}
+}
+
+fn main() {}
@@ -0,0 +1,120 @@
+#![allow(substrace::panics)]
+#![allow(substrace::enable_singlepass_benchmarks, substrace::missing_transactional, substrace::unchecked_origin)]
+#![allow(substrace::unbounded_extrinsic_params, substrace::constant_weights)]
+#![allow(dead_code, non_camel_case_types, unused_variables)]
+// aux-build:frame-support.rs
+
+extern crate frame_support;
+
+use frame_support::traits::Get;
+use frame_support::weights::Weight;
+use frame_support::BoundedVec;
+
+pub trait WeightInfo {
+    fn set_members(m: u32) -> Weight;
+    fn add_members(m: u32) -> Weight;
+    fn refund() -> Weight;
+    fn vote() -> Weight;
+}
+
+pub trait Config {
+    type MaxMembers: Get<u32>;
+    type MaxProposals: Get<u32>;
+    type WeightInfo: WeightInfo;
+}
+
+pub struct Pallet<T>(core::marker::PhantomData<T>);
+
+impl<T: Config> Pallet<T> {
+    pub fn set_members(origin: u64, members: Vec<u64>) -> Result<(), ()> {
+        for member in members.iter() {
+            let _ = member;
+        }
+        Ok(())
+    }
+
+    pub fn add_members(origin: u64, members: BoundedVec<u64, T::MaxMembers>) -> Result<(), ()> {
+        let total: u64 = members.iter().copied().sum();
+        Ok(())
+    }
+
+    pub fn remove_members(origin: u64, members: BoundedVec<u64, T::MaxMembers>) -> Result<(), ()> {
+        for member in members.iter() {
+            let _ = member;
+        }
+        Ok(())
+    }
+
+    pub fn refund(origin: u64, count: u32) -> Result<(), ()> {
+        let refunds = count as usize;
+        for i in 0..refunds {
+            let _ = i;
+        }
+        Ok(())
+    }
+
+    pub fn distribute(origin: u64, recipients: Vec<u64>, amount: u64) -> Result<(), ()> {
+        Self::pay_all(&recipients, amount);
+        Ok(())
+    }
+
+    fn pay_all(recipients: &[u64], amount: u64) {
+        recipients.iter().for_each(|who| {
+            let _ = (who, amount);
+        });
+    }
+
+    pub fn vote(origin: u64, proposal: u32, ayes: Vec<u64>) -> Result<(), ()> {
+        for voter in Self::voters(proposal) {
+            let _ = voter;
+        }
+        for aye in ayes.iter().take(10) {
+            let _ = aye;
+        }
+        for i in 0..(proposal.min(10)) {
+            let _ = i;
+        }
+        Ok(())
+    }
+
+    fn voters(proposal: u32) -> Vec<u64> {
+        Vec::new()
+    }
+
+    #[allow(substrace::flat_weights)]
+    pub fn force_set_members(origin: u64, members: Vec<u64>) -> Result<(), ()> {
+        for member in members {
+            let _ = member;
+        }
+        Ok(())
+    }
+}
+
+frame_support::pallet_calls! {
+    impl<T: Config> Pallet<T> {
+        type Origin = u64;
+
+        #[weight(T::WeightInfo::set_members(members.len() as u32))]
+        fn set_members(members: Vec<u64>);
+
+        #[weight(T::WeightInfo::add_members(T::MaxMembers::get()))]
+        fn add_members(members: BoundedVec<u64, T::MaxMembers>);
+
+        #[weight(T::WeightInfo::add_members(T::MaxProposals::get()))]
+        fn remove_members(members: BoundedVec<u64, T::MaxMembers>);
+
+        #[weight(T::WeightInfo::refund())]
+        fn refund(count: u32);
+
+        #[weight(Weight::from_parts(10_000, 1_024))]
+        fn distribute(recipients: Vec<u64>, amount: u64);
+
+        #[weight(T::WeightInfo::vote())]
+        fn vote(proposal: u32, ayes: Vec<u64>);
+
+        #[weight(Weight::zero())]
+        fn force_set_members(members: Vec<u64>);
+    }
+}
+
+fn main() {}
@@ -0,0 +1,71 @@
+#![allow(substrace::panics)]
+#![allow(substrace::enable_singlepass_benchmarks, substrace::missing_transactional, substrace::unchecked_origin)]
+#![allow(dead_code, non_camel_case_types)]
+// aux-build:frame-support.rs
+// aux-build:sp-arithmetic.rs
+
+#[macro_use]
+extern crate frame_support;
+extern crate sp_arithmetic;
+
+use frame_support::traits::Hooks;
+use sp_arithmetic::{FixedU128, Perbill};
+
+parameter_types! {
+    pub const BurnRatio: f64 = 0.25;
+    pub const Deposit: u64 = 100;
+}
+
+pub struct Pallet<T>(core::marker::PhantomData<T>);
+
+impl<T> Pallet<T> {
+    pub fn transfer(_origin: u64, amount: u64) -> Result<(), ()> {
+        let _fee = Self::fee(amount);
+        Ok(())
+    }
+
+    pub fn set_rate(_origin: u64, rate: FixedU128) -> Result<(), ()> {
+        let _rate: f64 = rate.to_float();
+        let _rate = FixedU128::from_float(1.5);
+        let _share = Perbill::from_percent(5);
+        Ok(())
+    }
+
+    fn fee(amount: u64) -> u64 {
+        (amount as f64 * 0.05) as u64
+    }
+
+    fn scale(value: f32) -> f32 {
+        value * 1_000.125
+    }
+
+    #[allow(substrace::floating_point)]
+    pub fn legacy(_origin: u64, amount: u64) -> Result<(), ()> {
+        let _fee = amount as f64 * 0.001;
+        Ok(())
+    }
+
+    // Not reachable from an entry point.
+    fn report(amount: u64) -> f64 {
+        amount as f64 / 2.0
+    }
+}
+
+impl<T> Hooks<u32> for Pallet<T> {
+    fn on_initialize(_n: u32) -> u64 {
+        let decay = 0.0125;
+        (Deposit::get() as f64 * decay) as u64
+    }
+}
+
+frame_support::pallet_calls! {
+    impl<T> Pallet<T> {
+        type Origin = u64;
+
+        fn transfer(amount: u64);
+        fn set_rate(rate: FixedU128);
+        fn legacy(amount: u64);
+    }
+}
+
+fn main() {}
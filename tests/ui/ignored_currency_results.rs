@@ -0,0 +1,56 @@
+#![allow(substrace::panics)]
+#![allow(substrace::enable_singlepass_benchmarks)]
+#![allow(dead_code, unused_must_use, clippy::let_unit_value)]
+// aux-build:frame-support.rs
+
+extern crate frame_support;
+
+use frame_support::traits::fungible::{Inspect, Mutate, MutateHold};
+use frame_support::traits::{Currency, ReservableCurrency};
+
+pub trait Config {
+    type Currency: ReservableCurrency<u64, Balance = u64>;
+    type Fungible: Mutate<u64, Balance = u64> + MutateHold<u64, Balance = u64>;
+}
+
+pub struct Pallet<T>(core::marker::PhantomData<T>);
+
+impl<T: Config> Pallet<T> {
+    fn ignored(who: u64, dest: u64, amount: u64) {
+        let _ = T::Currency::transfer(&who, &dest, amount);
+        T::Currency::reserve(&who, amount).ok();
+        T::Currency::unreserve(&who, amount);
+        let _remaining = T::Currency::unreserve(&who, amount);
+        T::Currency::slash(&who, amount);
+        let (_imbalance, _) = T::Currency::slash(&who, amount);
+        drop(T::Currency::deposit_creating(&who, amount));
+        let _ = T::Fungible::mint_into(&who, amount);
+        let _ = <T::Fungible as Mutate<u64>>::transfer(&who, &dest, amount);
+        T::Fungible::hold(&who, amount);
+        let _ = T::Fungible::release(&who, amount, false).ok();
+    }
+
+    fn checked(who: u64, dest: u64, amount: u64) -> Result<u64, ()> {
+        T::Currency::transfer(&who, &dest, amount)?;
+        T::Currency::reserve(&who, amount)?;
+        let remaining = T::Currency::unreserve(&who, amount);
+        if remaining > 0 {
+            return Err(());
+        }
+        let (imbalance, remaining) = T::Currency::slash(&who, amount);
+        Self::resolve(imbalance);
+        T::Fungible::hold(&who, amount)?;
+        // Reading a balance moves no funds.
+        let _ = T::Currency::free_balance(&who);
+        Ok(remaining)
+    }
+
+    #[allow(substrace::ignored_currency_results)]
+    fn allowed(who: u64, amount: u64) {
+        let _ = T::Currency::reserve(&who, amount);
+    }
+
+    fn resolve(_imbalance: <T::Currency as Currency<u64>>::NegativeImbalance) {}
+}
+
+fn main() {}
@@ -0,0 +1,103 @@
+#![allow(substrace::panics)]
+#![allow(dead_code)]
+// aux-build:frame-support.rs
+// aux-build:frame-system.rs
+// aux-build:sp-io.rs
+// aux-build:sp-runtime.rs
+// aux-build:pallet-insecure-randomness-collective-flip.rs
+
+extern crate frame_support;
+extern crate frame_system;
+extern crate pallet_insecure_randomness_collective_flip;
+extern crate sp_io;
+extern crate sp_runtime;
+
+use frame_support::traits::{Randomness, UnixTime};
+use sp_io::hashing::{blake2_256, twox_128};
+use sp_runtime::traits::{BlakeTwo256, Hash};
+
+mod lottery {
+    use frame_support::traits::{Randomness, UnixTime};
+
+    pub trait Config {
+        type Randomness: Randomness<[u8; 32], u32>;
+        type UnixTime: UnixTime;
+    }
+}
+
+pub struct Runtime;
+
+pub type RandomnessCollectiveFlip = pallet_insecure_randomness_collective_flip::Pallet<Runtime>;
+
+pub struct Timestamp;
+
+impl UnixTime for Timestamp {
+    fn now() -> core::time::Duration {
+        core::time::Duration::ZERO
+    }
+}
+
+impl lottery::Config for Runtime {
+    type Randomness = RandomnessCollectiveFlip;
+    type UnixTime = Timestamp;
+}
+
+pub struct BabeRandomness;
+
+impl Randomness<[u8; 32], u32> for BabeRandomness {
+    fn random(_subject: &[u8]) -> ([u8; 32], u32) {
+        ([0; 32], 0)
+    }
+}
+
+pub struct SecureRuntime;
+
+impl lottery::Config for SecureRuntime {
+    type Randomness = BabeRandomness;
+    type UnixTime = Timestamp;
+}
+
+fn encode<S>(_value: &S) -> Vec<u8> {
+    Vec::new()
+}
+
+fn draw_winner() -> [u8; 32] {
+    RandomnessCollectiveFlip::random(b"lottery").0
+}
+
+fn seed() -> [u8; 32] {
+    let _material = pallet_insecure_randomness_collective_flip::Pallet::<Runtime>::random_material();
+    <RandomnessCollectiveFlip as Randomness<_, _>>::random_seed().0
+}
+
+fn draw<T: lottery::Config>() -> [u8; 32] {
+    T::Randomness::random(b"lottery").0
+}
+
+fn mint_rarity<T: lottery::Config>(who: u64) -> [u8; 32] {
+    let seed = (
+        who,
+        frame_system::Pallet::<T>::block_number(),
+        frame_system::Pallet::<T>::parent_hash(),
+    );
+    blake2_256(&encode(&seed))
+}
+
+fn shuffle<T: lottery::Config>() -> [u8; 32] {
+    BlakeTwo256::hash_of(&T::UnixTime::now().as_secs().to_le_bytes())
+}
+
+fn pick<T: lottery::Config>() -> [u8; 16] {
+    twox_128(&blake2_256(&encode(&frame_system::Pallet::<T>::extrinsic_index())))
+}
+
+fn static_key() -> [u8; 32] {
+    blake2_256(b"lottery")
+}
+
+#[allow(substrace::insecure_randomness)]
+fn ticket_id<T: lottery::Config>(who: u64) -> [u8; 32] {
+    blake2_256(&encode(&(who, frame_system::Pallet::<T>::block_number())))
+}
+
+fn main() {}
@@ -0,0 +1,34 @@
+#![allow(substrace::security)]
+// aux-build:frame-support.rs
+// compile-flags: -A substrace::weights
+
+extern crate frame_support;
+
+use frame_support::{
+    storage::types::map::StorageMap,
+    hash::Twox64Concat,
+};
+
+pub struct _GeneratedPrefixForStorageBalances;
+
+// Allowed with `substrace::security`.
+pub type Balances = StorageMap<_GeneratedPrefixForStorageBalances, Twox64Concat, u32, u64>;
+
+// Allowed with `-A substrace::weights`.
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking {}
+
+fn insert_while_iterating() {
+    for (who, balance) in Balances::iter() {
+        Balances::insert(who, balance + 1);
+    }
+}
+
+#[allow(substrace::storage)]
+fn insert_while_iterating_allowed() {
+    for (who, balance) in Balances::iter() {
+        Balances::insert(who, balance + 1);
+    }
+}
+
+fn main() {}
@@ -0,0 +1,128 @@
+#![allow(substrace::panics)]
+#![allow(substrace::enable_singlepass_benchmarks, substrace::missing_transactional, substrace::unchecked_origin)]
+#![allow(substrace::unbounded_extrinsic_params)]
+#![allow(dead_code, non_camel_case_types, clippy::unnecessary_cast)]
+// aux-build:frame-support.rs
+// aux-build:sp-arithmetic.rs
+
+extern crate frame_support;
+extern crate sp_arithmetic;
+
+use frame_support::traits::{Get, Hooks};
+use frame_support::weights::RuntimeDbWeight;
+use sp_arithmetic::traits::{AtLeast32BitUnsigned, SaturatedConversion, UniqueSaturatedInto};
+
+pub trait Config {
+    type Balance: AtLeast32BitUnsigned;
+    type DbWeight: Get<RuntimeDbWeight>;
+}
+
+pub type Balance = u128;
+pub type BalanceOf<T> = <T as Config>::Balance;
+pub type Weight = u64;
+
+pub enum Error<T> {
+    InsufficientBalance,
+    BalanceOverflow,
+    __Ignore(core::marker::PhantomData<T>),
+}
+
+pub struct Pallet<T>(core::marker::PhantomData<T>);
+
+impl<T: Config> Pallet<T> {
+    pub fn transfer(_origin: u32, amount: Balance, balance: BalanceOf<T>) -> Result<(), Error<T>> {
+        let _amount = amount as u64;
+        let _index: u32 = balance.unique_saturated_into();
+        let _fee = amount.saturated_into::<u32>();
+        let _balance = T::Balance::saturated_from(amount);
+        let _signed = amount as i128;
+        Ok(())
+    }
+
+    pub fn widen(_origin: u32, count: u32, balance: T::Balance) -> Result<(), Error<T>> {
+        let _count = count as Balance;
+        let _same = count as u32;
+        let _balance: u128 = balance.saturated_into();
+        let _from_count: T::Balance = count.saturated_into();
+        Ok(())
+    }
+
+    pub fn not_a_balance(_origin: u32, index: u64, delta: i64) -> Result<(), Error<T>> {
+        let _index = index as u32;
+        let _delta = delta as u64;
+        Ok(())
+    }
+
+    pub fn in_closure(_origin: u32, amounts: Vec<T::Balance>) -> Result<(), Error<T>> {
+        let _amounts: Vec<u64> = amounts.into_iter().map(|amount| amount.saturated_into::<u64>()).collect();
+        Ok(())
+    }
+
+    pub fn refund(_origin: u32, used: frame_support::weights::Weight) -> Result<(), Error<T>> {
+        let _ref_time = used.ref_time() as u32;
+        let _proof_size = used.proof_size() as u32;
+        let _read = T::DbWeight::get().read as u32;
+        let _writes = (used.ref_time() / T::DbWeight::get().write) as u16;
+        Ok(())
+    }
+
+    #[allow(substrace::lossy_conversions)]
+    pub fn allowed(_origin: u32, amount: Balance) -> Result<(), Error<T>> {
+        let _amount = amount as u64;
+        Ok(())
+    }
+
+    fn unreachable(amount: Balance) -> u64 {
+        amount as u64
+    }
+}
+
+impl<T: Config> Hooks<u64> for Pallet<T> {
+    fn on_initialize(n: u64) -> u64 {
+        let _slot = n as u8;
+        let weight: Weight = 10;
+        let _ref_time = weight as i64;
+        0
+    }
+}
+
+frame_support::pallet_calls! {
+    impl<T: Config> Pallet<T> {
+        type Origin = u32;
+
+        fn transfer(amount: Balance, balance: BalanceOf<T>);
+        fn widen(count: u32, balance: T::Balance);
+        fn not_a_balance(index: u64, delta: i64);
+        fn in_closure(amounts: Vec<T::Balance>);
+        fn refund(used: frame_support::weights::Weight);
+        fn allowed(amount: Balance);
+    }
+}
+
+mod other {
+    use super::{Balance, Config};
+
+    pub enum Error<T> {
+        AmountOverflow,
+        __Ignore(core::marker::PhantomData<T>),
+    }
+
+    pub struct Pallet<T>(core::marker::PhantomData<T>);
+
+    impl<T: Config> Pallet<T> {
+        pub fn burn(_origin: u32, amount: Balance) -> Result<(), Error<T>> {
+            let _amount = amount as u32;
+            Ok(())
+        }
+    }
+
+    frame_support::pallet_calls! {
+        impl<T: Config> Pallet<T> {
+            type Origin = u32;
+
+            fn burn(amount: Balance);
+        }
+    }
+}
+
+fn main() {}
@@ -1,21 +1,61 @@
-#![allow(panics)]
-#![allow(enable_singlepass_benchmarks)]
+#![allow(substrace::panics)]
+#![allow(substrace::enable_singlepass_benchmarks)]
+#![allow(dead_code)]
 // aux-build:frame-support.rs
 
 extern crate frame_support;
 
 use frame_support::{
-    storage::{types::map::StorageMap as Bar},
-    hash::Twox64Concat,
+    storage::types::{map::StorageMap as Bar, Key, StorageDoubleMap, StorageNMap},
+    hash::{Blake2_128, Blake2_128Concat, Twox128, Twox64Concat},
     Identity,
 };
 
 /// # Security
 ///
 /// Twox64Concat is allowed because this is a test
-pub type Foo<K, V> = Bar<Twox64Concat, K, V>;
+pub type Foo<K, V> = Bar<(), Twox64Concat, K, V>;
 
 
-pub type Foo2<K, V> = Bar<Twox64Concat, K, V>;
+pub type Foo2<K, V> = Bar<(), Twox64Concat, K, V>;
+
+// Checked where the hasher is written.
+pub type Foo3 = Foo2<u32, u64>;
+
+// Hashers are found through aliases.
+type FastHasher = Twox64Concat;
+pub type Aliased = Bar<(), FastHasher, u32, u64>;
+
+// Only the second key is insecure.
+pub type Allowances = StorageDoubleMap<(), Blake2_128Concat, u32, Identity, u64, u128>;
+
+pub type Approvals = StorageNMap<(), (Key<Blake2_128Concat, u32>, Key<Twox64Concat, u64>), bool>;
+
+pub struct AccountsPrefix;
+pub struct ProxiesPrefix;
+pub struct VotesPrefix;
+pub struct LocksPrefix;
+
+// The values can't be matched with their keys when iterating.
+pub type Accounts = Bar<AccountsPrefix, Blake2_128, u32, u64>;
+
+// Only the second key isn't given when iterating over a prefix.
+pub type Proxies = StorageDoubleMap<ProxiesPrefix, Blake2_128, u32, Blake2_128, u64, ()>;
+
+// The second key is read back when iterating over a prefix.
+pub type Votes = StorageDoubleMap<VotesPrefix, Blake2_128, u32, Blake2_128Concat, u64, ()>;
+
+// The first two keys are given, so only the last can't be matched.
+/// # Security
+///
+/// The block number is not chosen by users.
+pub type Locks = StorageNMap<LocksPrefix, (Key<Twox128, u32>, Key<Blake2_128, u64>, Key<Blake2_128, u8>), ()>;
+
+fn iterate() {
+    for _ in Accounts::iter_values() {}
+    for _ in Proxies::iter_prefix_values(0) {}
+    for _ in Votes::iter_prefix(0) {}
+    for _ in Locks::iter_prefix_values((0_u32, 0_u64)) {}
+}
 
 fn main() {}
@@ -0,0 +1,90 @@
+#![allow(substrace::panics)]
+#![allow(substrace::enable_singlepass_benchmarks, substrace::unchecked_origin)]
+#![allow(dead_code, non_camel_case_types)]
+// aux-build:frame-support.rs
+// rustc-env:SUBSTRACE_FRAME_VERSION=3.0.0
+
+// Pallets in several places of the crate, with the calls the `#[pallet]` macro generates for them.
+
+extern crate frame_support;
+
+use frame_support::storage::transactional::with_transaction;
+
+pub mod runtime {
+    pub mod pallets {
+        // A pallet nested a few modules deep.
+        pub mod balances {
+            use super::super::super::*;
+
+            pub struct Pallet<T>(core::marker::PhantomData<T>);
+
+            impl<T> Pallet<T> {
+                pub fn transfer(_origin: u32, _amount: u64) -> Result<(), ()> {
+                    Ok(())
+                }
+
+                pub fn force_transfer(_origin: u32, _amount: u64) -> Result<(), ()> {
+                    with_transaction(|| Ok(()))
+                }
+
+                // Not a call, so it doesn't need to be transactional.
+                pub fn transfer_all(_origin: u32) -> Result<(), ()> {
+                    Ok(())
+                }
+            }
+
+            frame_support::pallet_calls! {
+                impl<T> Pallet<T> {
+                    type Origin = u32;
+
+                    fn transfer(amount: u64);
+                    fn force_transfer(amount: u64);
+                }
+            }
+        }
+    }
+}
+
+// A second pallet in the same crate, with a call of the same name as a helper of the first pallet.
+pub mod vesting {
+    use super::*;
+
+    pub struct Pallet<T>(core::marker::PhantomData<T>);
+
+    impl<T> Pallet<T> {
+        pub fn transfer_all(_origin: u32) -> Result<(), ()> {
+            Ok(())
+        }
+    }
+
+    frame_support::pallet_calls! {
+        impl<T> Pallet<T> {
+            type Origin = u32;
+
+            fn transfer_all();
+        }
+    }
+}
+
+// An instantiable pallet.
+pub mod collective {
+    use super::*;
+
+    pub struct Pallet<T, I = ()>(core::marker::PhantomData<(T, I)>);
+
+    impl<T, I> Pallet<T, I> {
+        pub fn vote(_origin: u32, _aye: bool) -> Result<(), ()> {
+            Ok(())
+        }
+    }
+
+    frame_support::pallet_calls! {
+        impl<T, I> Pallet<T, I> {
+            type Origin = u32;
+
+            fn vote(aye: bool);
+        }
+    }
+}
+
+fn main() {}
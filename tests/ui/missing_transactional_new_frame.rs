@@ -0,0 +1,51 @@
+#![allow(substrace::panics)]
+#![allow(substrace::enable_singlepass_benchmarks, substrace::unchecked_origin)]
+#![allow(dead_code, non_camel_case_types)]
+// aux-build:frame-support.rs
+// aux-build:frame-support-procedural.rs
+// rustc-env:SUBSTRACE_FRAME_VERSION=polkadot-v0.9.30
+
+// Every extrinsic is transactional on this FRAME version, so explicit transactions around the
+// whole extrinsic are not needed.
+
+extern crate frame_support;
+extern crate frame_support_procedural;
+
+use frame_support::storage::transactional::with_transaction;
+use frame_support_procedural::transactional;
+
+pub struct Pallet<T>(core::marker::PhantomData<T>);
+
+impl<T> Pallet<T> {
+    pub fn transfer(_origin: u32, _amount: u64) -> Result<(), ()> {
+        Ok(())
+    }
+
+    #[transactional]
+    pub fn force_transfer(_origin: u32, _amount: u64) -> Result<(), ()> {
+        Ok(())
+    }
+
+    pub fn transfer_all(_origin: u32) -> Result<(), ()> {
+        with_transaction(|| Ok(()))
+    }
+
+    pub fn transfer_keep_alive(_origin: u32, amount: u64) -> Result<(), ()> {
+        // Rolling back part of the extrinsic still needs its own transaction.
+        let _ = with_transaction(|| if amount > 0 { Ok(()) } else { Err(()) });
+        Ok(())
+    }
+}
+
+frame_support::pallet_calls! {
+    impl<T> Pallet<T> {
+        type Origin = u32;
+
+        fn transfer(amount: u64);
+        fn force_transfer(amount: u64);
+        fn transfer_all();
+        fn transfer_keep_alive(amount: u64);
+    }
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+#![deny(clippy::unwrap_used, clippy::panic, clippy::disallowed_methods)]
+#![allow(substrace::enable_singlepass_benchmarks)]
+// aux-build:frame-support.rs
+
+extern crate frame_support;
+
+// Only the attributes of the crate count.
+#[warn(clippy::todo)]
+fn main() {}
@@ -0,0 +1,9 @@
+#![warn(clippy::todo)]
+#![allow(substrace::enable_singlepass_benchmarks)]
+// aux-build:frame-support.rs
+// rustc-env:CARGO_MANIFEST_DIR=tests/ui/no_panics/workspace/pallet
+// rustc-env:CLIPPY_CONF_DIR=tests/ui/no_panics
+
+extern crate frame_support;
+
+fn main() {}
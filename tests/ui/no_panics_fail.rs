@@ -1,3 +1,6 @@
-#![allow(enable_singlepass_benchmarks)]
+#![allow(substrace::enable_singlepass_benchmarks)]
+// aux-build:frame-support.rs
 
-fn main() {}
\ No newline at end of file
+extern crate frame_support;
+
+fn main() {}
@@ -0,0 +1,9 @@
+#![allow(substrace::enable_singlepass_benchmarks)]
+// aux-build:frame-support.rs
+// compile-flags: -D clippy::unwrap_used -A clippy::panic
+// rustc-env:CARGO_MANIFEST_DIR=tests/ui/no_panics/rustflags
+// rustc-env:SUBSTRACE_CONF_DIR=tests/ui/no_panics/rustflags
+
+extern crate frame_support;
+
+fn main() {}
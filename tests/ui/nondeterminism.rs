@@ -0,0 +1,69 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+// The sysroot ships `rand`, `once_cell` and `lazy_static` for the compiler, which makes mocks with
+// the same names ambiguous.
+#![feature(rustc_private)]
+#![allow(substrace::panics)]
+#![allow(dead_code)]
+// compile-flags: --cfg feature="std"
+
+#[macro_use]
+extern crate lazy_static;
+extern crate once_cell;
+extern crate rand;
+
+use once_cell::sync::Lazy;
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::{Instant, SystemTime};
+
+static mut NONCE: u64 = 0;
+static FEE: u64 = 10;
+static CACHE: Lazy<Vec<u32>> = Lazy::new(Vec::new);
+
+thread_local! {
+    static CALLS: Cell<u32> = Cell::new(0);
+}
+
+lazy_static! {
+    static ref LIMITS: Vec<u32> = Vec::new();
+}
+
+fn tally(votes: &HashMap<u64, u32>, voters: HashSet<u64>, ordered: &BTreeMap<u64, u32>) -> u32 {
+    let mut total: u32 = votes.values().sum();
+    for who in &voters {
+        total += *who as u32;
+    }
+    for (_, count) in ordered {
+        total += count;
+    }
+    total + votes.get(&0).copied().unwrap_or_default() + voters.len() as u32
+}
+
+fn elapsed() -> u64 {
+    let start = Instant::now();
+    let _since = SystemTime::now();
+    start.elapsed().as_secs()
+}
+
+fn pick() -> u64 {
+    rand::random::<u64>()
+}
+
+fn next_nonce() -> u64 {
+    unsafe {
+        NONCE += 1;
+        NONCE
+    }
+}
+
+fn globals() -> usize {
+    CALLS.with(|calls| calls.set(calls.get() + 1));
+    CACHE.len() + LIMITS.len() + FEE as usize
+}
+
+#[allow(substrace::nondeterminism)]
+fn offchain_elapsed() -> Instant {
+    Instant::now()
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+#![allow(substrace::panics)]
+#![allow(dead_code)]
+// Only crates compiled for the runtime are checked, this one is always compiled with `std`.
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+static mut NONCE: u64 = 0;
+
+fn tally(votes: &HashMap<u64, u32>) -> u32 {
+    let mut total = 0;
+    for count in votes.values() {
+        total += count;
+    }
+    total
+}
+
+fn elapsed() -> u64 {
+    Instant::now().elapsed().as_secs() + unsafe { NONCE }
+}
+
+fn main() {}
@@ -0,0 +1,90 @@
+#![allow(substrace::panics)]
+#![allow(substrace::enable_singlepass_benchmarks, substrace::missing_transactional, substrace::unchecked_origin)]
+#![allow(substrace::unbounded_extrinsic_params)]
+#![allow(dead_code, non_camel_case_types, non_snake_case)]
+// aux-build:frame-support.rs
+
+extern crate frame_support;
+
+use frame_support::traits::Hooks;
+
+pub struct Pallet<T>(core::marker::PhantomData<T>);
+
+impl<T> Pallet<T> {
+    pub fn transfer(_origin: u32, amounts: Vec<u64>, index: usize) -> Result<(), ()> {
+        let amount = amounts[index];
+        Self::checked_amount(amount)?;
+        Ok(())
+    }
+
+    pub fn set_total(_origin: u32, total: Option<u64>) -> Result<(), ()> {
+        let total = total.expect("total is set");
+        assert!(total > 0);
+        Ok(())
+    }
+
+    #[allow(substrace::reachable_panics)]
+    pub fn force_transfer(_origin: u32, amount: Option<u64>) -> Result<(), ()> {
+        amount.unwrap();
+        Ok(())
+    }
+
+    fn checked_amount(amount: u64) -> Result<u64, ()> {
+        let amounts = [amount; 4];
+        let _ = amounts[0];
+        helpers::checked(amount)
+    }
+
+    // Not reachable from any entry point.
+    pub fn unused(amount: Option<u64>) -> u64 {
+        amount.unwrap()
+    }
+}
+
+mod helpers {
+    pub fn checked(amount: u64) -> Result<u64, ()> {
+        if amount == u64::MAX {
+            unreachable!("checked before");
+        }
+        Some(amount).ok_or(())
+    }
+}
+
+impl<T> Hooks<u32> for Pallet<T> {
+    fn on_initialize(n: u32) -> u64 {
+        let weights: Vec<u64> = Vec::new();
+        weights.iter().map(|_| Option::<u64>::None.unwrap()).sum::<u64>() + u64::from(n)
+    }
+
+    // Panics in offchain workers don't affect block production.
+    fn offchain_worker(_n: u32) {
+        panic!("offchain");
+    }
+}
+
+frame_support::pallet_calls! {
+    impl<T> Pallet<T> {
+        type Origin = u32;
+
+        fn transfer(amounts: Vec<u64>, index: usize);
+        fn set_total(total: Option<u64>);
+        fn force_transfer(amount: Option<u64>);
+    }
+}
+
+// What `decl_runtime_apis!` and `impl_runtime_apis!` generate for a runtime API.
+pub mod runtime_decl_for_BalancesApi {
+    pub trait BalancesApi {
+        fn free_balance(balances: &[u64], who: usize) -> u64;
+    }
+}
+
+pub struct Runtime;
+
+impl runtime_decl_for_BalancesApi::BalancesApi for Runtime {
+    fn free_balance(balances: &[u64], who: usize) -> u64 {
+        balances.get(who).copied().unwrap_or_default() + balances[0]
+    }
+}
+
+fn main() {}
@@ -0,0 +1,70 @@
+#![allow(substrace::panics)]
+#![allow(substrace::enable_singlepass_benchmarks)]
+// aux-build:frame-support.rs
+
+extern crate frame_support;
+
+use frame_support::{
+    storage::types::map::StorageMap,
+    hash::Twox64Concat,
+};
+
+pub struct _GeneratedPrefixForStorageBalances;
+pub struct _GeneratedPrefixForStorageLocks;
+
+/// # Security
+///
+/// Twox64Concat is allowed because this is a test
+pub type Balances = StorageMap<_GeneratedPrefixForStorageBalances, Twox64Concat, u32, u64>;
+/// # Security
+///
+/// Twox64Concat is allowed because this is a test
+pub type Locks = StorageMap<_GeneratedPrefixForStorageLocks, Twox64Concat, u32, u64>;
+
+fn insert_while_iterating() {
+    for (who, balance) in Balances::iter() {
+        Balances::insert(who, balance + 1);
+    }
+}
+
+fn remove_while_draining() {
+    let mut drain = Balances::drain().filter(|(_, balance)| *balance == 0);
+    let first = drain.next();
+    Balances::remove(0);
+    let _ = (first, drain.next());
+}
+
+fn mutate_in_closure() {
+    Balances::iter_keys().for_each(|who| {
+        Balances::mutate(who, |balance| *balance = None);
+    });
+}
+
+fn take_while_iterating_values() {
+    let mut values = Balances::iter_values();
+    while let Some(value) = values.next() {
+        let _ = Balances::take(value as u32);
+    }
+}
+
+fn collect_first() {
+    let who: Vec<u32> = Balances::iter_keys().collect();
+    for who in who {
+        Balances::remove(who);
+    }
+}
+
+fn other_storage_item() {
+    for (who, balance) in Balances::iter() {
+        Locks::insert(who, balance);
+    }
+}
+
+fn iterator_no_longer_used() {
+    let mut iter = Balances::iter();
+    let first = iter.next();
+    Balances::insert(0, 0);
+    let _ = first;
+}
+
+fn main() {}
@@ -0,0 +1,102 @@
+#![allow(substrace::panics)]
+#![allow(substrace::enable_singlepass_benchmarks, substrace::missing_transactional, substrace::unchecked_origin)]
+#![allow(dead_code, non_camel_case_types)]
+// aux-build:frame-support.rs
+// rustc-env:SUBSTRACE_FRAME_VERSION=3.0.0
+
+// FRAME 3.0.0 doesn't run extrinsics in a storage transaction.
+
+#[macro_use]
+extern crate frame_support;
+
+use frame_support::storage::transactional::with_transaction;
+use frame_support::storage::types::{StorageMap, StorageValue};
+use frame_support::Blake2_128Concat;
+
+pub struct _GeneratedPrefixForStorageBalances;
+pub struct _GeneratedPrefixForStorageTotal;
+
+pub type Balances = StorageMap<_GeneratedPrefixForStorageBalances, Blake2_128Concat, u32, u64>;
+pub type Total = StorageValue<_GeneratedPrefixForStorageTotal, u64>;
+
+pub struct Pallet<T>(core::marker::PhantomData<T>);
+
+impl<T> Pallet<T> {
+    pub fn transfer(_origin: u32, to: u32, amount: u64) -> Result<(), ()> {
+        Balances::insert(to, amount);
+        ensure!(amount > 0, ());
+        Ok(())
+    }
+
+    pub fn mint(_origin: u32, to: u32, amount: u64) -> Result<(), ()> {
+        Self::credit(to, amount);
+        Self::check_total(amount)?;
+        Ok(())
+    }
+
+    pub fn burn(_origin: u32, from: u32, amount: u64) -> Result<(), ()> {
+        let balance = Balances::get(from).ok_or(())?;
+        if balance < amount {
+            return Err(());
+        }
+        Balances::insert(from, balance - amount);
+        Ok(())
+    }
+
+    pub fn reset(_origin: u32, amount: u64) -> Result<(), ()> {
+        Total::put(amount);
+        if amount == 0 {
+            Err(())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn add(_origin: u32, to: u32, amount: u64) -> Result<(), ()> {
+        Balances::try_mutate(to, |balance| -> Result<(), ()> {
+            *balance = Some(balance.unwrap_or(0).checked_add(amount).ok_or(())?);
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    pub fn force_transfer(_origin: u32, to: u32, amount: u64) -> Result<(), ()> {
+        with_transaction(|| {
+            Balances::insert(to, amount);
+            ensure!(amount > 0, ());
+            Ok(())
+        })
+    }
+
+    #[allow(substrace::storage_write_before_check)]
+    pub fn set_total(_origin: u32, amount: u64) -> Result<(), ()> {
+        Total::put(amount);
+        ensure!(amount > 0, ());
+        Ok(())
+    }
+
+    fn credit(to: u32, amount: u64) {
+        Balances::insert(to, amount);
+    }
+
+    fn check_total(amount: u64) -> Result<(), ()> {
+        ensure!(amount < 1_000, ());
+        Ok(())
+    }
+}
+
+frame_support::pallet_calls! {
+    impl<T> Pallet<T> {
+        type Origin = u32;
+
+        fn transfer(to: u32, amount: u64);
+        fn mint(to: u32, amount: u64);
+        fn burn(from: u32, amount: u64);
+        fn reset(amount: u64);
+        fn add(to: u32, amount: u64);
+        fn force_transfer(to: u32, amount: u64);
+        fn set_total(amount: u64);
+    }
+}
+
+fn main() {}
@@ -0,0 +1,68 @@
+#![allow(substrace::panics)]
+#![allow(substrace::enable_singlepass_benchmarks, substrace::missing_transactional, substrace::unchecked_origin)]
+#![allow(dead_code, non_camel_case_types)]
+// aux-build:frame-support.rs
+// rustc-env:SUBSTRACE_FRAME_VERSION=polkadot-v0.9.30
+
+// Since `polkadot-v0.9.25` every extrinsic runs in a storage layer, but hooks don't.
+
+#[macro_use]
+extern crate frame_support;
+
+use frame_support::storage::types::{StorageMap, StorageValue};
+use frame_support::storage::with_storage_layer;
+use frame_support::traits::Hooks;
+use frame_support::Blake2_128Concat;
+
+pub struct _GeneratedPrefixForStorageBalances;
+pub struct _GeneratedPrefixForStorageVersion;
+
+pub type Balances = StorageMap<_GeneratedPrefixForStorageBalances, Blake2_128Concat, u32, u64>;
+pub type Version = StorageValue<_GeneratedPrefixForStorageVersion, u32>;
+
+pub struct Pallet<T>(core::marker::PhantomData<T>);
+
+impl<T> Pallet<T> {
+    // Rolled back by the storage layer of the extrinsic.
+    pub fn transfer(_origin: u32, to: u32, amount: u64) -> Result<(), ()> {
+        Balances::insert(to, amount);
+        ensure!(amount > 0, ());
+        Ok(())
+    }
+
+    fn migrate() -> Result<(), ()> {
+        Version::put(2);
+        with_storage_layer(|| {
+            Balances::insert(0, 0);
+            Err(())
+        })?;
+        Ok(())
+    }
+
+    fn migrate_in_layer() -> Result<(), ()> {
+        with_storage_layer(|| {
+            Balances::insert(0, 0);
+            Version::put(2);
+            ensure!(Version::get().is_some(), ());
+            Ok(())
+        })
+    }
+}
+
+impl<T> Hooks<u64> for Pallet<T> {
+    fn on_initialize(_n: u64) -> u64 {
+        let _ = Self::migrate();
+        let _ = Self::migrate_in_layer();
+        0
+    }
+}
+
+frame_support::pallet_calls! {
+    impl<T> Pallet<T> {
+        type Origin = u32;
+
+        fn transfer(to: u32, amount: u64);
+    }
+}
+
+fn main() {}
@@ -0,0 +1,98 @@
+#![allow(substrace::panics)]
+#![allow(substrace::enable_singlepass_benchmarks, substrace::missing_transactional, substrace::unchecked_origin)]
+#![allow(dead_code, non_camel_case_types, unused_variables)]
+// aux-build:frame-support.rs
+
+extern crate frame_support;
+
+use frame_support::traits::Get;
+use frame_support::BoundedVec;
+use std::collections::BTreeMap;
+
+type Origin = u64;
+
+pub trait Config {
+    type AccountId;
+    type MaxMembers: Get<u32>;
+    type MaxRemarkLen: Get<u32>;
+    type MaxProposals: Get<u64>;
+    type MaxAuthorities: Get<u32>;
+}
+
+pub struct Metadata {
+    pub name: BoundedVec<u8, ()>,
+    pub description: Vec<u8>,
+}
+
+pub struct Pallet<T>(core::marker::PhantomData<T>);
+
+impl<T: Config> Pallet<T> {
+    pub fn set_members(origin: Origin, members: Vec<T::AccountId>) -> Result<(), ()> {
+        Ok(())
+    }
+
+    pub fn remark(origin: Origin, remark: Vec<u8>) -> Result<(), ()> {
+        Ok(())
+    }
+
+    pub fn set_name(origin: Origin, name: String) -> Result<(), ()> {
+        Ok(())
+    }
+
+    pub fn invite(origin: Origin, new_members: Vec<T::AccountId>) -> Result<(), ()> {
+        Ok(())
+    }
+
+    // `MaxAuthorities` only starts with the name.
+    pub fn set_code(origin: Origin, a: Vec<u8>) -> Result<(), ()> {
+        Ok(())
+    }
+
+    pub fn set_metadata(origin: Origin, metadata: Metadata) -> Result<(), ()> {
+        Ok(())
+    }
+
+    pub fn add_members(origin: Origin, members: Option<Vec<T::AccountId>>) -> Result<(), ()> {
+        Ok(())
+    }
+
+    pub fn set_prices(origin: Origin, prices: BTreeMap<u32, u64>) -> Result<(), ()> {
+        Ok(())
+    }
+
+    pub fn propose(origin: Origin, proposals: BoundedVec<u32, T::MaxProposals>) -> Result<(), ()> {
+        Ok(())
+    }
+
+    pub fn transfer(origin: Origin, dest: T::AccountId, amount: u64) -> Result<(), ()> {
+        Ok(())
+    }
+
+    #[allow(substrace::unbounded_extrinsic_params)]
+    pub fn force_remark(origin: Origin, remark: Vec<u8>) -> Result<(), ()> {
+        Ok(())
+    }
+
+    // Not an extrinsic.
+    fn note(remark: Vec<u8>) {}
+}
+
+frame_support::pallet_calls! {
+    impl<T: Config> Pallet<T> {
+        type Origin = Origin;
+
+        fn set_members(members: Vec<T::AccountId>);
+        fn remark(remark: Vec<u8>);
+        fn set_name(name: String);
+        fn invite(new_members: Vec<T::AccountId>);
+        fn set_code(a: Vec<u8>);
+        fn set_metadata(metadata: Metadata);
+        fn add_members(members: Option<Vec<T::AccountId>>);
+        fn set_prices(prices: BTreeMap<u32, u64>);
+        fn propose(proposals: BoundedVec<u32, T::MaxProposals>);
+        fn transfer(dest: T::AccountId, amount: u64);
+        fn force_remark(remark: Vec<u8>);
+    }
+}
+
+fn main() {}
@@ -0,0 +1,99 @@
+#![feature(register_tool)]
+#![register_tool(pallet)]
+#![allow(substrace::panics)]
+#![allow(substrace::enable_singlepass_benchmarks)]
+#![allow(dead_code)]
+// aux-build:frame-support.rs
+
+extern crate frame_support;
+
+use frame_support::traits::{ConstU32, Get, MaxEncodedLen};
+use frame_support::storage::types::{StorageMap, StorageValue};
+use frame_support::{Blake2_128Concat, BoundedVec};
+use std::collections::BTreeMap;
+
+pub trait Config {
+    type AccountId: MaxEncodedLen;
+    type Delegate;
+    type MaxMembers: Get<u32>;
+}
+
+pub struct Proposal {
+    pub proposer: u64,
+    pub remark: Vec<u8>,
+}
+
+// Bounded, but without a `MaxEncodedLen` implementation.
+pub struct Metadata {
+    pub version: u32,
+}
+
+pub struct _GeneratedPrefixForStorageMembers;
+pub struct _GeneratedPrefixForStorageBoundedMembers;
+pub struct _GeneratedPrefixForStorageProposals;
+pub struct _GeneratedPrefixForStoragePrices;
+pub struct _GeneratedPrefixForStorageNames;
+pub struct _GeneratedPrefixForStorageRemarks;
+pub struct _GeneratedPrefixForStorageBalances;
+pub struct _GeneratedPrefixForStorageHistory;
+pub struct _GeneratedPrefixForStorageCurrentMetadata;
+pub struct _GeneratedPrefixForStorageOwner;
+pub struct _GeneratedPrefixForStorageOwners;
+pub struct _GeneratedPrefixForStorageDelegates;
+pub struct _GeneratedPrefixForStorageGeneric;
+
+#[pallet::without_storage_info]
+pub struct Pallet<T>(core::marker::PhantomData<T>);
+
+/// # Unbounded
+/// There is one pallet per runtime, and its storage is checked below.
+#[pallet::without_storage_info]
+pub struct OtherPallet<T>(core::marker::PhantomData<T>);
+
+#[pallet::unbounded]
+pub type Members<T> = StorageValue<_GeneratedPrefixForStorageMembers, Vec<<T as Config>::AccountId>>;
+
+pub type BoundedMembers<T> = StorageValue<
+    _GeneratedPrefixForStorageBoundedMembers,
+    BoundedVec<<T as Config>::AccountId, <T as Config>::MaxMembers>,
+>;
+
+pub type Proposals = StorageMap<_GeneratedPrefixForStorageProposals, Blake2_128Concat, u32, Proposal>;
+
+pub type Prices = StorageValue<_GeneratedPrefixForStoragePrices, BTreeMap<u32, u64>>;
+
+pub type Names = StorageMap<_GeneratedPrefixForStorageNames, Blake2_128Concat, String, u32>;
+
+pub type Remarks = StorageValue<_GeneratedPrefixForStorageRemarks, BoundedVec<u8, ConstU32<{ u32::MAX }>>>;
+
+pub type CurrentMetadata = StorageValue<_GeneratedPrefixForStorageCurrentMetadata, Metadata>;
+
+pub type Balances = StorageMap<_GeneratedPrefixForStorageBalances, Blake2_128Concat, u32, u64>;
+
+pub type Owner<T> = StorageValue<_GeneratedPrefixForStorageOwner, <T as Config>::AccountId>;
+
+#[allow(type_alias_bounds)]
+pub type Owners<T: Config> = StorageMap<_GeneratedPrefixForStorageOwners, Blake2_128Concat, u32, <T as Config>::AccountId>;
+
+#[allow(type_alias_bounds)]
+pub type Delegates<T: Config> = StorageMap<_GeneratedPrefixForStorageDelegates, Blake2_128Concat, u32, <T as Config>::Delegate>;
+
+// Only the aliases that use it know what `K` and `V` are.
+pub type GenericMap<K, V> = StorageMap<_GeneratedPrefixForStorageGeneric, Blake2_128Concat, K, V>;
+
+/// # Unbounded
+/// Entries are removed after a day, and at most one entry is added per block.
+#[pallet::unbounded]
+pub type History = StorageMap<_GeneratedPrefixForStorageHistory, Blake2_128Concat, u32, Vec<u64>>;
+
+mod other {
+    use super::*;
+
+    // Reported on its own, unlike the `Members` of the outer pallet.
+    pub type Members = StorageValue<_GeneratedPrefixForStorageMembers, Vec<u64>>;
+
+    #[pallet::unbounded]
+    pub type Remarks = StorageValue<_GeneratedPrefixForStorageRemarks, Vec<u8>>;
+}
+
+fn main() {}
@@ -0,0 +1,111 @@
+#![allow(substrace::panics)]
+#![allow(substrace::enable_singlepass_benchmarks, substrace::missing_transactional, substrace::unchecked_origin)]
+#![allow(dead_code, non_camel_case_types)]
+#![warn(substrace::unbounded_storage_iteration_maintenance)]
+// aux-build:frame-support.rs
+// compile-flags: --cfg feature="runtime-benchmarks" --cfg test
+
+extern crate frame_support;
+
+use frame_support::storage::types::{StorageDoubleMap, StorageMap};
+use frame_support::traits::{Hooks, OnRuntimeUpgrade};
+use frame_support::Blake2_128Concat;
+
+pub struct _GeneratedPrefixForStorageDeposits;
+pub struct _GeneratedPrefixForStorageVotes;
+
+pub type Deposits = StorageMap<_GeneratedPrefixForStorageDeposits, Blake2_128Concat, u64, u64>;
+pub type Votes = StorageDoubleMap<_GeneratedPrefixForStorageVotes, Blake2_128Concat, u32, Blake2_128Concat, u64, bool>;
+
+pub struct Pallet<T>(core::marker::PhantomData<T>);
+
+impl<T> Pallet<T> {
+    pub fn refund_all(_origin: u64) -> Result<(), ()> {
+        for (who, deposit) in Deposits::iter() {
+            let _ = (who, deposit);
+        }
+        Ok(())
+    }
+
+    pub fn refund_some(_origin: u64) -> Result<(), ()> {
+        for (who, deposit) in Deposits::iter().filter(|(_, deposit)| *deposit > 0).take(10) {
+            let _ = (who, deposit);
+        }
+        let _first = Deposits::iter_keys().next();
+        Ok(())
+    }
+
+    pub fn close(_origin: u64, proposal: u32) -> Result<(), ()> {
+        Self::count_votes(proposal);
+        Ok(())
+    }
+
+    fn count_votes(proposal: u32) -> usize {
+        Votes::iter_prefix(proposal).filter(|(_, aye)| *aye).count()
+    }
+
+    #[allow(substrace::unbounded_storage_iteration)]
+    pub fn force_refund_all(_origin: u64) -> Result<(), ()> {
+        Deposits::drain().for_each(drop);
+        Ok(())
+    }
+
+    fn try_state() -> Result<(), ()> {
+        let total: u64 = Deposits::iter_values().sum();
+        if total > 0 { Ok(()) } else { Err(()) }
+    }
+
+    // Not reachable from an entry point.
+    fn total() -> u64 {
+        Deposits::iter_values().sum()
+    }
+}
+
+impl<T> Hooks<u32> for Pallet<T> {
+    fn on_initialize(_n: u32) -> u64 {
+        Deposits::iter_values().count() as u64
+    }
+
+    fn on_idle(_n: u32, remaining_weight: u64) -> u64 {
+        Deposits::iter_keys().take(remaining_weight as usize).count() as u64
+    }
+}
+
+pub struct MigrateDeposits;
+
+impl OnRuntimeUpgrade for MigrateDeposits {
+    fn on_runtime_upgrade() -> u64 {
+        Deposits::drain().count() as u64
+    }
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking {
+    use super::Deposits;
+
+    fn refund_all() {
+        let _ = Deposits::iter().count();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Deposits;
+
+    fn refunds_everyone() {
+        assert_eq!(Deposits::iter_keys().count(), 0);
+    }
+}
+
+frame_support::pallet_calls! {
+    impl<T> Pallet<T> {
+        type Origin = u64;
+
+        fn refund_all();
+        fn refund_some();
+        fn close(proposal: u32);
+        fn force_refund_all();
+    }
+}
+
+fn main() {}
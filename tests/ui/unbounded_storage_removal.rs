@@ -0,0 +1,59 @@
+#![allow(substrace::panics)]
+#![allow(substrace::unbounded_storage)]
+#![allow(dead_code)]
+// aux-build:frame-support.rs
+
+extern crate frame_support;
+
+use frame_support::storage::types::{StorageDoubleMap, StorageMap, StorageValue};
+use frame_support::storage::KillStorageResult;
+use frame_support::Blake2_128Concat;
+
+pub struct _GeneratedPrefixForStorageDeposits;
+pub struct _GeneratedPrefixForStorageVotes;
+pub struct _GeneratedPrefixForStorageCursor;
+
+pub type Deposits = StorageMap<_GeneratedPrefixForStorageDeposits, Blake2_128Concat, u64, u64>;
+pub type Votes = StorageDoubleMap<_GeneratedPrefixForStorageVotes, Blake2_128Concat, u32, Blake2_128Concat, u64, bool>;
+pub type Cursor = StorageValue<_GeneratedPrefixForStorageCursor, Vec<u8>>;
+
+const MAX_REMOVALS: u32 = 100;
+
+fn clear_all() {
+    let _ = Deposits::clear(u32::MAX, None);
+    Votes::remove_prefix(1, None);
+    let _ = Deposits::remove_all(Some(u32::MAX));
+}
+
+fn clear_some() {
+    Deposits::clear(MAX_REMOVALS, None);
+    let _ = Votes::clear_prefix(1, 100, None);
+    let removed = Deposits::clear(MAX_REMOVALS, None).unique;
+    let result = Votes::clear_prefix(2, MAX_REMOVALS, None);
+    let _ = result.backend;
+    Deposits::remove_all(Some(10));
+    let _ = (removed, result.loops);
+}
+
+fn clear_over_blocks(limit: u32) {
+    let cursor = Cursor::get();
+    let result = Deposits::clear(limit, cursor.as_deref());
+    if let Some(cursor) = result.maybe_cursor {
+        Cursor::put(cursor);
+    }
+
+    let next = Votes::clear_prefix(1, MAX_REMOVALS, None).maybe_cursor;
+    let _ = next;
+
+    match Votes::remove_prefix(1, Some(MAX_REMOVALS)) {
+        KillStorageResult::AllRemoved(_) => Cursor::kill(),
+        KillStorageResult::SomeRemaining(_) => {},
+    }
+}
+
+#[allow(substrace::unbounded_storage_removal)]
+fn reset() {
+    let _ = Deposits::clear(u32::MAX, None);
+}
+
+fn main() {}
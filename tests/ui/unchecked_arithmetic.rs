@@ -0,0 +1,86 @@
+#![allow(substrace::panics)]
+#![allow(substrace::enable_singlepass_benchmarks, substrace::missing_transactional, substrace::unchecked_origin)]
+#![allow(dead_code, non_camel_case_types)]
+// aux-build:frame-support.rs
+// aux-build:sp-arithmetic.rs
+// rustc-env:SUBSTRACE_CONF_DIR=tests/ui/unchecked_arithmetic
+
+extern crate frame_support;
+extern crate sp_arithmetic;
+
+use frame_support::traits::Hooks;
+use sp_arithmetic::traits::{AtLeast32Bit, AtLeast32BitUnsigned, Saturating};
+
+pub trait Config {
+    type Balance: AtLeast32BitUnsigned;
+    type BlockNumber: AtLeast32Bit;
+    // Allowed in `substrace.toml`.
+    type Moment: AtLeast32Bit;
+}
+
+pub struct Pallet<T>(core::marker::PhantomData<T>);
+
+impl<T: Config> Pallet<T> {
+    pub fn transfer(_origin: u32, balance: T::Balance, amount: T::Balance) -> Result<(), ()> {
+        let _total = balance + amount;
+        let mut remaining = balance;
+        remaining -= amount;
+        Ok(())
+    }
+
+    pub fn split(_origin: u32, balance: T::Balance, parts: T::Balance) -> Result<(), ()> {
+        let _share = balance / parts;
+        let _half = balance / 2u32.into();
+        let _third = balance / T::Balance::from(3);
+        let _rest = balance % 0u32.into();
+        Self::fee(balance);
+        Ok(())
+    }
+
+    pub fn saturating(_origin: u32, balance: T::Balance, amount: T::Balance) -> Result<(), ()> {
+        let _total = balance.saturating_add(amount);
+        // Primitive integers are left to `clippy::arithmetic_side_effects`.
+        let _count = 1u32 + 2;
+        Ok(())
+    }
+
+    pub fn set_time(_origin: u32, now: T::Moment, period: T::Moment) -> Result<(), ()> {
+        let _next = now + period;
+        Ok(())
+    }
+
+    #[allow(substrace::unchecked_arithmetic)]
+    pub fn allowed(_origin: u32, balance: T::Balance, amount: T::Balance) -> Result<(), ()> {
+        let _total = balance * amount;
+        Ok(())
+    }
+
+    fn fee(balance: T::Balance) -> T::Balance {
+        balance * balance
+    }
+
+    fn unreachable(balance: T::Balance) -> T::Balance {
+        balance * balance
+    }
+}
+
+impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+    fn on_initialize(n: T::BlockNumber) -> u64 {
+        let _next = n + 1u32.into();
+        0
+    }
+}
+
+frame_support::pallet_calls! {
+    impl<T: Config> Pallet<T> {
+        type Origin = u32;
+
+        fn transfer(balance: T::Balance, amount: T::Balance);
+        fn split(balance: T::Balance, parts: T::Balance);
+        fn saturating(balance: T::Balance, amount: T::Balance);
+        fn allowed(balance: T::Balance, amount: T::Balance);
+        fn set_time(now: T::Moment, period: T::Moment);
+    }
+}
+
+fn main() {}
@@ -0,0 +1,97 @@
+#![allow(substrace::panics)]
+#![allow(substrace::enable_singlepass_benchmarks, substrace::missing_transactional)]
+#![allow(dead_code, non_camel_case_types, unused_variables)]
+// aux-build:frame-support.rs
+// aux-build:frame-system.rs
+
+extern crate frame_support;
+extern crate frame_system;
+
+use frame_support::traits::EnsureOrigin;
+use frame_system::{ensure_none, ensure_root, ensure_signed, RawOrigin};
+
+type Origin = RawOrigin<u64>;
+
+pub trait Config {
+    type AdminOrigin: EnsureOrigin<Origin, Success = ()>;
+}
+
+pub struct Pallet<T>(core::marker::PhantomData<T>);
+
+impl<T: Config> Pallet<T> {
+    pub fn transfer(origin: Origin, amount: u64) -> Result<(), ()> {
+        let who = ensure_signed(origin)?;
+        Ok(())
+    }
+
+    pub fn set_admin(origin: Origin) -> Result<(), ()> {
+        T::AdminOrigin::ensure_origin(origin)?;
+        Ok(())
+    }
+
+    pub fn force_transfer(origin: Origin, amount: u64) -> Result<(), ()> {
+        Self::ensure_admin(origin)?;
+        Ok(())
+    }
+
+    pub fn heartbeat(origin: Origin) -> Result<(), ()> {
+        let unsigned = origin.clone();
+        ensure_none(unsigned)
+    }
+
+    pub fn vote(origin: Origin) -> Result<(), ()> {
+        match origin {
+            RawOrigin::Signed(_) => Ok(()),
+            _ => Err(()),
+        }
+    }
+
+    pub fn set_balance(_origin: Origin, amount: u64) -> Result<(), ()> {
+        Ok(())
+    }
+
+    pub fn kill(origin: Origin) -> Result<(), ()> {
+        let origin: Origin = RawOrigin::Root;
+        ensure_root(origin)
+    }
+
+    pub fn remark(origin: Origin) -> Result<(), ()> {
+        drop(origin);
+        Ok(())
+    }
+
+    pub fn force_remark(origin: Origin) -> Result<(), ()> {
+        Self::log(origin);
+        Ok(())
+    }
+
+    #[allow(substrace::unchecked_origin)]
+    pub fn sudo_remark(_origin: Origin) -> Result<(), ()> {
+        Ok(())
+    }
+
+    fn ensure_admin(origin: Origin) -> Result<(), ()> {
+        T::AdminOrigin::ensure_origin(origin)
+    }
+
+    fn log(_origin: Origin) {}
+}
+
+frame_support::pallet_calls! {
+    impl<T: Config> Pallet<T> {
+        type Origin = Origin;
+
+        fn transfer(amount: u64);
+        fn set_admin();
+        fn force_transfer(amount: u64);
+        fn heartbeat();
+        fn vote();
+        fn set_balance(amount: u64);
+        fn kill();
+        fn remark();
+        fn force_remark();
+        fn sudo_remark();
+    }
+}
+
+fn main() {}
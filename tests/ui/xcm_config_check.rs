@@ -0,0 +1,103 @@
+#![allow(substrace::panics)]
+#![allow(substrace::enable_singlepass_benchmarks)]
+// aux-build:frame-support.rs
+// aux-build:xcm-executor.rs
+// aux-build:xcm-builder.rs
+
+extern crate frame_support;
+extern crate xcm_builder;
+extern crate xcm_executor;
+
+use frame_support::traits::{Contains, Everything};
+use xcm_builder::{
+    AllowKnownQueryResponses, AllowTopLevelPaidExecutionFrom, AllowUnpaidExecutionFrom, TakeWeightCredit,
+    WithComputedOrigin,
+};
+
+pub struct ParentOrSiblings;
+
+impl Contains<u32> for ParentOrSiblings {
+    fn contains(location: &u32) -> bool {
+        *location == 0
+    }
+}
+
+pub struct AllowAll;
+
+impl Contains<u32> for AllowAll {
+    fn contains(_: &u32) -> bool {
+        true
+    }
+}
+
+frame_support::parameter_types! {
+    pub const AllowAnyOrigin: bool = true;
+    pub const AllowRelayOnly: bool = false;
+}
+
+// A filter toggled through `parameter_types!`.
+pub struct ConfiguredOrigins;
+
+impl Contains<u32> for ConfiguredOrigins {
+    fn contains(_: &u32) -> bool {
+        AllowAnyOrigin::get()
+    }
+}
+
+pub struct RelayOnly;
+
+impl Contains<u32> for RelayOnly {
+    fn contains(_: &u32) -> bool {
+        AllowRelayOnly::get()
+    }
+}
+
+pub type Anything = Everything;
+
+pub type Barrier = (
+    TakeWeightCredit,
+    AllowTopLevelPaidExecutionFrom<Anything>,
+    AllowUnpaidExecutionFrom<ParentOrSiblings>,
+);
+
+pub struct UnpaidConfig;
+
+impl xcm_executor::Config for UnpaidConfig {
+    type Barrier = AllowUnpaidExecutionFrom<Everything>;
+}
+
+pub struct TupleConfig;
+
+impl xcm_executor::Config for TupleConfig {
+    type Barrier = Barrier;
+}
+
+pub struct WrappedConfig;
+
+impl xcm_executor::Config for WrappedConfig {
+    type Barrier = WithComputedOrigin<(AllowKnownQueryResponses<()>, AllowUnpaidExecutionFrom<AllowAll>), (), ()>;
+}
+
+pub struct StrictConfig;
+
+impl xcm_executor::Config for StrictConfig {
+    type Barrier = (
+        TakeWeightCredit,
+        AllowTopLevelPaidExecutionFrom<ParentOrSiblings>,
+        AllowUnpaidExecutionFrom<ParentOrSiblings>,
+    );
+}
+
+pub struct ParameterConfig;
+
+impl xcm_executor::Config for ParameterConfig {
+    type Barrier = (TakeWeightCredit, AllowUnpaidExecutionFrom<ConfiguredOrigins>);
+}
+
+pub struct RelayOnlyConfig;
+
+impl xcm_executor::Config for RelayOnlyConfig {
+    type Barrier = (TakeWeightCredit, AllowUnpaidExecutionFrom<RelayOnly>);
+}
+
+fn main() {}